@@ -1,10 +1,35 @@
 // Configuration structs
 
+use crate::game::directions;
+
 #[derive(Clone)]
 pub struct ServerConfig {
     pub max_users: usize,
     pub database_url: String,
     pub telnet_port: u16,
+    pub max_attribute_rerolls: u32,
+    pub point_buy_pool: i64,
+    pub point_buy_min: i64,
+    pub point_buy_max: i64,
+    pub reset_interval_secs: u64,
+    pub tick_millis: u64,
+    pub travel_millis: u64, // pace of walkto and speedwalk steps
+    pub script_max_operations: u64,
+    pub direction_opposites: Vec<(String, String)>, // pairs of exit names that lead back to each other
+    pub world_dir: String, // where @export and @import keep world files
+    pub owner_username: String, // the account made on first boot
+    /// Password for that account; the MUD_OWNER_PASSWORD environment variable overrides it,
+    /// and if neither is set a random one is printed once.
+    pub owner_password: Option<String>,
+    pub max_login_attempts: u32, // per connection, before it is closed
+    pub login_backoff_millis: u64, // wait after the first failed login; doubles with each one after
+    pub login_lockout_failures: u32, // failed logins from one address or for one account before a lockout
+    pub login_lockout_secs: u64, // how long a lockout lasts, and how long failures are remembered
+    pub username_min_length: usize,
+    pub username_max_length: usize,
+    pub password_min_length: usize,
+    pub reserved_names: Vec<String>, // names nobody can register, on top of command words
+    pub blocked_name_words: Vec<String>, // names can't contain these anywhere
 }
 
 impl Default for ServerConfig {
@@ -13,6 +38,35 @@ impl Default for ServerConfig {
             max_users: 100,
             database_url: "sqlite://./mud.db".to_string(),
             telnet_port: 4000,
+            max_attribute_rerolls: 3,
+            point_buy_pool: 600,
+            point_buy_min: 70,
+            point_buy_max: 130,
+            reset_interval_secs: 600,
+            tick_millis: 3000,
+            travel_millis: 500,
+            script_max_operations: 10_000,
+            direction_opposites: directions::default_opposites(),
+            world_dir: "world".to_string(),
+            owner_username: "admin".to_string(),
+            owner_password: None,
+            max_login_attempts: 3,
+            login_backoff_millis: 500,
+            login_lockout_failures: 8,
+            login_lockout_secs: 900,
+            username_min_length: 3,
+            username_max_length: 16,
+            password_min_length: 8,
+            reserved_names: [
+                "admin", "administrator", "owner", "root", "sysop", "god", "immortal", "staff", "builder",
+                "helper", "moderator", "system", "server", "guest", "someone", "somebody", "nobody",
+                "everyone", "all", "self", "me", "you",
+            ]
+            .map(String::from)
+            .to_vec(),
+            blocked_name_words: ["fuck", "shit", "cunt", "bitch", "whore", "slut", "nazi", "hitler"]
+                .map(String::from)
+                .to_vec(),
         }
     }
 }
@@ -0,0 +1,116 @@
+// Area database operations
+
+use crate::models::{Area, GameObject};
+use sqlx::{SqliteExecutor, SqlitePool};
+
+pub async fn create_area<'e>(
+    db: impl SqliteExecutor<'e>,
+    name: &str,
+) -> Result<i64, String> {
+    let result = sqlx::query("INSERT INTO areas (name, created_at) VALUES (?, ?)")
+        .bind(name)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to create area: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn get_area<'e>(
+    db: impl SqliteExecutor<'e>,
+    area_id: i64,
+) -> Result<Area, String> {
+    sqlx::query_as("SELECT * FROM areas WHERE id = ?")
+        .bind(area_id)
+        .fetch_one(db)
+        .await
+        .map_err(|_| "Area not found".to_string())
+}
+
+pub async fn get_area_by_name<'e>(
+    db: impl SqliteExecutor<'e>,
+    name: &str,
+) -> Result<Option<Area>, String> {
+    sqlx::query_as("SELECT * FROM areas WHERE name = ? COLLATE NOCASE")
+        .bind(name)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to fetch area: {}", e))
+}
+
+pub async fn list_areas(
+    db: &SqlitePool,
+) -> Result<Vec<Area>, String> {
+    sqlx::query_as("SELECT * FROM areas ORDER BY name")
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch areas: {}", e))
+}
+
+/// Updates a single area column. `column` must come from a fixed list, never from user input.
+pub async fn update_area_field<'e, T>(
+    db: impl SqliteExecutor<'e>,
+    area_id: i64,
+    column: &str,
+    value: T,
+) -> Result<(), String>
+where
+    T: for<'q> sqlx::Encode<'q, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite> + Send,
+{
+    sqlx::query(&format!("UPDATE areas SET {} = ? WHERE id = ?", column))
+        .bind(value)
+        .bind(area_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update area: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn get_rooms_in_area(
+    db: &SqlitePool,
+    area_id: i64,
+) -> Result<Vec<GameObject>, String> {
+    sqlx::query_as("SELECT * FROM game_objects WHERE object_type = 'room' AND area_id = ? ORDER BY created_at, rowid")
+        .bind(area_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch rooms: {}", e))
+}
+
+pub async fn count_rooms(
+    db: &SqlitePool,
+    area_id: i64,
+) -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM game_objects WHERE object_type = 'room' AND area_id = ?")
+        .bind(area_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| format!("Failed to count rooms: {}", e))?;
+
+    Ok(count)
+}
+
+/// Moves a room into an area, taking the room's resets along.
+pub async fn set_room_area(
+    db: &SqlitePool,
+    room_id: &str,
+    area: &Area,
+) -> Result<(), String> {
+    sqlx::query("UPDATE game_objects SET area_id = ? WHERE id = ? AND object_type = 'room'")
+        .bind(area.id)
+        .bind(room_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update room: {}", e))?;
+
+    sqlx::query("UPDATE resets SET area = ? WHERE room_id = ?")
+        .bind(&area.name)
+        .bind(room_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update resets: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+// Channel subscription database operations
+
+use sqlx::SqlitePool;
+
+/// Whether a player has joined (Some(true)) or left (Some(false)) a channel; None means
+/// they never changed it and the channel's default applies.
+pub async fn get_subscription(
+    db: &SqlitePool,
+    player_id: &str,
+    channel: &str,
+) -> Result<Option<bool>, String> {
+    let row: Option<(i64,)> = sqlx::query_as(
+        "SELECT joined FROM channel_subscriptions WHERE player_id = ? AND channel = ?"
+    )
+    .bind(player_id)
+    .bind(channel)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| format!("Failed to fetch subscription: {}", e))?;
+
+    Ok(row.map(|(joined,)| joined == 1))
+}
+
+pub async fn set_subscription(
+    db: &SqlitePool,
+    player_id: &str,
+    channel: &str,
+    joined: bool,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO channel_subscriptions (player_id, channel, joined) VALUES (?, ?, ?)
+        ON CONFLICT (player_id, channel) DO UPDATE SET joined = excluded.joined
+        "#,
+    )
+    .bind(player_id)
+    .bind(channel)
+    .bind(joined as i64)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to update subscription: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+// Connection history for the `last` command
+
+use crate::models::Connection;
+use sqlx::SqlitePool;
+
+/// Records a login and returns its id, to close it off at logout.
+pub async fn log_connection(
+    db: &SqlitePool,
+    player_id: &str,
+    username: &str,
+    address: &str,
+) -> Result<i64, String> {
+    let result = sqlx::query(
+        "INSERT INTO connections (player_id, username, address, connected_at) VALUES (?, ?, ?, ?)"
+    )
+    .bind(player_id)
+    .bind(username)
+    .bind(address)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to log connection: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn log_disconnection(
+    db: &SqlitePool,
+    connection_id: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE connections SET disconnected_at = ? WHERE id = ?")
+        .bind(chrono::Utc::now().timestamp())
+        .bind(connection_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to log disconnection: {}", e))?;
+
+    Ok(())
+}
+
+/// The most recent connections, newest first, optionally for one player.
+pub async fn get_connections(
+    db: &SqlitePool,
+    player_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Connection>, String> {
+    let query = match player_id {
+        Some(_) => "SELECT * FROM connections WHERE player_id = ? ORDER BY connected_at DESC, id DESC LIMIT ?",
+        None => "SELECT * FROM connections ORDER BY connected_at DESC, id DESC LIMIT ?",
+    };
+    let mut query = sqlx::query_as(query);
+    if let Some(id) = player_id {
+        query = query.bind(id);
+    }
+    query
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch connections: {}", e))
+}
@@ -0,0 +1,103 @@
+// Room exit and door database operations
+
+use crate::models::RoomExit;
+use sqlx::{SqliteExecutor, SqlitePool};
+
+pub async fn get_exits(
+    db: &SqlitePool,
+    room_id: &str,
+) -> Result<Vec<RoomExit>, String> {
+    sqlx::query_as("SELECT * FROM room_exits WHERE room_id = ? ORDER BY rowid")
+        .bind(room_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to get exits: {}", e))
+}
+
+/// The exit on the other side of a door: the one in the destination room that leads back.
+pub async fn get_reverse_exit(
+    db: &SqlitePool,
+    exit: &RoomExit,
+) -> Result<Option<RoomExit>, String> {
+    sqlx::query_as("SELECT * FROM room_exits WHERE room_id = ? AND destination_id = ? LIMIT 1")
+        .bind(&exit.destination_id)
+        .bind(&exit.room_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to get exits: {}", e))
+}
+
+/// Adds an exit, replacing any the room already has in that direction.
+pub async fn create_exit<'e>(
+    db: impl SqliteExecutor<'e>,
+    room_id: &str,
+    direction: &str,
+    destination_id: &str,
+) -> Result<(), String> {
+    sqlx::query("INSERT OR REPLACE INTO room_exits (room_id, direction, destination_id) VALUES (?, ?, ?)")
+        .bind(room_id)
+        .bind(direction.to_lowercase())
+        .bind(destination_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to add exit: {}", e))?;
+
+    Ok(())
+}
+
+/// Writes the door fields of an exit.
+pub async fn update_door<'e>(
+    db: impl SqliteExecutor<'e>,
+    exit: &RoomExit,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        UPDATE room_exits
+        SET door_name = ?, is_closed = ?, is_locked = ?, key_id = ?, pick_difficulty = ?, is_hidden = ?
+        WHERE room_id = ? AND direction = ?
+        "#,
+    )
+    .bind(&exit.door_name)
+    .bind(exit.is_closed)
+    .bind(exit.is_locked)
+    .bind(&exit.key_id)
+    .bind(exit.pick_difficulty)
+    .bind(exit.is_hidden)
+    .bind(&exit.room_id)
+    .bind(&exit.direction)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to update door: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn delete_exit(
+    db: &SqlitePool,
+    room_id: &str,
+    direction: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM room_exits WHERE room_id = ? AND direction = ?")
+        .bind(room_id)
+        .bind(direction)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete exit: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes every exit leading out of or into a room.
+pub async fn delete_exits_touching(
+    db: &SqlitePool,
+    room_id: &str,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM room_exits WHERE room_id = ? OR destination_id = ?")
+        .bind(room_id)
+        .bind(room_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete exits: {}", e))?;
+
+    Ok(())
+}
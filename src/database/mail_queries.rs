@@ -0,0 +1,147 @@
+// Board post and mail database operations
+
+use crate::models::{BoardPost, Mail};
+use sqlx::SqlitePool;
+
+pub async fn get_board_posts(
+    db: &SqlitePool,
+    board_id: &str,
+) -> Result<Vec<BoardPost>, String> {
+    sqlx::query_as("SELECT * FROM board_posts WHERE board_id = ? ORDER BY id")
+        .bind(board_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch posts: {}", e))
+}
+
+pub async fn create_board_post(
+    db: &SqlitePool,
+    board_id: &str,
+    author_id: &str,
+    author_name: &str,
+    subject: &str,
+    body: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO board_posts (board_id, author_id, author_name, subject, body, posted_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(board_id)
+    .bind(author_id)
+    .bind(author_name)
+    .bind(subject)
+    .bind(body)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to post: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn delete_board_post(
+    db: &SqlitePool,
+    post_id: i64,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM board_posts WHERE id = ?")
+        .bind(post_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to remove post: {}", e))?;
+
+    Ok(())
+}
+
+/// Removes every post on a board, for when the board itself is destroyed.
+pub async fn delete_board(
+    db: &SqlitePool,
+    board_id: &str,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM board_posts WHERE board_id = ?")
+        .bind(board_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to remove posts: {}", e))?;
+
+    Ok(())
+}
+
+/// A player's mailbox, oldest first.
+pub async fn get_mailbox(
+    db: &SqlitePool,
+    recipient_id: &str,
+) -> Result<Vec<Mail>, String> {
+    sqlx::query_as("SELECT * FROM mail WHERE recipient_id = ? ORDER BY id")
+        .bind(recipient_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch mail: {}", e))
+}
+
+pub async fn count_unread_mail(
+    db: &SqlitePool,
+    recipient_id: &str,
+) -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM mail WHERE recipient_id = ? AND is_read = 0")
+        .bind(recipient_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| format!("Failed to count mail: {}", e))?;
+
+    Ok(count)
+}
+
+pub async fn send_mail(
+    db: &SqlitePool,
+    recipient_id: &str,
+    sender_id: &str,
+    sender_name: &str,
+    subject: &str,
+    body: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO mail (recipient_id, sender_id, sender_name, subject, body, sent_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(recipient_id)
+    .bind(sender_id)
+    .bind(sender_name)
+    .bind(subject)
+    .bind(body)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to send mail: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn mark_mail_read(
+    db: &SqlitePool,
+    mail_id: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE mail SET is_read = 1 WHERE id = ?")
+        .bind(mail_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update mail: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn delete_mail(
+    db: &SqlitePool,
+    mail_id: i64,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM mail WHERE id = ?")
+        .bind(mail_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete mail: {}", e))?;
+
+    Ok(())
+}
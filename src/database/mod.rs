@@ -3,5 +3,17 @@
 pub mod schema;
 pub mod player_queries;
 pub mod object_queries;
+pub mod npc_queries;
+pub mod reset_queries;
+pub mod script_queries;
+pub mod exit_queries;
+pub mod area_queries;
+pub mod channel_queries;
+pub mod tell_queries;
+pub mod social_queries;
+pub mod mail_queries;
+pub mod connection_queries;
+pub mod permission_queries;
+pub mod sanction_queries;
 
 pub use schema::initialize_database;
@@ -0,0 +1,233 @@
+// NPC database operations
+
+use crate::models::{Npc, NpcPrototype};
+use sqlx::{SqliteExecutor, SqlitePool};
+
+const NPC_SELECT: &str = r#"
+    SELECT n.id, n.prototype_id, n.room_id, n.current_health, n.home_room_id, n.target_id,
+           p.name, p.description, p.level, p.dexterity, p.strength, p.max_health,
+           p.physical_defense, p.physical_armor, p.loot_table, p.dialogue, p.behaviors
+    FROM npcs n
+    JOIN npc_prototypes p ON p.id = n.prototype_id
+"#;
+
+pub async fn create_prototype(
+    db: &SqlitePool,
+    name: &str,
+    description: &str,
+) -> Result<i64, String> {
+    let result = sqlx::query(
+        "INSERT INTO npc_prototypes (name, description, created_at) VALUES (?, ?, ?)"
+    )
+    .bind(name)
+    .bind(description)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to create NPC prototype: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Inserts a complete prototype (as loaded from a world file) and returns its new id.
+pub async fn insert_prototype<'e>(
+    db: impl SqliteExecutor<'e>,
+    prototype: &NpcPrototype,
+) -> Result<i64, String> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO npc_prototypes (name, description, level, dexterity, strength, vitality, perception, willpower, charisma,
+                                    max_health, physical_defense, physical_armor, loot_table, dialogue, behaviors, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&prototype.name)
+    .bind(&prototype.description)
+    .bind(prototype.level)
+    .bind(prototype.dexterity)
+    .bind(prototype.strength)
+    .bind(prototype.vitality)
+    .bind(prototype.perception)
+    .bind(prototype.willpower)
+    .bind(prototype.charisma)
+    .bind(prototype.max_health)
+    .bind(prototype.physical_defense)
+    .bind(prototype.physical_armor)
+    .bind(&prototype.loot_table)
+    .bind(&prototype.dialogue)
+    .bind(&prototype.behaviors)
+    .bind(prototype.created_at)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to create NPC prototype: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn get_prototype(
+    db: &SqlitePool,
+    prototype_id: i64,
+) -> Result<NpcPrototype, String> {
+    sqlx::query_as("SELECT * FROM npc_prototypes WHERE id = ?")
+        .bind(prototype_id)
+        .fetch_one(db)
+        .await
+        .map_err(|_| format!("NPC prototype #{} not found", prototype_id))
+}
+
+pub async fn list_prototypes(
+    db: &SqlitePool,
+) -> Result<Vec<NpcPrototype>, String> {
+    sqlx::query_as("SELECT * FROM npc_prototypes ORDER BY id")
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch NPC prototypes: {}", e))
+}
+
+/// Updates a single prototype column. `column` must come from a fixed list, never from user input.
+pub async fn update_prototype_field<T>(
+    db: &SqlitePool,
+    prototype_id: i64,
+    column: &str,
+    value: T,
+) -> Result<(), String>
+where
+    T: for<'q> sqlx::Encode<'q, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite> + Send,
+{
+    sqlx::query(&format!("UPDATE npc_prototypes SET {} = ? WHERE id = ?", column))
+        .bind(value)
+        .bind(prototype_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update NPC prototype: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn spawn_npc(
+    db: &SqlitePool,
+    id: &str,
+    prototype: &NpcPrototype,
+    room_id: &str,
+    reset_id: Option<i64>,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO npcs (id, prototype_id, room_id, home_room_id, current_health, reset_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(id)
+    .bind(prototype.id)
+    .bind(room_id)
+    .bind(room_id)
+    .bind(prototype.max_health)
+    .bind(reset_id)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to spawn NPC: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn get_npc(
+    db: &SqlitePool,
+    npc_id: &str,
+) -> Result<Npc, String> {
+    sqlx::query_as(&format!("{} WHERE n.id = ?", NPC_SELECT))
+        .bind(npc_id)
+        .fetch_one(db)
+        .await
+        .map_err(|_| "NPC not found".to_string())
+}
+
+pub async fn get_npcs_in_room(
+    db: &SqlitePool,
+    room_id: &str,
+) -> Result<Vec<Npc>, String> {
+    sqlx::query_as(&format!("{} WHERE n.room_id = ? ORDER BY n.created_at", NPC_SELECT))
+        .bind(room_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch NPCs: {}", e))
+}
+
+pub async fn get_all_npcs(
+    db: &SqlitePool,
+) -> Result<Vec<Npc>, String> {
+    sqlx::query_as(&format!("{} ORDER BY n.created_at", NPC_SELECT))
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch NPCs: {}", e))
+}
+
+pub async fn move_npc(
+    db: &SqlitePool,
+    npc_id: &str,
+    room_id: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE npcs SET room_id = ? WHERE id = ?")
+        .bind(room_id)
+        .bind(npc_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to move NPC: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn set_npc_target(
+    db: &SqlitePool,
+    npc_id: &str,
+    target_id: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE npcs SET target_id = ? WHERE id = ?")
+        .bind(target_id)
+        .bind(npc_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update NPC: {}", e))?;
+
+    Ok(())
+}
+
+/// Makes every NPC forget a player, e.g. once the player has been killed.
+pub async fn clear_targets_on(
+    db: &SqlitePool,
+    player_id: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE npcs SET target_id = NULL WHERE target_id = ?")
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update NPCs: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_npc_health(
+    db: &SqlitePool,
+    npc_id: &str,
+    health: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE npcs SET current_health = ? WHERE id = ?")
+        .bind(health)
+        .bind(npc_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update NPC: {}", e))?;
+
+    Ok(())
+}
+
+/// Returns false if the NPC was already gone.
+pub async fn delete_npc(
+    db: &SqlitePool,
+    npc_id: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM npcs WHERE id = ?")
+        .bind(npc_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete NPC: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
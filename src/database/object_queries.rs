@@ -1,16 +1,16 @@
 // Game object database operations
 
 use crate::models::GameObject;
-use sqlx::SqlitePool;
+use sqlx::{SqliteExecutor, SqlitePool};
 
-pub async fn create_object(
-    db: &SqlitePool,
+pub async fn create_object<'e>(
+    db: impl SqliteExecutor<'e>,
     object: &GameObject,
 ) -> Result<(), String> {
     sqlx::query(
         r#"
-        INSERT INTO game_objects (id, name, description, object_type, container_id, properties, created_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO game_objects (id, name, description, object_type, container_id, properties, created_at, area_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&object.id)
@@ -20,6 +20,7 @@ pub async fn create_object(
     .bind(&object.container_id)
     .bind(&object.properties)
     .bind(object.created_at)
+    .bind(object.area_id)
     .execute(db)
     .await
     .map_err(|e| format!("Failed to create object: {}", e))?;
@@ -38,6 +39,17 @@ pub async fn get_room(
         .map_err(|_| "Room not found".to_string())
 }
 
+pub async fn get_object(
+    db: &SqlitePool,
+    object_id: &str,
+) -> Result<GameObject, String> {
+    sqlx::query_as("SELECT * FROM game_objects WHERE id = ?")
+        .bind(object_id)
+        .fetch_one(db)
+        .await
+        .map_err(|_| "Object not found".to_string())
+}
+
 pub async fn get_objects_in_container(
     db: &SqlitePool,
     container_id: &str,
@@ -49,6 +61,15 @@ pub async fn get_objects_in_container(
         .map_err(|e| format!("Failed to fetch objects: {}", e))
 }
 
+pub async fn get_all_objects(
+    db: &SqlitePool,
+) -> Result<Vec<GameObject>, String> {
+    sqlx::query_as("SELECT * FROM game_objects ORDER BY created_at, rowid")
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch objects: {}", e))
+}
+
 pub async fn delete_object(
     db: &SqlitePool,
     object_id: &str,
@@ -61,3 +82,134 @@ pub async fn delete_object(
 
     Ok(())
 }
+
+pub async fn move_object(
+    db: &SqlitePool,
+    object_id: &str,
+    container_id: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE game_objects SET container_id = ? WHERE id = ?")
+        .bind(container_id)
+        .bind(object_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to move object: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_object_properties(
+    db: &SqlitePool,
+    object_id: &str,
+    properties: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE game_objects SET properties = ? WHERE id = ?")
+        .bind(properties)
+        .bind(object_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update object: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_object_name(
+    db: &SqlitePool,
+    object_id: &str,
+    name: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE game_objects SET name = ? WHERE id = ?")
+        .bind(name)
+        .bind(object_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update object: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_object_description(
+    db: &SqlitePool,
+    object_id: &str,
+    description: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE game_objects SET description = ? WHERE id = ?")
+        .bind(description)
+        .bind(object_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update object: {}", e))?;
+
+    Ok(())
+}
+
+/// Case-insensitive substring search over object names, optionally limited to one type.
+pub async fn find_objects(
+    db: &SqlitePool,
+    pattern: &str,
+    object_type: Option<&str>,
+) -> Result<Vec<GameObject>, String> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM game_objects
+        WHERE name LIKE '%' || ? || '%' AND (? IS NULL OR object_type = ?)
+        ORDER BY object_type, name
+        LIMIT 50
+        "#,
+    )
+    .bind(pattern)
+    .bind(object_type)
+    .bind(object_type)
+    .fetch_all(db)
+    .await
+    .map_err(|e| format!("Failed to fetch objects: {}", e))
+}
+
+/// The room carrying a `landmark` property with this name, if any.
+pub async fn get_landmark(
+    db: &SqlitePool,
+    landmark: &str,
+) -> Result<Option<GameObject>, String> {
+    sqlx::query_as(
+        "SELECT * FROM game_objects WHERE object_type = 'room' AND json_extract(properties, '$.landmark') = ? COLLATE NOCASE",
+    )
+    .bind(landmark)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| format!("Failed to fetch landmark: {}", e))
+}
+
+pub async fn get_landmarks(
+    db: &SqlitePool,
+) -> Result<Vec<(String, GameObject)>, String> {
+    let rooms: Vec<GameObject> = sqlx::query_as(
+        r#"
+        SELECT * FROM game_objects
+        WHERE object_type = 'room' AND json_extract(properties, '$.landmark') IS NOT NULL
+        ORDER BY json_extract(properties, '$.landmark')
+        "#,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| format!("Failed to fetch landmarks: {}", e))?;
+
+    Ok(rooms
+        .into_iter()
+        .map(|room| {
+            let properties: serde_json::Value = serde_json::from_str(&room.properties).unwrap_or_default();
+            let name = properties["landmark"].as_str().unwrap_or_default().to_string();
+            (name, room)
+        })
+        .collect())
+}
+
+pub async fn get_rooms_named(
+    db: &SqlitePool,
+    name: &str,
+) -> Result<Vec<GameObject>, String> {
+    sqlx::query_as("SELECT * FROM game_objects WHERE object_type = 'room' AND name = ? COLLATE NOCASE")
+        .bind(name)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch rooms: {}", e))
+}
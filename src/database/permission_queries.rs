@@ -0,0 +1,125 @@
+// Roles, per-command permissions and the audit log
+
+use crate::models::AuditEntry;
+use sqlx::SqlitePool;
+
+pub async fn update_player_role(
+    db: &SqlitePool,
+    player_id: &str,
+    role: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET role = ? WHERE id = ?")
+        .bind(role)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update role: {}", e))?;
+
+    Ok(())
+}
+
+/// The role a command has been changed to need with @perms, if any.
+pub async fn get_command_role(
+    db: &SqlitePool,
+    command: &str,
+) -> Result<Option<String>, String> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT role FROM command_roles WHERE command = ?")
+        .bind(command)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to fetch command role: {}", e))?;
+
+    Ok(row.map(|(role,)| role))
+}
+
+pub async fn set_command_role(
+    db: &SqlitePool,
+    command: &str,
+    role: &str,
+) -> Result<(), String> {
+    sqlx::query("INSERT OR REPLACE INTO command_roles (command, role) VALUES (?, ?)")
+        .bind(command)
+        .bind(role)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to set command role: {}", e))?;
+
+    Ok(())
+}
+
+/// Commands granted to a player on top of their role.
+pub async fn get_grants(
+    db: &SqlitePool,
+    player_id: &str,
+) -> Result<Vec<String>, String> {
+    let rows: Vec<(String,)> = sqlx::query_as("SELECT command FROM command_grants WHERE player_id = ? ORDER BY command")
+        .bind(player_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch grants: {}", e))?;
+
+    Ok(rows.into_iter().map(|(command,)| command).collect())
+}
+
+pub async fn set_grant(
+    db: &SqlitePool,
+    player_id: &str,
+    command: &str,
+    granted: bool,
+) -> Result<(), String> {
+    let query = match granted {
+        true => "INSERT OR IGNORE INTO command_grants (player_id, command) VALUES (?, ?)",
+        false => "DELETE FROM command_grants WHERE player_id = ? AND command = ?",
+    };
+    sqlx::query(query)
+        .bind(player_id)
+        .bind(command)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update grants: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn log_action(
+    db: &SqlitePool,
+    player_id: &str,
+    username: &str,
+    room_id: &str,
+    command: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO audit_log (player_id, username, room_id, command, created_at) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(player_id)
+    .bind(username)
+    .bind(room_id)
+    .bind(command)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to write audit log: {}", e))?;
+
+    Ok(())
+}
+
+/// The most recent audit entries, newest first, optionally for one player.
+pub async fn get_audit_log(
+    db: &SqlitePool,
+    player_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<AuditEntry>, String> {
+    let query = match player_id {
+        Some(_) => "SELECT * FROM audit_log WHERE player_id = ? ORDER BY id DESC LIMIT ?",
+        None => "SELECT * FROM audit_log ORDER BY id DESC LIMIT ?",
+    };
+    let mut query = sqlx::query_as(query);
+    if let Some(id) = player_id {
+        query = query.bind(id);
+    }
+    query
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch audit log: {}", e))
+}
@@ -9,22 +9,26 @@ pub async fn create_player(
 ) -> Result<(), String> {
     sqlx::query(
         r#"
-        INSERT INTO players (id, username, password_hash, current_location, created_at, dexterity, strength, vitality, perception, willpower, charisma)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO players (id, username, password_hash, current_location, role, created_at, dexterity, strength, vitality, perception, willpower, charisma, description, gender, level, gold)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&player.id)
     .bind(&player.username)
     .bind(&player.password_hash)
     .bind(&player.current_location)
-    .bind(&player.is_admin)
+    .bind(&player.role)
     .bind(player.created_at)
-    .bind(&player.dexterity)
-    .bind(&player.strength)
-    .bind(&player.vitality)
-    .bind(&player.perception)
-    .bind(&player.willpower)
-    .bind(&player.charisma)
+    .bind(player.dexterity)
+    .bind(player.strength)
+    .bind(player.vitality)
+    .bind(player.perception)
+    .bind(player.willpower)
+    .bind(player.charisma)
+    .bind(&player.description)
+    .bind(&player.gender)
+    .bind(player.level)
+    .bind(player.gold)
     .execute(db)
     .await
     .map_err(|e| format!("Failed to create player: {}", e))?;
@@ -43,6 +47,18 @@ pub async fn get_player_by_username(
         .map_err(|e| format!("Database error: {}", e))
 }
 
+/// Like `get_player_by_username`, but ignoring case, for commands that name other players.
+pub async fn find_player_by_username(
+    db: &SqlitePool,
+    username: &str,
+) -> Result<Option<Player>, String> {
+    sqlx::query_as("SELECT * FROM players WHERE username = ? COLLATE NOCASE ORDER BY created_at LIMIT 1")
+        .bind(username)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))
+}
+
 pub async fn get_player_by_id(
     db: &SqlitePool,
     player_id: &str,
@@ -79,3 +95,169 @@ pub async fn update_player_location(
 
     Ok(())
 }
+
+pub async fn update_player_health(
+    db: &SqlitePool,
+    player_id: &str,
+    health: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET current_health = ? WHERE id = ?")
+        .bind(health)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update health: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_player_gold(
+    db: &SqlitePool,
+    player_id: &str,
+    gold: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET gold = ? WHERE id = ?")
+        .bind(gold)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update gold: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_last_login(
+    db: &SqlitePool,
+    player_id: &str,
+    timestamp: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET last_login = ? WHERE id = ?")
+        .bind(timestamp)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update last login: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_player_title(
+    db: &SqlitePool,
+    player_id: &str,
+    title: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET title = ? WHERE id = ?")
+        .bind(title)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update title: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn update_player_plan(
+    db: &SqlitePool,
+    player_id: &str,
+    plan: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET plan = ? WHERE id = ?")
+        .bind(plan)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update plan: {}", e))?;
+
+    Ok(())
+}
+
+/// Creates the owner account made on first boot, before any character exists for it.
+pub async fn create_owner(
+    db: &SqlitePool,
+    id: &str,
+    username: &str,
+    password_hash: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO players (id, username, password_hash, current_location, role, created_at, must_change_password)
+        VALUES (?, ?, ?, 'room_start', 'owner', ?, 1)
+        "#,
+    )
+    .bind(id)
+    .bind(username)
+    .bind(password_hash)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to create owner: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn count_players_with_role(
+    db: &SqlitePool,
+    role: &str,
+) -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM players WHERE role = ?")
+        .bind(role)
+        .fetch_one(db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(count)
+}
+
+/// Sets a new password hash; `must_change` asks for another new one at next login.
+pub async fn update_password(
+    db: &SqlitePool,
+    player_id: &str,
+    password_hash: &str,
+    must_change: bool,
+) -> Result<(), String> {
+    sqlx::query("UPDATE players SET password_hash = ?, must_change_password = ? WHERE id = ?")
+        .bind(password_hash)
+        .bind(must_change as i64)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update password: {}", e))?;
+
+    Ok(())
+}
+
+/// Player columns scripts are allowed to change.
+pub const SCRIPTABLE_STATS: [&str; 12] = [
+    "current_health",
+    "max_health",
+    "current_stamina",
+    "max_stamina",
+    "dexterity",
+    "strength",
+    "vitality",
+    "perception",
+    "willpower",
+    "charisma",
+    "level",
+    "gold",
+];
+
+pub async fn update_player_stat(
+    db: &SqlitePool,
+    player_id: &str,
+    stat: &str,
+    value: i64,
+) -> Result<(), String> {
+    if !SCRIPTABLE_STATS.contains(&stat) {
+        return Err(format!("Unknown stat '{}'", stat));
+    }
+
+    sqlx::query(&format!("UPDATE players SET {} = ? WHERE id = ?", stat))
+        .bind(value)
+        .bind(player_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update {}: {}", stat, e))?;
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+// Area reset database operations
+
+use crate::models::Reset;
+use sqlx::{SqliteExecutor, SqlitePool};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_reset<'e>(
+    db: impl SqliteExecutor<'e>,
+    area: &str,
+    room_id: &str,
+    container_id: Option<&str>,
+    reset_type: &str,
+    npc_prototype_id: Option<i64>,
+    name: &str,
+    description: &str,
+    object_type: &str,
+    properties: &str,
+    max_count: i64,
+) -> Result<i64, String> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO resets (area, room_id, container_id, reset_type, npc_prototype_id, name, description, object_type, properties, max_count, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(area)
+    .bind(room_id)
+    .bind(container_id)
+    .bind(reset_type)
+    .bind(npc_prototype_id)
+    .bind(name)
+    .bind(description)
+    .bind(object_type)
+    .bind(properties)
+    .bind(max_count)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to create reset: {}", e))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn get_resets(
+    db: &SqlitePool,
+    area: Option<&str>,
+) -> Result<Vec<Reset>, String> {
+    match area {
+        Some(area) => sqlx::query_as("SELECT * FROM resets WHERE area = ? ORDER BY id")
+            .bind(area)
+            .fetch_all(db)
+            .await,
+        None => sqlx::query_as("SELECT * FROM resets ORDER BY id")
+            .fetch_all(db)
+            .await,
+    }
+    .map_err(|e| format!("Failed to fetch resets: {}", e))
+}
+
+pub async fn get_reset(
+    db: &SqlitePool,
+    reset_id: i64,
+) -> Result<Option<Reset>, String> {
+    sqlx::query_as("SELECT * FROM resets WHERE id = ?")
+        .bind(reset_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to fetch reset: {}", e))
+}
+
+pub async fn get_resets_in_room(
+    db: &SqlitePool,
+    room_id: &str,
+) -> Result<Vec<Reset>, String> {
+    sqlx::query_as("SELECT * FROM resets WHERE room_id = ? ORDER BY id")
+        .bind(room_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch resets: {}", e))
+}
+
+pub async fn delete_reset(
+    db: &SqlitePool,
+    reset_id: i64,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM resets WHERE id = ?")
+        .bind(reset_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete reset: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes every reset that spawns into a room or container.
+pub async fn delete_resets_for(
+    db: &SqlitePool,
+    object_id: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query("DELETE FROM resets WHERE room_id = ? OR container_id = ?")
+        .bind(object_id)
+        .bind(object_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete resets: {}", e))?;
+
+    Ok(result.rows_affected())
+}
+
+/// Counts objects spawned by a reset that are still where the reset puts them.
+pub async fn count_reset_objects(
+    db: &SqlitePool,
+    reset: &Reset,
+) -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM game_objects WHERE container_id = ? AND json_extract(properties, '$.reset_id') = ?"
+    )
+    .bind(reset.target_id())
+    .bind(reset.id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| format!("Failed to count objects: {}", e))?;
+
+    Ok(count)
+}
+
+/// Counts living NPCs spawned by a reset, wherever they have wandered.
+pub async fn count_reset_npcs(
+    db: &SqlitePool,
+    reset_id: i64,
+) -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM npcs WHERE reset_id = ?")
+        .bind(reset_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| format!("Failed to count NPCs: {}", e))?;
+
+    Ok(count)
+}
+
+/// Every area name that has at least one reset.
+pub async fn get_reset_areas(
+    db: &SqlitePool,
+) -> Result<Vec<String>, String> {
+    let rows: Vec<(String,)> = sqlx::query_as("SELECT DISTINCT area FROM resets ORDER BY area")
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch resets: {}", e))?;
+
+    Ok(rows.into_iter().map(|(area,)| area).collect())
+}
@@ -0,0 +1,84 @@
+// Bans, freezes and silences
+
+use crate::models::Sanction;
+use sqlx::SqlitePool;
+
+/// Adds a sanction, replacing any of the same kind already on the target.
+pub async fn add_sanction(
+    db: &SqlitePool,
+    kind: &str,
+    target: &str,
+    target_name: &str,
+    reason: &str,
+    issued_by: &str,
+    expires_at: Option<i64>,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT OR REPLACE INTO sanctions (kind, target, target_name, reason, issued_by, created_at, expires_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(kind)
+    .bind(target)
+    .bind(target_name)
+    .bind(reason)
+    .bind(issued_by)
+    .bind(chrono::Utc::now().timestamp())
+    .bind(expires_at)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to save sanction: {}", e))?;
+
+    Ok(())
+}
+
+/// Lifts a sanction. Returns false if there wasn't one.
+pub async fn remove_sanction(
+    db: &SqlitePool,
+    kind: &str,
+    target: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM sanctions WHERE kind = ? AND target = ?")
+        .bind(kind)
+        .bind(target)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to lift sanction: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Sanctions that haven't expired, optionally of one kind, oldest first.
+pub async fn get_active_sanctions(
+    db: &SqlitePool,
+    kind: Option<&str>,
+) -> Result<Vec<Sanction>, String> {
+    let query = match kind {
+        Some(_) => "SELECT * FROM sanctions WHERE (expires_at IS NULL OR expires_at > ?) AND kind = ? ORDER BY id",
+        None => "SELECT * FROM sanctions WHERE (expires_at IS NULL OR expires_at > ?) ORDER BY kind, id",
+    };
+    let mut query = sqlx::query_as(query).bind(chrono::Utc::now().timestamp());
+    if let Some(kind) = kind {
+        query = query.bind(kind);
+    }
+    query
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch sanctions: {}", e))
+}
+
+/// The sanction of this kind on a player, if it hasn't expired.
+pub async fn get_active_sanction(
+    db: &SqlitePool,
+    kind: &str,
+    target: &str,
+) -> Result<Option<Sanction>, String> {
+    sqlx::query_as("SELECT * FROM sanctions WHERE kind = ? AND target = ? AND (expires_at IS NULL OR expires_at > ?)")
+        .bind(kind)
+        .bind(target)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to fetch sanction: {}", e))
+}
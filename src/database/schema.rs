@@ -1,5 +1,6 @@
 // Database schema creation
 
+use crate::models::DEFAULT_SOCIALS;
 use sqlx::SqlitePool;
 // use crate::database::player_queries;
 
@@ -36,7 +37,28 @@ pub async fn initialize_database(db: &SqlitePool) -> Result<(), Box<dyn std::err
     .execute(db)
     .await?;
 
+    // Columns added after the original players table shipped
+    add_column_if_missing(db, "players", "description", "TEXT NOT NULL DEFAULT ''").await?;
+    add_column_if_missing(db, "players", "gender", "TEXT NOT NULL DEFAULT 'neutral'").await?;
+    add_column_if_missing(db, "players", "level", "INTEGER NOT NULL DEFAULT 1").await?;
+    add_column_if_missing(db, "players", "gold", "INTEGER NOT NULL DEFAULT 100").await?;
+    add_column_if_missing(db, "players", "title", "TEXT NOT NULL DEFAULT ''").await?;
+    add_column_if_missing(db, "players", "plan", "TEXT NOT NULL DEFAULT ''").await?;
+    add_column_if_missing(db, "players", "last_login", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(db, "players", "role", "TEXT NOT NULL DEFAULT 'player'").await?;
+    add_column_if_missing(db, "players", "must_change_password", "INTEGER NOT NULL DEFAULT 0").await?;
+    migrate_admin_flag(db).await?;
+    // Names are unique whatever their capitalization; older databases may already hold
+    // clashing names, which are left alone
+    if let Err(e) = sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS players_username_nocase ON players (username COLLATE NOCASE)")
+        .execute(db)
+        .await
+    {
+        eprintln!("Couldn't make usernames unique regardless of case: {}", e);
+    }
+
     // Create game_objects table
+    // container_id can be a room, another object or a player (inventory), so it has no foreign key
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS game_objects (
@@ -46,19 +68,51 @@ pub async fn initialize_database(db: &SqlitePool) -> Result<(), Box<dyn std::err
             object_type TEXT NOT NULL,
             container_id TEXT,
             properties TEXT NOT NULL,
-            created_at INTEGER NOT NULL,
-            FOREIGN KEY (container_id) REFERENCES game_objects(id)
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    drop_container_foreign_key(db).await?;
+    add_column_if_missing(db, "game_objects", "area_id", "INTEGER").await?;
+
+    // Areas group rooms for resets, flags and builder permissions
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS areas (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            builders TEXT NOT NULL DEFAULT '[]',
+            min_level INTEGER NOT NULL DEFAULT 1,
+            max_level INTEGER NOT NULL DEFAULT 100,
+            reset_interval_secs INTEGER NOT NULL DEFAULT 0,
+            is_safe INTEGER NOT NULL DEFAULT 0,
+            no_recall INTEGER NOT NULL DEFAULT 0,
+            pk_allowed INTEGER NOT NULL DEFAULT 0,
+            vnum_low INTEGER NOT NULL DEFAULT 0,
+            vnum_high INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
         )
         "#,
     )
     .execute(db)
     .await?;
+    sqlx::query("INSERT OR IGNORE INTO areas (name, created_at) VALUES ('default', ?)")
+        .bind(chrono::Utc::now().timestamp())
+        .execute(db)
+        .await?;
 
     // Create starting room if it doesn't exist
     create_starting_room(db).await?;
 
-    // Create admin if they don't exist
-    create_starting_admin(db).await?;
+    // Rooms from before areas existed go into the default area
+    sqlx::query(
+        "UPDATE game_objects SET area_id = (SELECT id FROM areas WHERE name = 'default') \
+         WHERE object_type = 'room' AND area_id IS NULL"
+    )
+    .execute(db)
+    .await?;
 
     // create exits table for room exits
     sqlx::query(
@@ -75,6 +129,393 @@ pub async fn initialize_database(db: &SqlitePool) -> Result<(), Box<dyn std::err
     )
     .execute(db)
     .await?;    
+    add_column_if_missing(db, "room_exits", "door_name", "TEXT").await?;
+    add_column_if_missing(db, "room_exits", "is_closed", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(db, "room_exits", "is_locked", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(db, "room_exits", "key_id", "TEXT").await?;
+    add_column_if_missing(db, "room_exits", "pick_difficulty", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(db, "room_exits", "is_hidden", "INTEGER NOT NULL DEFAULT 0").await?;
+
+    // NPC prototypes, edited by builders with @mcreate/@mset
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS npc_prototypes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT NOT NULL,
+            level INTEGER NOT NULL DEFAULT 1,
+            dexterity INTEGER NOT NULL DEFAULT 100,
+            strength INTEGER NOT NULL DEFAULT 100,
+            vitality INTEGER NOT NULL DEFAULT 100,
+            perception INTEGER NOT NULL DEFAULT 100,
+            willpower INTEGER NOT NULL DEFAULT 100,
+            charisma INTEGER NOT NULL DEFAULT 100,
+            max_health INTEGER NOT NULL DEFAULT 100,
+            physical_defense INTEGER NOT NULL DEFAULT 100,
+            physical_armor INTEGER NOT NULL DEFAULT 0,
+            loot_table TEXT NOT NULL DEFAULT '[]',
+            dialogue TEXT NOT NULL DEFAULT '{}',
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // NPC instances placed in rooms
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS npcs (
+            id TEXT PRIMARY KEY,
+            prototype_id INTEGER NOT NULL,
+            room_id TEXT NOT NULL,
+            current_health INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (prototype_id) REFERENCES npc_prototypes(id),
+            FOREIGN KEY (room_id) REFERENCES game_objects(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    add_column_if_missing(db, "npcs", "reset_id", "INTEGER").await?;
+    add_column_if_missing(db, "npcs", "home_room_id", "TEXT").await?;
+    add_column_if_missing(db, "npcs", "target_id", "TEXT").await?;
+    add_column_if_missing(db, "npc_prototypes", "behaviors", "TEXT NOT NULL DEFAULT '{}'").await?;
+
+    // Area resets: which objects and NPCs belong where, and how many of each
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS resets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            area TEXT NOT NULL DEFAULT 'default',
+            room_id TEXT NOT NULL,
+            container_id TEXT,
+            reset_type TEXT NOT NULL,
+            npc_prototype_id INTEGER,
+            name TEXT NOT NULL DEFAULT '',
+            description TEXT NOT NULL DEFAULT '',
+            object_type TEXT NOT NULL DEFAULT 'item',
+            properties TEXT NOT NULL DEFAULT '{}',
+            max_count INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (room_id) REFERENCES game_objects(id),
+            FOREIGN KEY (npc_prototype_id) REFERENCES npc_prototypes(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Event scripts for objects, rooms and NPC prototypes
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS scripts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            owner_type TEXT NOT NULL,
+            owner_id TEXT NOT NULL,
+            event TEXT NOT NULL,
+            code TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            UNIQUE (owner_type, owner_id, event)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Channels a player has joined or left; channels they never touched use their default
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS channel_subscriptions (
+            player_id TEXT NOT NULL,
+            channel TEXT NOT NULL,
+            joined INTEGER NOT NULL,
+            PRIMARY KEY (player_id, channel),
+            FOREIGN KEY (player_id) REFERENCES players(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Players whose tells someone doesn't want to see
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS ignores (
+            player_id TEXT NOT NULL,
+            ignored_id TEXT NOT NULL,
+            PRIMARY KEY (player_id, ignored_id),
+            FOREIGN KEY (player_id) REFERENCES players(id),
+            FOREIGN KEY (ignored_id) REFERENCES players(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Tells sent to players who were offline, shown at their next login
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS offline_tells (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recipient_id TEXT NOT NULL,
+            sender_id TEXT NOT NULL,
+            sender_name TEXT NOT NULL,
+            message TEXT NOT NULL,
+            sent_at INTEGER NOT NULL,
+            FOREIGN KEY (recipient_id) REFERENCES players(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Socials (smile, bow, ...); the defaults are only added to a new, empty table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS socials (
+            name TEXT PRIMARY KEY,
+            no_target_actor TEXT NOT NULL DEFAULT '',
+            no_target_room TEXT NOT NULL DEFAULT '',
+            actor TEXT NOT NULL DEFAULT '',
+            target TEXT NOT NULL DEFAULT '',
+            room TEXT NOT NULL DEFAULT '',
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    seed_socials(db).await?;
+
+    // Messages pinned to board objects
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS board_posts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            board_id TEXT NOT NULL,
+            author_id TEXT NOT NULL,
+            author_name TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            body TEXT NOT NULL,
+            posted_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Player mail
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS mail (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recipient_id TEXT NOT NULL,
+            sender_id TEXT NOT NULL,
+            sender_name TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            body TEXT NOT NULL,
+            sent_at INTEGER NOT NULL,
+            is_read INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (recipient_id) REFERENCES players(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // One row per login, for the admin `last` command
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS connections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            player_id TEXT NOT NULL,
+            username TEXT NOT NULL,
+            address TEXT NOT NULL,
+            connected_at INTEGER NOT NULL,
+            disconnected_at INTEGER,
+            FOREIGN KEY (player_id) REFERENCES players(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Roles commands have been changed to need with @perms, over the defaults in COMMAND_ROLES
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS command_roles (
+            command TEXT PRIMARY KEY,
+            role TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Single commands granted to a player whatever their role
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS command_grants (
+            player_id TEXT NOT NULL,
+            command TEXT NOT NULL,
+            PRIMARY KEY (player_id, command),
+            FOREIGN KEY (player_id) REFERENCES players(id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Every privileged command, for @audit
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            player_id TEXT NOT NULL,
+            username TEXT NOT NULL,
+            room_id TEXT NOT NULL,
+            command TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Site bans, player bans, freezes and silences; expired rows are ignored, not deleted
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS sanctions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            target TEXT NOT NULL,
+            target_name TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            issued_by TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER,
+            UNIQUE (kind, target)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Turns the old is_admin flag into the admin role. The flag is cleared so a later
+/// demotion sticks; if there is no owner yet, the longest-standing admin becomes one.
+async fn migrate_admin_flag(db: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
+    sqlx::query("UPDATE players SET role = 'admin', is_admin = 0 WHERE is_admin = 1 AND role = 'player'")
+        .execute(db)
+        .await?;
+
+    let (owners,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM players WHERE role = 'owner'")
+        .fetch_one(db)
+        .await?;
+    if owners == 0 {
+        sqlx::query(
+            "UPDATE players SET role = 'owner' WHERE id = (SELECT id FROM players WHERE role = 'admin' ORDER BY created_at LIMIT 1)"
+        )
+        .execute(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn seed_socials(db: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM socials")
+        .fetch_one(db)
+        .await?;
+    if count > 0 {
+        return Ok(());
+    }
+
+    for [name, no_target_actor, no_target_room, actor, target, room] in DEFAULT_SOCIALS {
+        sqlx::query(
+            "INSERT INTO socials (name, no_target_actor, no_target_room, actor, target, room, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(name)
+        .bind(no_target_actor)
+        .bind(no_target_room)
+        .bind(actor)
+        .bind(target)
+        .bind(room)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Adds a column to an existing table so databases created by older builds keep working.
+async fn add_column_if_missing(
+    db: &SqlitePool,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?"
+    )
+    .bind(table)
+    .bind(column)
+    .fetch_one(db)
+    .await?;
+
+    if count == 0 {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Older databases declared container_id as a foreign key to game_objects, which made it
+/// impossible to put anything in a player's inventory. SQLite can't drop a constraint, so the
+/// table is rebuilt once with foreign keys switched off for the copy.
+async fn drop_container_foreign_key(db: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pragma_foreign_key_list('game_objects')"
+    )
+    .fetch_one(db)
+    .await?;
+
+    if count == 0 {
+        return Ok(());
+    }
+
+    let mut conn = db.acquire().await?;
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+    sqlx::query("BEGIN").execute(&mut *conn).await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE game_objects_new (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT NOT NULL,
+            object_type TEXT NOT NULL,
+            container_id TEXT,
+            properties TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("INSERT INTO game_objects_new SELECT id, name, description, object_type, container_id, properties, created_at FROM game_objects")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DROP TABLE game_objects").execute(&mut *conn).await?;
+    sqlx::query("ALTER TABLE game_objects_new RENAME TO game_objects").execute(&mut *conn).await?;
+    sqlx::query("COMMIT").execute(&mut *conn).await?;
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
 
     Ok(())
 }
@@ -109,51 +550,4 @@ async fn create_starting_room(db: &SqlitePool) -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
-async fn create_starting_admin(db: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
-    let username = "admin";
-    let admin_exists: Option<(i64,)> = sqlx::query_as(
-        "SELECT COUNT(*) FROM players WHERE username = ?"
-    )
-    .bind(username)
-    .fetch_optional(db)
-    .await?;
-
-    if admin_exists.map(|r| r.0).unwrap_or(0) == 0 {
-        use argon2::{
-            password_hash::{
-                rand_core::OsRng,
-                PasswordHasher, SaltString
-            },
-            Argon2
-        };
-        let id = "admin";
-        let username = "admin";
-        let password = "12345";
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = argon2::Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| format!("Password hashing failed: {}", e))?
-            .to_string();
-        let is_admin = 1; 
-        
-        sqlx::query(
-            r#"
-            INSERT INTO players (id, username, password_hash, current_location, is_admin, created_at)
-            VALUES (?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(id)
-        .bind(username)
-        .bind(password_hash)
-        .bind("room_start")
-        .bind(is_admin)
-        .bind(chrono::Utc::now().timestamp())
-        .execute(db)
-        .await?;
-    }
-
-    Ok(())
-}
-
 
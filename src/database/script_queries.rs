@@ -0,0 +1,101 @@
+// Script database operations
+
+use crate::models::Script;
+use sqlx::{SqliteExecutor, SqlitePool};
+
+pub async fn set_script<'e>(
+    db: impl SqliteExecutor<'e>,
+    owner_type: &str,
+    owner_id: &str,
+    event: &str,
+    code: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO scripts (owner_type, owner_id, event, code, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT (owner_type, owner_id, event) DO UPDATE SET code = excluded.code
+        "#,
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .bind(event)
+    .bind(code)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to save script: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn get_script(
+    db: &SqlitePool,
+    owner_type: &str,
+    owner_id: &str,
+    event: &str,
+) -> Result<Option<Script>, String> {
+    sqlx::query_as("SELECT * FROM scripts WHERE owner_type = ? AND owner_id = ? AND event = ?")
+        .bind(owner_type)
+        .bind(owner_id)
+        .bind(event)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to fetch script: {}", e))
+}
+
+pub async fn get_scripts_for_owner(
+    db: &SqlitePool,
+    owner_type: &str,
+    owner_id: &str,
+) -> Result<Vec<Script>, String> {
+    sqlx::query_as("SELECT * FROM scripts WHERE owner_type = ? AND owner_id = ? ORDER BY event")
+        .bind(owner_type)
+        .bind(owner_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch scripts: {}", e))
+}
+
+pub async fn get_scripts_for_event(
+    db: &SqlitePool,
+    event: &str,
+) -> Result<Vec<Script>, String> {
+    sqlx::query_as("SELECT * FROM scripts WHERE event = ?")
+        .bind(event)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch scripts: {}", e))
+}
+
+pub async fn delete_script(
+    db: &SqlitePool,
+    owner_type: &str,
+    owner_id: &str,
+    event: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM scripts WHERE owner_type = ? AND owner_id = ? AND event = ?")
+        .bind(owner_type)
+        .bind(owner_id)
+        .bind(event)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete script: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_scripts_for_owner(
+    db: &SqlitePool,
+    owner_type: &str,
+    owner_id: &str,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM scripts WHERE owner_type = ? AND owner_id = ?")
+        .bind(owner_type)
+        .bind(owner_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete scripts: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// Social database operations
+
+use crate::models::Social;
+use sqlx::SqlitePool;
+
+pub async fn get_social(
+    db: &SqlitePool,
+    name: &str,
+) -> Result<Option<Social>, String> {
+    sqlx::query_as("SELECT * FROM socials WHERE name = ?")
+        .bind(name.to_lowercase())
+        .fetch_optional(db)
+        .await
+        .map_err(|e| format!("Failed to fetch social: {}", e))
+}
+
+pub async fn list_socials(
+    db: &SqlitePool,
+) -> Result<Vec<Social>, String> {
+    sqlx::query_as("SELECT * FROM socials ORDER BY name")
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to fetch socials: {}", e))
+}
+
+pub async fn create_social(
+    db: &SqlitePool,
+    social: &Social,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO socials (name, no_target_actor, no_target_room, actor, target, room, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&social.name)
+    .bind(&social.no_target_actor)
+    .bind(&social.no_target_room)
+    .bind(&social.actor)
+    .bind(&social.target)
+    .bind(&social.room)
+    .bind(social.created_at)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to create social: {}", e))?;
+
+    Ok(())
+}
+
+/// Sets one message column; `column` must be one of the social's message columns.
+pub async fn update_social_field(
+    db: &SqlitePool,
+    name: &str,
+    column: &str,
+    value: &str,
+) -> Result<(), String> {
+    sqlx::query(&format!("UPDATE socials SET {} = ? WHERE name = ?", column))
+        .bind(value)
+        .bind(name)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update social: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn delete_social(
+    db: &SqlitePool,
+    name: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM socials WHERE name = ?")
+        .bind(name)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to delete social: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,117 @@
+// Ignore lists and offline tells
+
+use sqlx::SqlitePool;
+
+pub async fn is_ignoring(
+    db: &SqlitePool,
+    player_id: &str,
+    other_id: &str,
+) -> Result<bool, String> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM ignores WHERE player_id = ? AND ignored_id = ?"
+    )
+    .bind(player_id)
+    .bind(other_id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| format!("Failed to check ignores: {}", e))?;
+
+    Ok(count > 0)
+}
+
+/// Usernames a player is ignoring.
+pub async fn get_ignored_names(
+    db: &SqlitePool,
+    player_id: &str,
+) -> Result<Vec<String>, String> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT p.username FROM ignores i JOIN players p ON p.id = i.ignored_id
+        WHERE i.player_id = ?
+        ORDER BY p.username
+        "#,
+    )
+    .bind(player_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| format!("Failed to fetch ignores: {}", e))?;
+
+    Ok(rows.into_iter().map(|(name,)| name).collect())
+}
+
+pub async fn set_ignoring(
+    db: &SqlitePool,
+    player_id: &str,
+    other_id: &str,
+    ignoring: bool,
+) -> Result<(), String> {
+    let query = match ignoring {
+        true => "INSERT OR IGNORE INTO ignores (player_id, ignored_id) VALUES (?, ?)",
+        false => "DELETE FROM ignores WHERE player_id = ? AND ignored_id = ?",
+    };
+    sqlx::query(query)
+        .bind(player_id)
+        .bind(other_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to update ignores: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn count_offline_tells(
+    db: &SqlitePool,
+    recipient_id: &str,
+) -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM offline_tells WHERE recipient_id = ?")
+        .bind(recipient_id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| format!("Failed to count tells: {}", e))?;
+
+    Ok(count)
+}
+
+pub async fn queue_offline_tell(
+    db: &SqlitePool,
+    recipient_id: &str,
+    sender_id: &str,
+    sender_name: &str,
+    message: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO offline_tells (recipient_id, sender_id, sender_name, message, sent_at) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(recipient_id)
+    .bind(sender_id)
+    .bind(sender_name)
+    .bind(message)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to queue tell: {}", e))?;
+
+    Ok(())
+}
+
+/// Removes and returns a player's queued tells as (sender_id, sender_name, message, sent_at), oldest first.
+pub async fn take_offline_tells(
+    db: &SqlitePool,
+    recipient_id: &str,
+) -> Result<Vec<(String, String, String, i64)>, String> {
+    let tells = sqlx::query_as(
+        "SELECT sender_id, sender_name, message, sent_at FROM offline_tells WHERE recipient_id = ? ORDER BY id"
+    )
+    .bind(recipient_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| format!("Failed to fetch tells: {}", e))?;
+
+    sqlx::query("DELETE FROM offline_tells WHERE recipient_id = ?")
+        .bind(recipient_id)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to clear tells: {}", e))?;
+
+    Ok(tells)
+}
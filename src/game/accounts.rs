@@ -0,0 +1,247 @@
+// Passwords: hashing, login throttling, the first-boot owner account and changing passwords
+
+use crate::config::ServerConfig;
+use crate::database::player_queries;
+use crate::game::{moderation, validation, GameState};
+use crate::models::Player;
+use rand::distr::{Alphanumeric, SampleString};
+use sqlx::SqlitePool;
+use std::net::IpAddr;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Overrides `owner_password` in the config.
+pub const OWNER_PASSWORD_ENV: &str = "MUD_OWNER_PASSWORD";
+const GENERATED_PASSWORD_LENGTH: usize = 16;
+/// What older versions seeded the first admin with, and advertised in the README.
+const OLD_DEFAULT_ADMIN: (&str, &str) = ("admin", "12345");
+/// Longest wait between failed logins, however many there have been.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Failed logins from one address or for one account.
+#[derive(Debug, Clone)]
+pub struct LoginFailures {
+    pub count: u32,
+    pub last_failure: Instant,
+    pub locked_until: Option<Instant>,
+}
+
+impl LoginFailures {
+    /// How long after the last failure the next try is allowed: doubling per failure.
+    fn backoff(&self, base_millis: u64) -> Duration {
+        let doublings = self.count.saturating_sub(1).min(16);
+        Duration::from_millis(base_millis.saturating_mul(1 << doublings)).min(MAX_BACKOFF)
+    }
+
+    /// Time left before another login may be tried, if any.
+    pub fn wait(&self, base_millis: u64) -> Option<Duration> {
+        let until = match self.locked_until {
+            Some(locked) => locked,
+            None => self.last_failure + self.backoff(base_millis),
+        };
+        until.checked_duration_since(Instant::now())
+    }
+}
+
+fn ip_key(ip: &IpAddr) -> String {
+    format!("ip {}", ip)
+}
+
+fn account_key(username: &str) -> String {
+    format!("account {}", username.to_lowercase())
+}
+
+/// A real hash to check passwords against when the username doesn't exist.
+pub fn dummy_hash() -> &'static str {
+    static DUMMY: OnceLock<String> = OnceLock::new();
+    DUMMY.get_or_init(|| hash_password("not a real password").unwrap_or_default())
+}
+
+pub fn hash_password(password: &str) -> Result<String, String> {
+    use argon2::password_hash::{rand_core::OsRng, PasswordHasher, SaltString};
+    let salt = SaltString::generate(&mut OsRng);
+    argon2::Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Password hashing failed: {}", e))
+}
+
+pub fn verify_password(password_hash: &str, password: &str) -> bool {
+    use argon2::password_hash::{PasswordHash, PasswordVerifier};
+    match PasswordHash::new(password_hash) {
+        Ok(parsed) => argon2::Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Forgets failures older than the lockout period, so they don't count forever.
+async fn expire_failures(state: &Arc<GameState>) {
+    let memory = Duration::from_secs(state.config.login_lockout_secs);
+    state.login_failures.write().await.retain(|_, f| {
+        f.last_failure.elapsed() < memory || f.locked_until.is_some_and(|l| l > Instant::now())
+    });
+}
+
+async fn record_failure(state: &Arc<GameState>, key: String) -> u32 {
+    let mut failures = state.login_failures.write().await;
+    let entry = failures.entry(key).or_insert(LoginFailures {
+        count: 0,
+        last_failure: Instant::now(),
+        locked_until: None,
+    });
+    entry.count += 1;
+    entry.last_failure = Instant::now();
+    if entry.count >= state.config.login_lockout_failures {
+        entry.locked_until = Some(Instant::now() + Duration::from_secs(state.config.login_lockout_secs));
+    }
+    entry.count
+}
+
+/// Checks a login, refusing it while the address or account is backing off or locked out.
+/// Failures are counted against both and answered only after the backoff, to slow guessing.
+pub async fn login(state: &Arc<GameState>, ip: &IpAddr, username: &str, password: &str) -> Result<Player, String> {
+    expire_failures(state).await;
+    let keys = [ip_key(ip), account_key(username)];
+
+    let wait = {
+        let failures = state.login_failures.read().await;
+        keys.iter()
+            .filter_map(|k| failures.get(k))
+            .filter_map(|f| f.wait(state.config.login_backoff_millis))
+            .max()
+    };
+    if let Some(wait) = wait {
+        let seconds = wait.as_secs() + 1;
+        return Err(format!("Too many failed logins. Try again in {} second{}.", seconds, if seconds == 1 { "" } else { "s" }));
+    }
+
+    match state.authenticate(username, password).await {
+        Ok(player) => {
+            // the address keeps its count, or one good account could clear the way for guessing others
+            state.login_failures.write().await.remove(&keys[1]);
+            moderation::check_login(state, &player).await?;
+            Ok(player)
+        }
+        Err(e) => {
+            let mut worst = 0;
+            for key in keys {
+                worst = worst.max(record_failure(state, key).await);
+            }
+            println!("Failed login for '{}' from {} ({} recent failures)", username, ip, worst);
+            let backoff = LoginFailures { count: worst, last_failure: Instant::now(), locked_until: None };
+            tokio::time::sleep(backoff.backoff(state.config.login_backoff_millis)).await;
+            Err(e)
+        }
+    }
+}
+
+/// Makes the owner account if there isn't one. Its password comes from the environment or
+/// config, or is generated and printed here, once; either way it must be changed at first
+/// login. Also makes an owner still on the old published default password change it.
+pub async fn bootstrap_owner(db: &SqlitePool, config: &ServerConfig) -> Result<(), String> {
+    if player_queries::count_players_with_role(db, "owner").await? > 0 {
+        let (id, password) = OLD_DEFAULT_ADMIN;
+        if let Ok(admin) = player_queries::get_player_by_id(db, id).await {
+            if admin.must_change_password == 0 && verify_password(&admin.password_hash, password) {
+                player_queries::update_password(db, &admin.id, &admin.password_hash, true).await?;
+                println!("The '{}' account still has the old default password; it must be changed at next login.", admin.username);
+            }
+        }
+        return Ok(());
+    }
+
+    let username = &config.owner_username;
+    if player_queries::find_player_by_username(db, username).await?.is_some() {
+        eprintln!(
+            "There is no owner account, and '{}' is already taken by a player. Set owner_username to a free name.",
+            username
+        );
+        return Ok(());
+    }
+
+    let configured = std::env::var(OWNER_PASSWORD_ENV).ok().or(config.owner_password.clone());
+    let password = match &configured {
+        Some(p) => p.clone(),
+        None => Alphanumeric.sample_string(&mut rand::rng(), GENERATED_PASSWORD_LENGTH),
+    };
+    player_queries::create_owner(db, &Uuid::new_v4().to_string(), username, &hash_password(&password)?).await?;
+
+    match configured {
+        Some(_) => println!("Created the owner account '{}' with the configured password.", username),
+        None => {
+            println!("Created the owner account '{}' with the password: {}", username, password);
+            println!("It won't be shown again, and must be changed at first login.");
+        }
+    }
+    Ok(())
+}
+
+/// Saves a new password and clears any pending forced change.
+pub async fn set_password(state: &Arc<GameState>, player_id: &str, password: &str) -> Result<(), String> {
+    let password_hash = hash_password(password)?;
+    player_queries::update_password(&state.db, player_id, &password_hash, false).await
+}
+
+// regular commands
+/// `password <current> <new>` changes the player's own password.
+pub async fn handle_password(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() != 3 {
+        return "Usage: password <current password> <new password>\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    if !verify_password(&player.password_hash, parts[1]) {
+        return "That isn't your current password.\n".to_string();
+    }
+    if parts[1] == parts[2] {
+        return "That's the password you have now.\n".to_string();
+    }
+    if let Err(e) = validation::check_password(&state.config, &player.username, parts[2]) {
+        return format!("{}\n", e);
+    }
+
+    set_password(&state, player_id, parts[2]).await.unwrap();
+    "Password changed.\n".to_string()
+}
+
+// admin commands
+/// `@lockouts` lists addresses and accounts with recent failed logins;
+/// `@lockouts clear <address|name>` forgives one.
+pub async fn handle_admin_lockouts(state: Arc<GameState>, parts: &[&str]) -> String {
+    expire_failures(&state).await;
+
+    if parts.len() == 3 && parts[1].eq_ignore_ascii_case("clear") {
+        let mut failures = state.login_failures.write().await;
+        let removed = [format!("ip {}", parts[2]), account_key(parts[2])]
+            .iter()
+            .filter(|k| failures.remove(*k).is_some())
+            .count();
+        return match removed {
+            0 => format!("Nothing recorded for '{}'.\n", parts[2]),
+            _ => format!("Cleared failed logins for '{}'.\n", parts[2]),
+        };
+    }
+    if parts.len() != 1 {
+        return "Usage: @lockouts, @lockouts clear <address|name>\n".to_string();
+    }
+
+    let failures = state.login_failures.read().await;
+    if failures.is_empty() {
+        return "No recent failed logins.\n".to_string();
+    }
+
+    let mut entries: Vec<_> = failures.iter().collect();
+    entries.sort_by_key(|(_, f)| std::cmp::Reverse(f.count));
+    let mut response = "Recent failed logins:\n".to_string();
+    for (key, f) in entries {
+        let status = match f.locked_until.and_then(|l| l.checked_duration_since(Instant::now())) {
+            Some(left) => format!("locked for {}s more", left.as_secs()),
+            None => format!("last {}s ago", f.last_failure.elapsed().as_secs()),
+        };
+        response.push_str(&format!("  {:<32} {:>3} failures  {}\n", key, f.count, status));
+    }
+    response
+}
@@ -0,0 +1,211 @@
+// Area management commands
+
+use crate::database::{area_queries, npc_queries, player_queries, reset_queries};
+use crate::game::GameState;
+use crate::models::{Area, Role};
+use std::sync::Arc;
+
+async fn find_area(state: &Arc<GameState>, name: &str) -> Result<Area, String> {
+    match area_queries::get_area_by_name(&state.db, name).await? {
+        Some(area) => Ok(area),
+        None => Err(format!("There is no area called '{}'.\n", name)),
+    }
+}
+
+fn describe_flags(area: &Area) -> String {
+    let flags = area.flags();
+    if flags.is_empty() {
+        "none".to_string()
+    } else {
+        flags.join(", ")
+    }
+}
+
+// admin commands
+pub async fn handle_admin_acreate(state: Arc<GameState>, parts: &[&str]) -> String {
+    if parts.len() != 2 {
+        return "Usage: @acreate <area_name> (one word)\n".to_string();
+    }
+
+    let name = parts[1].to_lowercase();
+    if area_queries::get_area_by_name(&state.db, &name).await.unwrap().is_some() {
+        return "An area with that name already exists.\n".to_string();
+    }
+
+    match area_queries::create_area(&state.db, &name).await {
+        Ok(id) => format!("Area '{}' created (#{}). Use @aroom {} to move rooms into it.\n", name, id, name),
+        Err(e) => format!("{}\n", e),
+    }
+}
+
+pub async fn handle_admin_alist(state: Arc<GameState>) -> String {
+    let areas = area_queries::list_areas(&state.db).await.unwrap();
+    let mut response = "Areas:\n".to_string();
+    for area in areas {
+        let rooms = area_queries::count_rooms(&state.db, area.id).await.unwrap();
+        response.push_str(&format!(
+            "  {:<16} {:>4} rooms  levels {}-{}  flags: {}\n",
+            area.name,
+            rooms,
+            area.min_level,
+            area.max_level,
+            describe_flags(&area)
+        ));
+    }
+    response
+}
+
+pub async fn handle_admin_astat(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let area = match parts.get(1) {
+        Some(name) => match find_area(&state, name).await {
+            Ok(area) => area,
+            Err(e) => return e,
+        },
+        None => state.get_room_area(&player.current_location).await.unwrap(),
+    };
+
+    let rooms = area_queries::count_rooms(&state.db, area.id).await.unwrap();
+    let resets = reset_queries::get_resets(&state.db, Some(&area.name)).await.unwrap();
+    let builders = area.builder_list();
+    let reset_interval = if area.reset_interval_secs > 0 {
+        format!("every {}s", area.reset_interval_secs)
+    } else {
+        format!("every {}s (server default)", state.config.reset_interval_secs)
+    };
+
+    let mut response = format!(
+        "Area '{}' (#{})\n  Rooms: {}\n  Levels: {}-{}\n  Resets: {}, {}\n  Flags: {}\n  Builders: {}\n",
+        area.name,
+        area.id,
+        rooms,
+        area.min_level,
+        area.max_level,
+        resets.len(),
+        reset_interval,
+        describe_flags(&area),
+        if builders.is_empty() { "none".to_string() } else { builders.join(", ") }
+    );
+
+    if area.vnum_high > 0 {
+        response.push_str(&format!("  NPC prototype ids: {}-{}\n", area.vnum_low, area.vnum_high));
+        let prototypes = npc_queries::list_prototypes(&state.db).await.unwrap();
+        for p in prototypes.iter().filter(|p| area.owns_vnum(p.id)) {
+            response.push_str(&format!("    #{:<4} {}\n", p.id, p.name));
+        }
+    }
+
+    response
+}
+
+pub async fn handle_admin_aset(state: Arc<GameState>, parts: &[&str]) -> String {
+    let usage = "Usage: @aset <area> <field> <value>\n\
+                 Fields: levels <min> <max>, reset <seconds> (0 for the server default),\n\
+                 safe|norecall|pk <on|off>, builder +<name>|-<name>, vnums <low> <high>\n";
+
+    if parts.len() < 4 {
+        return usage.to_string();
+    }
+
+    let area = match find_area(&state, parts[1]).await {
+        Ok(area) => area,
+        Err(e) => return e,
+    };
+    let field = parts[2].to_lowercase();
+    let numbers: Vec<i64> = parts[3..].iter().filter_map(|p| p.parse().ok()).collect();
+
+    let result = match field.as_str() {
+        "levels" => match numbers.as_slice() {
+            [min, max] if *min >= 1 && min <= max => {
+                let set_min = area_queries::update_area_field(&state.db, area.id, "min_level", *min).await;
+                match set_min {
+                    Ok(()) => area_queries::update_area_field(&state.db, area.id, "max_level", *max).await,
+                    Err(e) => Err(e),
+                }
+            }
+            _ => return "Levels must be two numbers, lowest first.\n".to_string(),
+        },
+        "reset" => match numbers.as_slice() {
+            [secs] if *secs >= 0 => area_queries::update_area_field(&state.db, area.id, "reset_interval_secs", *secs).await,
+            _ => return "The reset interval must be a number of seconds.\n".to_string(),
+        },
+        "safe" | "norecall" | "no-recall" | "pk" => {
+            let value = match parts[3].to_lowercase().as_str() {
+                "on" | "yes" | "1" => 1,
+                "off" | "no" | "0" => 0,
+                _ => return usage.to_string(),
+            };
+            let column = match field.as_str() {
+                "safe" => "is_safe",
+                "pk" => "pk_allowed",
+                _ => "no_recall",
+            };
+            area_queries::update_area_field(&state.db, area.id, column, value).await
+        }
+        "builder" | "builders" => {
+            let mut builders = area.builder_list();
+            let arg = parts[3];
+            if let Some(name) = arg.strip_prefix('-') {
+                builders.retain(|b| !b.eq_ignore_ascii_case(name));
+            } else {
+                let name = arg.trim_start_matches('+');
+                match player_queries::get_player_by_username(&state.db, name).await {
+                    Ok(Some(p)) if !area.is_builder(&p.username) => builders.push(p.username),
+                    Ok(Some(_)) => return format!("{} is already a builder here.\n", name),
+                    _ => return "There is no such player.\n".to_string(),
+                }
+            }
+            let json = serde_json::to_string(&builders).unwrap();
+            area_queries::update_area_field(&state.db, area.id, "builders", json).await
+        }
+        "vnums" => match numbers.as_slice() {
+            [low, high] if *low >= 1 && low <= high => {
+                let areas = area_queries::list_areas(&state.db).await.unwrap();
+                let overlap = areas
+                    .iter()
+                    .find(|a| a.id != area.id && a.vnum_high > 0 && *low <= a.vnum_high && *high >= a.vnum_low);
+                if let Some(other) = overlap {
+                    return format!("That range overlaps area '{}' ({}-{}).\n", other.name, other.vnum_low, other.vnum_high);
+                }
+                let set_low = area_queries::update_area_field(&state.db, area.id, "vnum_low", *low).await;
+                match set_low {
+                    Ok(()) => area_queries::update_area_field(&state.db, area.id, "vnum_high", *high).await,
+                    Err(e) => Err(e),
+                }
+            }
+            _ => return "The id range must be two positive numbers, lowest first.\n".to_string(),
+        },
+        _ => return usage.to_string(),
+    };
+
+    match result {
+        Ok(()) => format!("Area '{}' updated.\n", area.name),
+        Err(e) => format!("{}\n", e),
+    }
+}
+
+/// Moves the current room (and its resets) into an area.
+pub async fn handle_admin_aroom(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if parts.len() != 2 {
+        return "Usage: @aroom <area>\n".to_string();
+    }
+
+    let area = match find_area(&state, parts[1]).await {
+        Ok(area) => area,
+        Err(e) => return e,
+    };
+    if player.role() < Role::Admin && !area.is_builder(&player.username) {
+        return format!("You aren't a builder of '{}'.\n", area.name);
+    }
+
+    area_queries::set_room_area(&state.db, &player.current_location, &area).await.unwrap();
+
+    format!("This room now belongs to area '{}'.\n", area.name)
+}
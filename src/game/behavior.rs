@@ -0,0 +1,375 @@
+// NPC behaviors run on the server tick
+//
+// Each NPC prototype carries a JSON map of behavior name -> parameters, e.g.
+// {"wander": {"chance": 25, "radius": 3}, "emote": {"chance": 10, "lines": ["yawns."]}}.
+// New behaviors implement `Behavior` and are added to `BehaviorRegistry::with_defaults`.
+
+use crate::database::{npc_queries, player_queries};
+use crate::game::pathfinding::{self, PathOptions};
+use crate::game::{combat, GameState};
+use crate::models::Npc;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+pub struct BehaviorContext<'a> {
+    pub state: &'a Arc<GameState>,
+    pub npc: &'a Npc,
+    pub params: &'a Value,
+    pub tick: u64,
+}
+
+pub trait Behavior: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>>;
+}
+
+pub struct BehaviorRegistry {
+    behaviors: HashMap<&'static str, Box<dyn Behavior>>,
+}
+
+impl BehaviorRegistry {
+    pub fn new() -> Self {
+        Self {
+            behaviors: HashMap::new(),
+        }
+    }
+
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(Wander));
+        registry.register(Box::new(Aggressive));
+        registry.register(Box::new(Follow));
+        registry.register(Box::new(Emote));
+        registry.register(Box::new(Schedule));
+        registry.register(Box::new(Shopkeeper));
+        registry.register(Box::new(Guard));
+        registry
+    }
+
+    pub fn register(&mut self, behavior: Box<dyn Behavior>) {
+        self.behaviors.insert(behavior.name(), behavior);
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.behaviors.keys().copied().collect();
+        names.sort();
+        names
+    }
+
+    /// Runs every behavior of every NPC once.
+    pub async fn run_tick(&self, state: &Arc<GameState>, tick: u64) -> Result<(), String> {
+        let npcs = npc_queries::get_all_npcs(&state.db).await?;
+
+        for npc in npcs {
+            for (name, params) in npc.behavior_map() {
+                // Reload each time: an earlier behavior may have moved or killed this NPC
+                let current = match npc_queries::get_npc(&state.db, &npc.id).await {
+                    Ok(n) => n,
+                    Err(_) => break,
+                };
+                if let Some(behavior) = self.behaviors.get(name.as_str()) {
+                    let ctx = BehaviorContext {
+                        state,
+                        npc: &current,
+                        params: &params,
+                        tick,
+                    };
+                    if let Err(e) = behavior.tick(ctx).await {
+                        eprintln!("Behavior '{}' failed for {}: {}", name, current.name, e);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn param_u64(params: &Value, key: &str, default: u64) -> u64 {
+    params.get(key).and_then(|v| v.as_u64()).unwrap_or(default)
+}
+
+/// Percent chance check, `chance` in 0..=100.
+fn roll(chance: u64) -> bool {
+    rand::random_range(1..=100) <= chance
+}
+
+/// Moves an NPC and tells both rooms about it.
+async fn move_npc(state: &Arc<GameState>, npc: &Npc, direction: &str, destination: &str) -> Result<(), String> {
+    npc_queries::move_npc(&state.db, &npc.id, destination).await?;
+    state.send_to_room(&npc.room_id, &format!("{} leaves {}.\n", npc.name, direction), None).await;
+    state.send_to_room(destination, &format!("{} arrives.\n", npc.name), None).await;
+    Ok(())
+}
+
+/// Rooms reachable from `start` in at most `radius` steps without leaving its area.
+async fn rooms_within(state: &Arc<GameState>, start: &str, radius: u64) -> Result<HashSet<String>, String> {
+    let area_id = state.get_room(start).await?.area_id;
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start.to_string(), 0)]);
+
+    while let Some((room, depth)) = queue.pop_front() {
+        if depth >= radius {
+            continue;
+        }
+        for exit in state.get_exits(&room).await? {
+            if !exit.is_passable() || seen.contains(&exit.destination_id) {
+                continue;
+            }
+            let destination = state.get_room(&exit.destination_id).await?;
+            if destination.area_id != area_id || destination.room_flags().has("death-trap") {
+                continue;
+            }
+            seen.insert(exit.destination_id.clone());
+            queue.push_back((exit.destination_id, depth + 1));
+        }
+    }
+
+    Ok(seen)
+}
+
+/// The player this NPC is fighting or chasing. A target that has logged off, or has left the
+/// room of an NPC that doesn't follow, is forgotten so the NPC goes back to its business.
+async fn live_target(state: &Arc<GameState>, npc: &Npc) -> Result<Option<String>, String> {
+    let target_id = match &npc.target_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    let stale = if !state.is_online(target_id).await {
+        true
+    } else if npc.has_behavior("follow") {
+        // Follow drops the target itself once it is out of range
+        false
+    } else {
+        match player_queries::get_player_by_id(&state.db, target_id).await {
+            Ok(target) => target.current_location != npc.room_id,
+            Err(_) => true,
+        }
+    };
+
+    if stale {
+        npc_queries::set_npc_target(&state.db, &npc.id, None).await?;
+        return Ok(None);
+    }
+    Ok(Some(target_id.clone()))
+}
+
+/// Wanders through random exits, staying within `radius` rooms of where it was spawned and
+/// inside that room's area.
+struct Wander;
+
+impl Behavior for Wander {
+    fn name(&self) -> &'static str {
+        "wander"
+    }
+
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            if !roll(param_u64(ctx.params, "chance", 25)) || live_target(ctx.state, ctx.npc).await?.is_some() {
+                return Ok(());
+            }
+
+            let exits = ctx.state.get_exits(&ctx.npc.room_id).await?;
+            if exits.is_empty() {
+                return Ok(());
+            }
+
+            let home = ctx.npc.home_room_id.as_deref().unwrap_or(&ctx.npc.room_id);
+            let allowed = rooms_within(ctx.state, home, param_u64(ctx.params, "radius", 3)).await?;
+            let choices: Vec<_> = exits
+                .iter()
+                .filter(|e| e.is_passable() && allowed.contains(&e.destination_id))
+                .collect();
+            if choices.is_empty() {
+                return Ok(());
+            }
+
+            let exit = choices[rand::random_range(0..choices.len())];
+            move_npc(ctx.state, ctx.npc, &exit.direction, &exit.destination_id).await
+        })
+    }
+}
+
+/// Attacks online players in the room whose level is below `below_level`.
+struct Aggressive;
+
+impl Behavior for Aggressive {
+    fn name(&self) -> &'static str {
+        "aggressive"
+    }
+
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let below_level = param_u64(ctx.params, "below_level", 10) as i64;
+            if ctx.state.get_room_area(&ctx.npc.room_id).await?.is_safe == 1 {
+                return Ok(());
+            }
+            if ctx.state.get_room(&ctx.npc.room_id).await?.room_flags().has("no-combat") {
+                return Ok(());
+            }
+            let players = ctx.state.get_players_in_room(&ctx.npc.room_id).await?;
+            let target = live_target(ctx.state, ctx.npc).await?;
+
+            // Keep fighting the current target if it is still here, otherwise pick a new victim
+            let mut victim = None;
+            for p in players {
+                if p.level >= below_level || !ctx.state.is_online(&p.id).await {
+                    continue;
+                }
+                let is_target = target.as_deref() == Some(p.id.as_str());
+                if victim.is_none() || is_target {
+                    victim = Some(p);
+                }
+                if is_target {
+                    break;
+                }
+            }
+
+            if let Some(player) = victim {
+                npc_queries::set_npc_target(&ctx.state.db, &ctx.npc.id, Some(&player.id)).await?;
+                let result = combat::npc_attacks_player(ctx.state, ctx.npc, &player).await;
+                ctx.state.send_to_player(&player.id, &result).await;
+                ctx.state
+                    .send_to_room(&ctx.npc.room_id, &format!("{} attacks {}!\n", ctx.npc.name, player.username), Some(&player.id))
+                    .await;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Follows its target player, tracking them up to `range` rooms away (default 3).
+struct Follow;
+
+impl Behavior for Follow {
+    fn name(&self) -> &'static str {
+        "follow"
+    }
+
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let target_id = match &ctx.npc.target_id {
+                Some(id) => id,
+                None => return Ok(()),
+            };
+
+            if !ctx.state.is_online(target_id).await {
+                return npc_queries::set_npc_target(&ctx.state.db, &ctx.npc.id, None).await;
+            }
+
+            let target = player_queries::get_player_by_id(&ctx.state.db, target_id).await?;
+            if target.current_location == ctx.npc.room_id {
+                return Ok(());
+            }
+
+            let path = pathfinding::find_path(ctx.state, &ctx.npc.room_id, &target.current_location, &PathOptions::npc()).await?;
+            let range = param_u64(ctx.params, "range", 3) as usize;
+            let step = match path {
+                Some(path) if path.steps.len() <= range => path.steps.into_iter().next(),
+                _ => None,
+            };
+
+            let exits = ctx.state.get_exits(&ctx.npc.room_id).await?;
+            match step.and_then(|s| exits.into_iter().find(|e| e.direction == s)) {
+                Some(exit) => move_npc(ctx.state, ctx.npc, &exit.direction, &exit.destination_id).await,
+                // Lost them
+                None => npc_queries::set_npc_target(&ctx.state.db, &ctx.npc.id, None).await,
+            }
+        })
+    }
+}
+
+/// Randomly performs one of its `lines` as an emote.
+struct Emote;
+
+impl Behavior for Emote {
+    fn name(&self) -> &'static str {
+        "emote"
+    }
+
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            if !roll(param_u64(ctx.params, "chance", 10)) {
+                return Ok(());
+            }
+
+            let lines: Vec<&str> = ctx.params
+                .get("lines")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|l| l.as_str()).collect())
+                .unwrap_or_default();
+            if lines.is_empty() {
+                return Ok(());
+            }
+
+            let line = lines[rand::random_range(0..lines.len())];
+            ctx.state.send_to_room(&ctx.npc.room_id, &format!("{} {}\n", ctx.npc.name, line), None).await;
+            Ok(())
+        })
+    }
+}
+
+/// Says `say` every `every` ticks, e.g. a town crier.
+struct Schedule;
+
+impl Behavior for Schedule {
+    fn name(&self) -> &'static str {
+        "schedule"
+    }
+
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let every = param_u64(ctx.params, "every", 20).max(1);
+            if !ctx.tick.is_multiple_of(every) {
+                return Ok(());
+            }
+
+            if let Some(text) = ctx.params.get("say").and_then(|v| v.as_str()) {
+                ctx.state.send_to_room(&ctx.npc.room_id, &format!("{} says: {}\n", ctx.npc.name, text), None).await;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Sells its `stock` through the `list` and `buy` commands. It has nothing to do on the tick.
+struct Shopkeeper;
+
+impl Behavior for Shopkeeper {
+    fn name(&self) -> &'static str {
+        "shopkeeper"
+    }
+
+    fn tick<'a>(&'a self, _ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+/// Attacks aggressive NPCs that are in the same room.
+struct Guard;
+
+impl Behavior for Guard {
+    fn name(&self) -> &'static str {
+        "guard"
+    }
+
+    fn tick<'a>(&'a self, ctx: BehaviorContext<'a>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let npcs = ctx.state.get_npcs_in_room(&ctx.npc.room_id).await?;
+            let hostile = npcs.iter().find(|n| n.id != ctx.npc.id && n.has_behavior("aggressive"));
+
+            if let Some(hostile) = hostile {
+                let result = combat::npc_attacks_npc(ctx.state, ctx.npc, hostile).await;
+                ctx.state.send_to_room(&ctx.npc.room_id, &result, None).await;
+            }
+            Ok(())
+        })
+    }
+}
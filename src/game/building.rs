@@ -0,0 +1,698 @@
+// Online building (OLC) commands
+
+use crate::database::{area_queries, exit_queries, mail_queries, npc_queries, object_queries, player_queries, reset_queries, script_queries};
+use crate::game::{commands, directions, editor, permissions, GameState};
+use crate::models::{EditTarget, GameObject, Player, Role};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Item properties that rooms check for: a light for dark rooms, a boat for water and
+/// something to breathe with underwater.
+pub const ITEM_FLAGS: [&str; 3] = ["light", "boat", "breathing"];
+
+/// Splits a command line into words, keeping "double quoted" phrases together.
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_word = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_word = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_word {
+                    args.push(std::mem::take(&mut current));
+                    has_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_word = true;
+            }
+        }
+    }
+    if has_word {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Everything after the command word, untouched.
+fn rest_of(cmd: &str) -> &str {
+    let cmd = cmd.trim();
+    match cmd.split_once(char::is_whitespace) {
+        Some((_, rest)) => rest.trim(),
+        None => "",
+    }
+}
+
+/// The player running a building command; permissions::gate has already checked their
+/// role and that they can build here.
+async fn get_builder(state: &Arc<GameState>, player_id: &str) -> Player {
+    player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap()
+}
+
+/// Resolves a builder's target: "here", an object id, then carried items, then the room's contents.
+/// Below admin, ids only reach things in the room or carried, so builders stay in their areas.
+async fn find_object(state: &Arc<GameState>, player: &Player, target: &str) -> Option<GameObject> {
+    let target = target.trim();
+    if target.eq_ignore_ascii_case("here") {
+        return state.get_room(&player.current_location).await.ok();
+    }
+    if let Ok(obj) = object_queries::get_object(&state.db, target).await {
+        let nearby = obj.id == player.current_location
+            || obj.container_id.as_deref().is_some_and(|c| c == player.id || c == player.current_location);
+        if nearby || player.role() >= Role::Admin {
+            return Some(obj);
+        }
+    }
+
+    let name = target.to_lowercase();
+    for container in [&player.id, &player.current_location] {
+        let objects = state.get_objects_in_container(container).await.unwrap();
+        if let Some(obj) = objects.into_iter().find(|o| o.name.to_lowercase() == name) {
+            return Some(obj);
+        }
+    }
+
+    None
+}
+
+pub async fn handle_admin_dig(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    if args.len() < 3 {
+        return "Usage: @dig <exit>[:<return_exit>] <room_name> <room_description>\n".to_string();
+    }
+
+    let (exit_input, return_input) = match args[0].split_once(':') {
+        Some((exit, back)) => (exit, Some(back)),
+        None => (args[0].as_str(), None),
+    };
+    let room_name = &args[1];
+    let room_desc = args[2..].join(" ");
+
+    // parse direction to ensure full string in room exits
+    let direction = directions::normalize(exit_input);
+    let opposite = match return_input {
+        Some(back) => Some(directions::normalize(back)),
+        None => directions::opposite(&state.config, &direction),
+    };
+
+    if has_exit(&state, &player.current_location, &direction).await {
+        return format!("There is already an exit '{}' here; @unlink it first.\n", direction);
+    }
+
+    // Create new room, in the same area as this one
+    let new_room = state.create_object(room_name, &room_desc, "room", None).await.unwrap();
+    let area = state.get_room_area(&player.current_location).await.unwrap();
+    area_queries::set_room_area(&state.db, &new_room.id, &area).await.unwrap();
+
+    // Add exit from current room to new room
+    state.add_exit(&player.current_location, &direction, &new_room.id).await.unwrap();
+
+    // Add return exit
+    match opposite {
+        Some(opposite) => {
+            state.add_exit(&new_room.id, &opposite, &player.current_location).await.unwrap();
+            format!("Room created! Exit '{}' added, with '{}' leading back.\n", direction, opposite)
+        }
+        None => format!(
+            "Room created! Exit '{}' added. It has no known opposite, so no return exit was made \
+             (use @dig <exit>:<return_exit> to name one).\n",
+            direction
+        ),
+    }
+}
+
+pub async fn handle_admin_create(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    if args.len() < 2 {
+        return "Usage: @create <item_name> <description>\n".to_string();
+    }
+
+    let item_name = &args[0];
+    let item_desc = args[1..].join(" ");
+
+    // Create item in current room
+    state.create_object(item_name, &item_desc, "item", Some(&player.current_location)).await.unwrap();
+
+    format!("Created '{}'.\n", item_name)
+}
+
+/// `@desc <text>` and `@desc <target> = <text>` replace a description;
+/// `@desc + <text>` and `@desc <target> += <text>` add a new line to it. With no text, the
+/// description is opened in the line editor.
+pub async fn handle_admin_desc(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let rest = rest_of(cmd);
+    let (target, text, append) = if let Some(text) = rest.strip_prefix("+ ") {
+        ("here".to_string(), text.trim(), true)
+    } else if let Some((target, text)) = rest.split_once("+=") {
+        (split_args(target).join(" "), text.trim(), true)
+    } else if let Some((target, text)) = rest.split_once('=') {
+        (split_args(target).join(" "), text.trim(), false)
+    } else {
+        ("here".to_string(), rest, false)
+    };
+
+    if text.is_empty() && append {
+        return "Usage: @desc [<target> =] <description>, or @desc [<target>] += <line> to add a line\n".to_string();
+    }
+
+    let obj = match find_object(&state, &player, &target).await {
+        Some(obj) => obj,
+        None => return "You don't see that here.\n".to_string(),
+    };
+
+    // no text: compose it in the line editor instead
+    if text.is_empty() {
+        let target = EditTarget::Description { object_id: obj.id.clone() };
+        return editor::start(&state, player_id, target, &obj.description).await;
+    }
+
+    let description = if append && !obj.description.is_empty() {
+        format!("{}\n{}", obj.description, text)
+    } else {
+        text.to_string()
+    };
+    object_queries::update_object_description(&state.db, &obj.id, &description).await.unwrap();
+
+    if append {
+        format!("Added a line to the description of {}.\n", obj.name)
+    } else {
+        format!("Description of {} updated.\n", obj.name)
+    }
+}
+
+pub async fn handle_admin_name(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    if args.len() < 2 {
+        return "Usage: @name <here|object> <new name>\n".to_string();
+    }
+
+    let obj = match find_object(&state, &player, &args[0]).await {
+        Some(obj) => obj,
+        None => return "You don't see that here.\n".to_string(),
+    };
+    let new_name = args[1..].join(" ");
+
+    object_queries::update_object_name(&state.db, &obj.id, &new_name).await.unwrap();
+
+    format!("{} is now called {}.\n", obj.name, new_name)
+}
+
+/// Whether a room already has an exit in this direction. Adding another would replace it.
+async fn has_exit(state: &GameState, room_id: &str, direction: &str) -> bool {
+    let exits = state.get_exits(room_id).await.unwrap_or_default();
+    exits.iter().any(|e| e.direction.eq_ignore_ascii_case(direction))
+}
+
+/// Joins the current room to an existing room, optionally with a return exit.
+pub async fn handle_admin_link(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    if args.len() < 2 {
+        return "Usage: @link <exit>[:<return_exit>] <room_id>\n".to_string();
+    }
+
+    let (exit_input, return_input) = match args[0].split_once(':') {
+        Some((exit, back)) => (exit, Some(back)),
+        None => (args[0].as_str(), None),
+    };
+    let destination = match state.get_room(&args[1]).await {
+        Ok(room) => room,
+        Err(_) => return "There is no room with that id. Use @find to look one up.\n".to_string(),
+    };
+
+    let direction = directions::normalize(exit_input);
+    let opposite = match return_input {
+        Some(back) => Some(directions::normalize(back)),
+        None => directions::opposite(&state.config, &direction),
+    };
+    if has_exit(&state, &player.current_location, &direction).await {
+        return format!("There is already an exit '{}' here; @unlink it first.\n", direction);
+    }
+    if opposite.is_some() && !permissions::can_build_in(&state, &player, &destination.id).await.unwrap() {
+        state.add_exit(&player.current_location, &direction, &destination.id).await.unwrap();
+        return format!(
+            "Linked '{}' to {}. You can't build in that room's area, so no return exit was made.\n",
+            direction, destination.name
+        );
+    }
+
+    if let Some(opposite) = &opposite {
+        if has_exit(&state, &destination.id, opposite).await {
+            return format!(
+                "{} already has an exit '{}'. @unlink it there first, or name another return exit with \
+                 @link <exit>:<return_exit>.\n",
+                destination.name, opposite
+            );
+        }
+    }
+
+    state.add_exit(&player.current_location, &direction, &destination.id).await.unwrap();
+
+    match opposite {
+        Some(opposite) => {
+            state.add_exit(&destination.id, &opposite, &player.current_location).await.unwrap();
+            format!("Linked '{}' to {}, with '{}' leading back.\n", direction, destination.name, opposite)
+        }
+        None => format!("Linked '{}' to {}. No return exit was made.\n", direction, destination.name),
+    }
+}
+
+pub async fn handle_admin_unlink(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    if args.is_empty() {
+        return "Usage: @unlink <exit> [both]\n".to_string();
+    }
+
+    let direction = directions::normalize(&args[0]);
+    let exits = state.get_exits(&player.current_location).await.unwrap();
+    let exit = match exits.into_iter().find(|e| e.direction == direction) {
+        Some(exit) => exit,
+        None => return "There is no exit that way.\n".to_string(),
+    };
+
+    exit_queries::delete_exit(&state.db, &exit.room_id, &exit.direction).await.unwrap();
+
+    if args.get(1).is_some_and(|a| a.eq_ignore_ascii_case("both")) {
+        if let Some(reverse) = exit_queries::get_reverse_exit(&state.db, &exit).await.unwrap() {
+            if !permissions::can_build_in(&state, &player, &reverse.room_id).await.unwrap() {
+                return format!("Removed exit '{}'. The way back is in an area you can't build in.\n", exit.direction);
+            }
+            exit_queries::delete_exit(&state.db, &reverse.room_id, &reverse.direction).await.unwrap();
+            return format!("Removed '{}' and '{}' on the other side.\n", exit.direction, reverse.direction);
+        }
+    }
+
+    format!("Removed exit '{}'.\n", exit.direction)
+}
+
+/// Destroys an object and everything inside it. Rooms must be empty of players.
+pub async fn handle_admin_destroy(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let target = split_args(rest_of(cmd)).join(" ");
+    if target.is_empty() {
+        return "Usage: @destroy <object|npc|room_id>\n".to_string();
+    }
+
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+    if let Some(n) = npcs.iter().find(|n| n.matches(&target.to_lowercase())) {
+        npc_queries::delete_npc(&state.db, &n.id).await.unwrap();
+        return format!("{} is destroyed.\n", n.name);
+    }
+
+    let obj = match find_object(&state, &player, &target).await {
+        Some(obj) => obj,
+        None => return "You don't see that here.\n".to_string(),
+    };
+
+    if obj.object_type == "room" {
+        if obj.id == "room_start" {
+            return "The starting room can't be destroyed.\n".to_string();
+        }
+        let players = state.get_players_in_room(&obj.id).await.unwrap();
+        if !players.is_empty() {
+            return "There are players in that room; move them out first.\n".to_string();
+        }
+    }
+
+    let (count, resets) = match destroy_object(&state, &obj).await {
+        Ok(result) => result,
+        Err(e) => return format!("Error: {}\n", e),
+    };
+
+    let mut response = if count > 1 {
+        format!("{} and {} thing(s) inside it are destroyed.\n", obj.name, count - 1)
+    } else {
+        format!("{} is destroyed.\n", obj.name)
+    };
+    if resets > 0 {
+        response.push_str(&format!("Removed {} reset(s) that spawned there.\n", resets));
+    }
+    response
+}
+
+/// Deletes an object, everything inside it and whatever refers to them: a room's NPCs and
+/// exits, scripts, boards and resets. Returns how many objects and resets went.
+pub async fn destroy_object(state: &GameState, obj: &GameObject) -> Result<(usize, u64), String> {
+    if obj.object_type == "room" {
+        for n in state.get_npcs_in_room(&obj.id).await? {
+            npc_queries::delete_npc(&state.db, &n.id).await?;
+        }
+        exit_queries::delete_exits_touching(&state.db, &obj.id).await?;
+    }
+
+    let mut pending = vec![obj.id.clone()];
+    let mut count = 0;
+    let mut resets = 0;
+    while let Some(id) = pending.pop() {
+        for child in state.get_objects_in_container(&id).await? {
+            pending.push(child.id);
+        }
+        resets += reset_queries::delete_resets_for(&state.db, &id).await?;
+        script_queries::delete_scripts_for_owner(&state.db, "object", &id).await?;
+        mail_queries::delete_board(&state.db, &id).await?;
+        object_queries::delete_object(&state.db, &id).await?;
+        count += 1;
+    }
+
+    Ok((count, resets))
+}
+
+/// `@teleport <room_id>` moves you; `@teleport <player> <room_id|player>` moves someone else.
+pub async fn handle_admin_teleport(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    let (subject, destination) = match args.as_slice() {
+        [destination] => (player.clone(), destination),
+        [name, destination] => match player_queries::get_player_by_username(&state.db, name).await {
+            Ok(Some(p)) => (p, destination),
+            _ => return "There is no such player.\n".to_string(),
+        },
+        _ => return "Usage: @teleport [player] <room_id|player>\n".to_string(),
+    };
+
+    let room_id = match state.get_room(destination).await {
+        Ok(room) => room.id,
+        Err(_) => match player_queries::get_player_by_username(&state.db, destination).await {
+            Ok(Some(p)) => p.current_location,
+            _ => return "There is no room or player by that name.\n".to_string(),
+        },
+    };
+
+    // moving someone else needs a higher rank than theirs, and somewhere the mover can build
+    if subject.id != player.id {
+        if player.role() != Role::Owner && subject.role() >= player.role() {
+            return "You don't have permission to do that.\n".to_string();
+        }
+        if !permissions::can_build_in(&state, &player, &room_id).await.unwrap() {
+            return "You can only move other players into areas you are a builder of.\n".to_string();
+        }
+    }
+
+    state
+        .send_to_room(&subject.current_location, &format!("{} vanishes.\n", subject.username), Some(&subject.id))
+        .await;
+    state.move_player_to_room(&subject.id, &room_id).await.unwrap();
+    state
+        .send_to_room(&room_id, &format!("{} appears out of thin air.\n", subject.username), Some(&subject.id))
+        .await;
+
+    if subject.id == player.id {
+        return commands::handle_look(state, player_id).await;
+    }
+    state.send_to_player(&subject.id, "You are whisked away!\n").await;
+
+    format!("{} has been teleported.\n", subject.username)
+}
+
+pub async fn handle_admin_examine(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let mut target = split_args(rest_of(cmd)).join(" ");
+    if target.is_empty() {
+        target = "here".to_string();
+    }
+
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+    if let Some(n) = npcs.iter().find(|n| n.matches(&target.to_lowercase())) {
+        return format!(
+            "NPC: {}\n  Id: {}\n  Prototype: #{}\n  Level: {}\n  Health: {}/{}\n  Room: {}\n  Home: {}\n  Behaviors: {}\n",
+            n.name,
+            n.id,
+            n.prototype_id,
+            n.level,
+            n.current_health,
+            n.max_health,
+            n.room_id,
+            n.home_room_id.as_deref().unwrap_or("-"),
+            n.behaviors
+        );
+    }
+
+    let obj = match find_object(&state, &player, &target).await {
+        Some(obj) => obj,
+        None => return "You don't see that here.\n".to_string(),
+    };
+
+    let mut response = format!(
+        "{}\n  Id: {}\n  Type: {}\n  Container: {}\n  Properties: {}\n  Description:\n{}\n",
+        obj.name,
+        obj.id,
+        obj.object_type,
+        obj.container_id.as_deref().unwrap_or("-"),
+        obj.properties,
+        obj.description
+    );
+
+    let contents = state.get_objects_in_container(&obj.id).await.unwrap();
+    if !contents.is_empty() {
+        response.push_str("  Contents:\n");
+        for item in contents {
+            response.push_str(&format!("    - {} [{}] ({})\n", item.name, item.object_type, item.id));
+        }
+    }
+
+    if obj.object_type == "room" {
+        let exits = state.get_exits(&obj.id).await.unwrap();
+        if !exits.is_empty() {
+            response.push_str("  Exits:\n");
+            for exit in exits {
+                response.push_str(&format!("    - {} -> {}\n", exit.direction, exit.destination_id));
+            }
+        }
+    }
+
+    let scripts = script_queries::get_scripts_for_owner(&state.db, "object", &obj.id).await.unwrap();
+    if !scripts.is_empty() {
+        let events: Vec<&str> = scripts.iter().map(|s| s.event.as_str()).collect();
+        response.push_str(&format!("  Scripts: {}\n", events.join(", ")));
+    }
+
+    response
+}
+
+/// Turns an item property such as `light` on or off.
+pub async fn handle_admin_oset(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let args = split_args(rest_of(cmd));
+    let usage = format!("Usage: @oset <object> <{}> <on|off>\n", ITEM_FLAGS.join("|"));
+    if args.len() != 3 || !ITEM_FLAGS.contains(&args[1].to_lowercase().as_str()) {
+        return usage;
+    }
+    let on = match args[2].to_lowercase().as_str() {
+        "on" | "yes" | "1" => true,
+        "off" | "no" | "0" => false,
+        _ => return usage,
+    };
+
+    let obj = match find_object(&state, &player, &args[0]).await {
+        Some(obj) if obj.object_type != "room" => obj,
+        Some(_) => return "Use @rset for room flags.\n".to_string(),
+        None => return "You don't see that here.\n".to_string(),
+    };
+
+    let mut properties: serde_json::Value = serde_json::from_str(&obj.properties)
+        .unwrap_or_else(|_| serde_json::json!({}));
+    let key = args[1].to_lowercase();
+    match properties.as_object_mut() {
+        Some(map) if on => {
+            map.insert(key.clone(), serde_json::Value::Bool(true));
+        }
+        Some(map) => {
+            map.remove(&key);
+        }
+        None => return "That object's properties aren't an object.\n".to_string(),
+    }
+
+    object_queries::update_object_properties(&state.db, &obj.id, &properties.to_string())
+        .await
+        .unwrap();
+    format!("{}: {} {}.\n", obj.name, key, if on { "on" } else { "off" })
+}
+
+/// Copies an object (with its properties and scripts, but not its contents) beside the original.
+pub async fn handle_admin_clone(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
+    let player = get_builder(&state, player_id).await;
+
+    let target = split_args(rest_of(cmd)).join(" ");
+    if target.is_empty() {
+        return "Usage: @clone <object>\n".to_string();
+    }
+
+    let obj = match find_object(&state, &player, &target).await {
+        Some(obj) => obj,
+        None => return "You don't see that here.\n".to_string(),
+    };
+    if obj.object_type == "room" {
+        return "Rooms can't be cloned; use @dig instead.\n".to_string();
+    }
+
+    // a copy shouldn't count towards the original's reset
+    let mut properties: serde_json::Value = serde_json::from_str(&obj.properties)
+        .unwrap_or_else(|_| serde_json::json!({}));
+    if let Some(map) = properties.as_object_mut() {
+        map.remove("reset_id");
+    }
+
+    let mut copy = GameObject::new(
+        Uuid::new_v4().to_string(),
+        obj.name.clone(),
+        obj.description.clone(),
+        obj.object_type.clone(),
+        obj.container_id.clone(),
+    );
+    copy.properties = properties.to_string();
+    object_queries::create_object(&state.db, &copy).await.unwrap();
+
+    for script in script_queries::get_scripts_for_owner(&state.db, "object", &obj.id).await.unwrap() {
+        script_queries::set_script(&state.db, "object", &copy.id, &script.event, &script.code)
+            .await
+            .unwrap();
+    }
+
+    format!("Cloned {} ({}).\n", copy.name, copy.id)
+}
+
+pub async fn handle_admin_find(state: Arc<GameState>, cmd: &str) -> String {
+
+    let args = split_args(rest_of(cmd));
+    let (object_type, pattern) = match args.as_slice() {
+        [] => return "Usage: @find [room|item] <name>\n".to_string(),
+        [kind, rest @ ..] if !rest.is_empty() && ["room", "item"].contains(&kind.as_str()) => {
+            (Some(kind.as_str()), rest.join(" "))
+        }
+        _ => (None, args.join(" ")),
+    };
+
+    let found = object_queries::find_objects(&state.db, &pattern, object_type).await.unwrap();
+    if found.is_empty() {
+        return "Nothing matches that.\n".to_string();
+    }
+
+    let mut response = String::from("Found:\n");
+    for obj in found {
+        response.push_str(&format!(
+            "  - {} [{}] {} (in {})\n",
+            obj.name,
+            obj.object_type,
+            obj.id,
+            obj.container_id.as_deref().unwrap_or("-")
+        ));
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::reset_queries;
+
+    /// A room north of the start holding a chest with a coin in it, plus an NPC, a script,
+    /// a board post and resets that all point at them.
+    async fn furnished_vault(state: &Arc<GameState>, author: &Player) -> (GameObject, GameObject, GameObject) {
+        let vault = state.create_object("Vault", "A vault.", "room", None).await.unwrap();
+        state.add_exit("room_start", "north", &vault.id).await.unwrap();
+        state.add_exit(&vault.id, "south", "room_start").await.unwrap();
+        let chest = state.create_object("chest", "A chest.", "item", Some(&vault.id)).await.unwrap();
+        let coin = state.create_object("coin", "A coin.", "item", Some(&chest.id)).await.unwrap();
+
+        let prototype_id = npc_queries::create_prototype(&state.db, "guard", "A guard.").await.unwrap();
+        state.spawn_npc(prototype_id, &vault.id, None).await.unwrap();
+        script_queries::set_script(&state.db, "object", &chest.id, "on_look", "say(\"hi\")").await.unwrap();
+        mail_queries::create_board_post(&state.db, &chest.id, &author.id, &author.username, "Hello", "First!").await.unwrap();
+        reset_queries::create_reset(
+            &state.db, "default", &vault.id, None, "npc", Some(prototype_id), "guard", "", "npc", "{}", 1,
+        )
+        .await
+        .unwrap();
+        reset_queries::create_reset(
+            &state.db, "default", &vault.id, Some(&chest.id), "object", None, "coin", "A coin.", "item", "{}", 1,
+        )
+        .await
+        .unwrap();
+
+        (vault, chest, coin)
+    }
+
+    #[tokio::test]
+    async fn destroying_a_room_clears_everything_that_points_at_it() {
+        let state = GameState::in_memory().await;
+        let admin = state.add_test_player("ada", Role::Admin).await;
+        let (vault, chest, coin) = furnished_vault(&state, &admin).await;
+        // a reset kept elsewhere that fills the chest
+        reset_queries::create_reset(
+            &state.db, "default", "room_start", Some(&chest.id), "object", None, "gem", "A gem.", "item", "{}", 1,
+        )
+        .await
+        .unwrap();
+
+        let response = handle_admin_destroy(state.clone(), &admin.id, &format!("@destroy {}", vault.id)).await;
+        assert_eq!(response, "Vault and 2 thing(s) inside it are destroyed.\nRemoved 3 reset(s) that spawned there.\n");
+
+        for id in [&vault.id, &chest.id, &coin.id] {
+            assert!(object_queries::get_object(&state.db, id).await.is_err());
+        }
+        assert!(state.get_exits("room_start").await.unwrap().is_empty());
+        assert!(state.get_npcs_in_room(&vault.id).await.unwrap().is_empty());
+        assert!(reset_queries::get_resets(&state.db, None).await.unwrap().is_empty());
+        assert!(script_queries::get_scripts_for_owner(&state.db, "object", &chest.id).await.unwrap().is_empty());
+        assert!(mail_queries::get_board_posts(&state.db, &chest.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn destroying_a_container_keeps_the_room() {
+        let state = GameState::in_memory().await;
+        let admin = state.add_test_player("ada", Role::Admin).await;
+        let (vault, _, coin) = furnished_vault(&state, &admin).await;
+        player_queries::update_player_location(&state.db, &admin.id, &vault.id).await.unwrap();
+
+        let response = handle_admin_destroy(state.clone(), &admin.id, "@destroy chest").await;
+        assert_eq!(response, "chest and 1 thing(s) inside it are destroyed.\nRemoved 1 reset(s) that spawned there.\n");
+
+        assert!(object_queries::get_object(&state.db, &coin.id).await.is_err());
+        assert!(state.get_room(&vault.id).await.is_ok());
+        assert_eq!(state.get_exits(&vault.id).await.unwrap().len(), 1);
+        // the room's own NPC reset is left alone
+        assert_eq!(reset_queries::get_resets_in_room(&state.db, &vault.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rooms_with_players_or_the_start_are_kept() {
+        let state = GameState::in_memory().await;
+        let admin = state.add_test_player("ada", Role::Admin).await;
+        let visitor = state.add_test_player("vic", Role::Player).await;
+        let (vault, _, _) = furnished_vault(&state, &admin).await;
+        player_queries::update_player_location(&state.db, &visitor.id, &vault.id).await.unwrap();
+
+        let response = handle_admin_destroy(state.clone(), &admin.id, &format!("@destroy {}", vault.id)).await;
+        assert_eq!(response, "There are players in that room; move them out first.\n");
+        assert!(state.get_room(&vault.id).await.is_ok());
+
+        let response = handle_admin_destroy(state.clone(), &admin.id, "@destroy room_start").await;
+        assert_eq!(response, "The starting room can't be destroyed.\n");
+    }
+}
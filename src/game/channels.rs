@@ -0,0 +1,156 @@
+// Chat channels: speaking, listening, history and subscriptions
+
+use crate::database::{channel_queries, player_queries, tell_queries};
+use crate::game::{moderation, GameState};
+use crate::models::{Channel, Player, CHANNELS, CHANNEL_HISTORY};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+fn can_use(channel: &Channel, player: &Player) -> bool {
+    player.role() >= channel.min_role
+}
+
+/// True if the player is on the channel, by choice or by default.
+async fn is_joined(state: &Arc<GameState>, channel: &Channel, player: &Player) -> Result<bool, String> {
+    if !can_use(channel, player) {
+        return Ok(false);
+    }
+    let subscription = channel_queries::get_subscription(&state.db, &player.id, channel.name).await?;
+    Ok(subscription.unwrap_or(channel.default_on))
+}
+
+async fn is_muted(state: &Arc<GameState>, channel: &Channel, player_id: &str) -> bool {
+    state.sessions.read().await.get(player_id).is_some_and(|s| s.muted_channels.contains(channel.name))
+}
+
+/// Sends a message to everyone listening on a channel and keeps it for replay.
+pub async fn broadcast(state: &Arc<GameState>, channel: &Channel, speaker: &Player, message: &str) -> Result<String, String> {
+    let line = format!("[{}] {}: {}\n", channel.title, speaker.username, message);
+
+    {
+        let mut history = state.channel_history.write().await;
+        let recent = history.entry(channel.name).or_insert_with(VecDeque::new);
+        recent.push_back(format!("{} {}", chrono::Local::now().format("%H:%M"), line));
+        if recent.len() > CHANNEL_HISTORY {
+            recent.pop_front();
+        }
+    }
+
+    let listeners: Vec<String> = state.sessions.read().await.keys().cloned().collect();
+    for listener_id in listeners {
+        if listener_id == speaker.id || is_muted(state, channel, &listener_id).await {
+            continue;
+        }
+        if tell_queries::is_ignoring(&state.db, &listener_id, &speaker.id).await? {
+            continue;
+        }
+        let listener = player_queries::get_player_by_id(&state.db, &listener_id).await?;
+        if is_joined(state, channel, &listener).await? {
+            state.send_to_player(&listener_id, &line).await;
+        }
+    }
+
+    Ok(line)
+}
+
+async fn history(state: &Arc<GameState>, channel: &Channel) -> String {
+    let history = state.channel_history.read().await;
+    match history.get(channel.name) {
+        Some(recent) if !recent.is_empty() => {
+            let mut response = format!("Recent {} messages:\n", channel.name);
+            for line in recent {
+                response.push_str(line);
+            }
+            response
+        }
+        _ => format!("Nothing has been said on {} lately.\n", channel.name),
+    }
+}
+
+// regular commands
+/// Speaks on a channel, e.g. `gossip hello`; with no message, replays its history.
+pub async fn handle_channel_say(state: Arc<GameState>, player_id: &str, channel: &Channel, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if !can_use(channel, &player) {
+        return "Unknown command. Type 'help' for available commands.\n".to_string();
+    }
+    if !is_joined(&state, channel, &player).await.unwrap() {
+        return format!("You aren't on {}. Type 'channel join {}' first.\n", channel.name, channel.name);
+    }
+    if parts.len() < 2 {
+        return history(&state, channel).await;
+    }
+    if let Some(refusal) = moderation::silenced_refusal(&state, player_id).await {
+        return refusal;
+    }
+
+    broadcast(&state, channel, &player, &parts[1..].join(" ")).await.unwrap()
+}
+
+/// Lists the channels the player can use and whether they are on each.
+pub async fn handle_channels(state: Arc<GameState>, player_id: &str) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let mut response = "Channels:\n".to_string();
+    for channel in CHANNELS.iter().filter(|c| can_use(c, &player)) {
+        let status = match is_joined(&state, channel, &player).await.unwrap() {
+            true if is_muted(&state, channel, player_id).await => "muted",
+            true => "on",
+            false => "off",
+        };
+        response.push_str(&format!("  {:<10} {:<6} {}\n", channel.name, status, channel.description));
+    }
+    response.push_str("Use 'channel <join|leave|mute|unmute|history> <name>' to change them.\n");
+    response
+}
+
+/// `channel join|leave|mute|unmute|history <name>`. Joining and leaving are saved; muting
+/// lasts until logout.
+pub async fn handle_channel(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let usage = "Usage: channel <join|leave|mute|unmute|history> <name>\n".to_string();
+    if parts.len() != 3 {
+        return usage;
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let channel = match Channel::find(parts[2]) {
+        Some(c) if can_use(c, &player) => c,
+        _ => return "There is no such channel.\n".to_string(),
+    };
+
+    match parts[1].to_lowercase().as_str() {
+        "join" | "leave" => {
+            let joined = parts[1].eq_ignore_ascii_case("join");
+            channel_queries::set_subscription(&state.db, player_id, channel.name, joined)
+                .await
+                .unwrap();
+            match joined {
+                true => format!("You join {}.\n", channel.name),
+                false => format!("You leave {}.\n", channel.name),
+            }
+        }
+        "mute" | "unmute" => {
+            let mute = parts[1].eq_ignore_ascii_case("mute");
+            let mut sessions = state.sessions.write().await;
+            if let Some(session) = sessions.get_mut(player_id) {
+                match mute {
+                    true => session.muted_channels.insert(channel.name.to_string()),
+                    false => session.muted_channels.remove(channel.name),
+                };
+            }
+            match mute {
+                true => format!("{} muted until you log out.\n", channel.title),
+                false => format!("{} unmuted.\n", channel.title),
+            }
+        }
+        "history" => history(&state, channel).await,
+        _ => usage,
+    }
+}
@@ -0,0 +1,177 @@
+// Combat between players and NPCs
+
+use crate::database::{npc_queries, player_queries};
+use crate::game::scripting::{self, ScriptContext};
+use crate::game::{travel, GameState};
+use crate::models::{Npc, Player};
+use crate::models::dice_rolls::random_distribution_roll_result;
+use std::sync::Arc;
+
+const START_ROOM: &str = "room_start";
+
+/// Result of a single swing.
+struct Swing {
+    hit: bool,
+    damage: i64,
+}
+
+/// Attacker's dexterity against the defender's defense, then strength against armor.
+async fn swing(attacker_dex: i64, attacker_str: i64, defender_defense: i64, defender_armor: i64) -> Swing {
+    let attack_roll = random_distribution_roll_result(attacker_dex as f64, 15.0).await;
+    let defense_roll = random_distribution_roll_result(defender_defense as f64, 15.0).await;
+
+    if attack_roll < defense_roll {
+        return Swing { hit: false, damage: 0 };
+    }
+
+    let raw = random_distribution_roll_result(attacker_str as f64 / 5.0, 4.0).await;
+    Swing {
+        hit: true,
+        damage: (raw - defender_armor).max(1),
+    }
+}
+
+pub async fn handle_attack(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Attack whom?\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    if state.get_room_area(&player.current_location).await.unwrap().is_safe == 1 {
+        return "A sense of peace stays your hand. This is a safe area.\n".to_string();
+    }
+    if state.get_room(&player.current_location).await.unwrap().room_flags().has("no-combat") {
+        return "You can't fight here.\n".to_string();
+    }
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+    let target = parts[1..].join(" ");
+
+    match npcs.into_iter().find(|n| n.matches(&target)) {
+        Some(npc) => fight_round(state, &player, npc).await,
+        None => "You don't see that here.\n".to_string(),
+    }
+}
+
+/// One exchange of blows: the player strikes first, then the NPC strikes back if it survived.
+pub async fn fight_round(state: Arc<GameState>, player: &Player, npc: Npc) -> String {
+    let mut response = String::new();
+
+    let player_swing = swing(
+        player.dexterity,
+        player.strength,
+        npc.physical_defense,
+        npc.physical_armor,
+    ).await;
+
+    let npc_health = npc.current_health - player_swing.damage;
+    if player_swing.hit {
+        response.push_str(&format!("You hit {} for {} damage.\n", npc.name, player_swing.damage));
+    } else {
+        response.push_str(&format!("You miss {}.\n", npc.name));
+    }
+
+    if npc_health <= 0 {
+        response.push_str(&kill_npc(&state, &player.username, Some(&player.id), &npc).await);
+        return response;
+    }
+    npc_queries::update_npc_health(&state.db, &npc.id, npc_health).await.unwrap();
+
+    // Remember who started the fight so follow/aggressive behaviors can pursue them
+    npc_queries::set_npc_target(&state.db, &npc.id, Some(&player.id)).await.unwrap();
+
+    response.push_str(&npc_attacks_player(&state, &npc, player).await);
+    response
+}
+
+/// The NPC swings at the player; handles the player's death if it lands hard enough.
+pub async fn npc_attacks_player(state: &Arc<GameState>, npc: &Npc, player: &Player) -> String {
+    // being attacked stops anyone walking a route
+    let mut response = match travel::interrupt(state, &player.id).await {
+        true => "You stop walking.\n".to_string(),
+        false => String::new(),
+    };
+
+    let npc_swing = swing(
+        npc.dexterity,
+        npc.strength,
+        player.physical_defense,
+        player.physical_armor,
+    ).await;
+
+    if !npc_swing.hit {
+        response.push_str(&format!("{} misses you.\n", npc.name));
+        return response;
+    }
+
+    let player_health = player.current_health - npc_swing.damage;
+    response.push_str(&format!("{} hits you for {} damage.\n", npc.name, npc_swing.damage));
+
+    if player_health <= 0 {
+        // Death is not permanent: restore health and send the player back to the start
+        player_queries::update_player_health(&state.db, &player.id, player.max_health).await.unwrap();
+        player_queries::update_player_location(&state.db, &player.id, START_ROOM).await.unwrap();
+        npc_queries::clear_targets_on(&state.db, &player.id).await.unwrap();
+        let _ = state.broadcast_tx.send(format!("{} has been slain by {}!\n", player.username, npc.name));
+        response.push_str("You have been slain! You awaken back where your adventure began.\n");
+    } else {
+        player_queries::update_player_health(&state.db, &player.id, player_health).await.unwrap();
+        response.push_str(&format!("Health: {}/{}\n", player_health, player.max_health));
+    }
+
+    response
+}
+
+/// An NPC swings at another NPC, e.g. a guard defending a room.
+pub async fn npc_attacks_npc(state: &Arc<GameState>, attacker: &Npc, defender: &Npc) -> String {
+    let npc_swing = swing(
+        attacker.dexterity,
+        attacker.strength,
+        defender.physical_defense,
+        defender.physical_armor,
+    ).await;
+
+    if !npc_swing.hit {
+        return format!("{} swings at {} and misses.\n", attacker.name, defender.name);
+    }
+
+    let health = defender.current_health - npc_swing.damage;
+    let mut response = format!("{} hits {}.\n", attacker.name, defender.name);
+    if health <= 0 {
+        response.push_str(&kill_npc(state, &attacker.name, None, defender).await);
+    } else {
+        npc_queries::update_npc_health(&state.db, &defender.id, health).await.unwrap();
+    }
+    response
+}
+
+async fn kill_npc(state: &Arc<GameState>, killer: &str, killer_id: Option<&str>, npc: &Npc) -> String {
+    // only whoever removes the NPC gets the kill, in case two fights finish it at once
+    if !npc_queries::delete_npc(&state.db, &npc.id).await.unwrap() {
+        return String::new();
+    }
+    scripting::fire(state, "npc", &npc.prototype_id.to_string(), ScriptContext {
+        event: "on_death",
+        self_id: &npc.id,
+        room_id: &npc.room_id,
+        actor_id: killer_id,
+        message: "",
+    }).await;
+
+    let mut response = format!("{} has been slain!\n", npc.name);
+    let _ = state.broadcast_tx.send(format!("{} has slain {}!\n", killer, npc.name));
+
+    for entry in npc.loot() {
+        let roll = rand::random_range(1..=100);
+        if roll <= entry.chance {
+            state
+                .create_object(&entry.name, &entry.description, "item", Some(&npc.room_id))
+                .await
+                .unwrap();
+            response.push_str(&format!("{} drops {}.\n", npc.name, entry.name));
+        }
+    }
+
+    response
+}
@@ -1,143 +1,184 @@
 // Command processing
 
-use crate::game::GameState;
-use crate::database::player_queries;
+use crate::game::scripting::{self, ScriptContext};
+use crate::game::{accounts, areas, building, channels, combat, directions, doors, editor, mail, map, moderation, npc, permissions, resets, rooms, socials, tells, travel, who, world, world_file, GameState};
+use crate::database::{object_queries, player_queries};
+use crate::models::Channel;
 use std::sync::Arc;
 
+/// Words the dispatcher below treats as commands, so players can't take them as names.
+/// Keep in step with the match; exits, channels and socials are checked separately.
+pub const COMMAND_WORDS: &[&str] = &[
+    "look", "l", "go", "open", "close", "lock", "unlock", "pick", "search", "map", "recall", "walkto",
+    "run", "stop", "say", "emote", "me", "pose", "socials", "tell", "reply", "ignore", "afk", "read",
+    "post", "remove", "mail", "who", "finger", "title", "plan", "last", "channels", "channel",
+    "inventory", "inv", "get", "take", "drop", "stats", "att", "score", "attack", "kill", "k", "talk",
+    "ask", "list", "buy", "password", "help", "quit",
+];
+
 pub async fn process_command(state: Arc<GameState>, player_id: &str, cmd: &str) -> String {
-    let parts: Vec<&str> = cmd.trim().split_whitespace().collect();
+    if editor::is_editing(&state, player_id).await {
+        return editor::handle_input(state, player_id, cmd).await;
+    }
+
+    let parts: Vec<&str> = cmd.split_whitespace().collect();
     if parts.is_empty() {
         return String::new();
     }
+    if let Some(refusal) = moderation::frozen_refusal(&state, player_id, parts[0]).await {
+        return refusal;
+    }
+    if let Some(refusal) = permissions::gate(&state, player_id, cmd).await {
+        return refusal;
+    }
 
     match parts[0].to_lowercase().as_str() {
-        "look" => handle_look(state, player_id).await,
-        "north" | "n" => handle_move(state, player_id, "north").await,
-        "south" | "s" => handle_move(state, player_id, "south").await,
-        "east" | "e" => handle_move(state, player_id, "east").await,
-        "west" | "w" => handle_move(state, player_id, "west").await,
-        "up" | "u" => handle_move(state, player_id, "up").await,
-        "down" | "d" => handle_move(state, player_id, "down").await,
+        "look" | "l" if parts.len() > 1 => handle_look_at(state, player_id, &parts).await,
+        "look" | "l" => handle_look(state, player_id).await,
+        dir if directions::is_compass(dir) => {
+            travel::interrupt(&state, player_id).await;
+            handle_move(state, player_id, &directions::normalize(dir)).await
+        }
+        "go" if parts.len() > 1 => {
+            travel::interrupt(&state, player_id).await;
+            handle_move(state, player_id, &directions::normalize(parts[1])).await
+        }
+        "go" => "Go where?\n".to_string(),
+        "open" | "close" | "lock" | "unlock" | "pick" => doors::handle_door_command(state, player_id, &parts).await,
+        "search" => doors::handle_search(state, player_id).await,
+        "map" => map::handle_map(state, player_id, &parts).await,
+        "recall" => handle_recall(state, player_id).await,
+        "walkto" => travel::handle_walkto(state, player_id, &parts).await,
+        "run" => travel::handle_run(state, player_id, &parts).await,
+        "stop" => travel::handle_stop(state, player_id).await,
         "say" => handle_say(state, player_id, &parts).await,
+        "emote" | "me" => socials::handle_emote(state, player_id, &parts).await,
+        "pose" => socials::handle_pose(state, player_id, &parts).await,
+        "socials" => socials::handle_socials(state).await,
+        "tell" => tells::handle_tell(state, player_id, &parts).await,
+        "reply" => tells::handle_reply(state, player_id, &parts).await,
+        "ignore" => tells::handle_ignore(state, player_id, &parts).await,
+        "afk" => tells::handle_afk(state, player_id, &parts).await,
+        "read" => mail::handle_read(state, player_id, &parts).await,
+        "post" => mail::handle_post(state, player_id, &parts).await,
+        "remove" => mail::handle_remove(state, player_id, &parts).await,
+        "mail" => mail::handle_mail(state, player_id, &parts).await,
+        "who" => who::handle_who(state).await,
+        "finger" => who::handle_finger(state, &parts).await,
+        "title" => who::handle_title(state, player_id, &parts).await,
+        "plan" => who::handle_plan(state, player_id).await,
+        "last" => who::handle_admin_last(state, &parts).await,
+        "channels" => channels::handle_channels(state, player_id).await,
+        "channel" => channels::handle_channel(state, player_id, &parts).await,
+        name if Channel::find(name).is_some() => {
+            channels::handle_channel_say(state, player_id, Channel::find(name).unwrap(), &parts).await
+        }
         "inventory" | "inv" => handle_inventory(state, player_id).await,
+        "get" | "take" => handle_get(state, player_id, &parts).await,
+        "drop" => handle_drop(state, player_id, &parts).await,
         "stats" | "att" | "score" => handle_stats(state, player_id).await,
-        "@dig" => handle_admin_dig(state, player_id, &parts).await,
-        "@create" => handle_admin_create(state, player_id, &parts).await,
-        "@desc" => handle_admin_desc(state, player_id, &parts).await,
+        "attack" | "kill" | "k" => combat::handle_attack(state, player_id, &parts).await,
+        "talk" | "ask" => npc::handle_talk(state, player_id, &parts).await,
+        "list" => npc::handle_list(state, player_id).await,
+        "buy" => npc::handle_buy(state, player_id, &parts).await,
+        "@dig" => building::handle_admin_dig(state, player_id, cmd).await,
+        "@create" => building::handle_admin_create(state, player_id, cmd).await,
+        "@desc" => building::handle_admin_desc(state, player_id, cmd).await,
+        "@name" => building::handle_admin_name(state, player_id, cmd).await,
+        "@link" => building::handle_admin_link(state, player_id, cmd).await,
+        "@unlink" => building::handle_admin_unlink(state, player_id, cmd).await,
+        "@destroy" => building::handle_admin_destroy(state, player_id, cmd).await,
+        "@teleport" | "@tel" => building::handle_admin_teleport(state, player_id, cmd).await,
+        "@examine" | "@ex" => building::handle_admin_examine(state, player_id, cmd).await,
+        "@oset" => building::handle_admin_oset(state, player_id, cmd).await,
+        "@clone" => building::handle_admin_clone(state, player_id, cmd).await,
+        "@find" => building::handle_admin_find(state, cmd).await,
+        "@mcreate" => npc::handle_admin_mcreate(state, &parts).await,
+        "@mload" => npc::handle_admin_mload(state, player_id, &parts).await,
+        "@mset" => npc::handle_admin_mset(state, &parts).await,
+        "@mlist" => npc::handle_admin_mlist(state).await,
+        "@reset" => resets::handle_admin_reset(state, player_id, &parts).await,
+        "@door" => doors::handle_admin_door(state, player_id, &parts).await,
+        "@script" => scripting::handle_admin_script(state, player_id, &parts).await,
+        "@acreate" => areas::handle_admin_acreate(state, &parts).await,
+        "@alist" => areas::handle_admin_alist(state).await,
+        "@astat" => areas::handle_admin_astat(state, player_id, &parts).await,
+        "@aset" => areas::handle_admin_aset(state, &parts).await,
+        "@aroom" => areas::handle_admin_aroom(state, player_id, &parts).await,
+        "@social" => socials::handle_admin_social(state, &parts).await,
+        "@board" => mail::handle_admin_board(state, player_id, &parts).await,
+        "@rset" => rooms::handle_admin_rset(state, player_id, &parts).await,
+        "@path" => travel::handle_admin_path(state, player_id, &parts).await,
+        "@landmark" => travel::handle_admin_landmark(state, player_id, &parts).await,
+        "@promote" => permissions::handle_admin_promote(state, player_id, &parts).await,
+        "@demote" => permissions::handle_admin_demote(state, player_id, &parts).await,
+        "@perms" => permissions::handle_admin_perms(state, player_id, &parts).await,
+        "@audit" => permissions::handle_admin_audit(state, &parts).await,
+        "@lockouts" => accounts::handle_admin_lockouts(state, &parts).await,
+        "@ban" => moderation::handle_admin_ban(state, player_id, &parts).await,
+        "@banplayer" => moderation::handle_admin_banplayer(state, player_id, &parts).await,
+        "@freeze" => moderation::handle_admin_freeze(state, player_id, &parts).await,
+        "@silence" => moderation::handle_admin_silence(state, player_id, &parts).await,
+        "@boot" => moderation::handle_admin_boot(state, player_id, &parts).await,
+        "@bans" => moderation::handle_admin_bans(state, &parts).await,
+        "@generate" => world::handle_admin_generate(state, player_id, &parts).await,
+        "@export" => world_file::handle_admin_export(state, player_id, &parts).await,
+        "@import" => world_file::handle_admin_import(state, &parts).await,
+        "password" => accounts::handle_password(state, player_id, &parts).await,
         "help" => handle_help().await,
         "quit" => "Goodbye!\n".to_string(),
-        _ => "Unknown command. Type 'help' for available commands.\n".to_string(),
+        _ => handle_exit_or_unknown(state, player_id, &parts).await,
     }
 }
 
-// admin commands
-async fn handle_admin_dig(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
-    let player = player_queries::get_player_by_id(&state.db, player_id)
-        .await
-        .unwrap();
-    
-    if player.is_admin == 0 {
-        return "You don't have permission to do that.\n".to_string();
-    }
-    
-    if parts.len() < 4 {
-        return "Usage: @dig <direction> <room_name> <room_description>\n".to_string();
+/// Custom exits ("portal", "gate") can be typed as commands on their own.
+async fn handle_exit_or_unknown(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() == 1 {
+        let player = player_queries::get_player_by_id(&state.db, player_id)
+            .await
+            .unwrap();
+        let exits = state.get_exits(&player.current_location).await.unwrap();
+        let name = parts[0].to_lowercase();
+        if exits.iter().any(|e| e.direction == name) {
+            travel::interrupt(&state, player_id).await;
+            return handle_move(state, player_id, &name).await;
+        }
     }
-    
-    let direction_input = parts[1];
-    let room_name = parts[2];
-    let room_desc = parts[3..].join(" ");
-
-    // parse direction to ensure full string in room exits
-    let direction = match direction_input {
-        "north" | "n" => "north",
-        "south" | "s" => "south",
-        "east" | "e" => "east",
-        "west" | "w" => "west",
-        "up" | "u" => "up",
-        "down" | "d" => "down",
-        _ => "enter",
-    };
-    
-    // Create new room
-    let new_room = state.create_object(room_name, &room_desc, "room", None).await.unwrap();
-    
-    // Add exit from current room to new room
-    state.add_exit(&player.current_location, direction, &new_room.id).await.unwrap();
-    
-    // Add return exit
-    let opposite = match direction {
-        "north" | "n" => "south",
-        "south" | "s" => "north",
-        "east" | "e" => "west",
-        "west" | "w" => "east",
-        "up" | "u" => "down",
-        "down" | "d" => "up",
-        _ => "back",
-    };
-    state.add_exit(&new_room.id, opposite, &player.current_location).await.unwrap();
-    
-    format!("Room created! Exit '{}' added.\n", direction)
-}
 
-async fn handle_admin_create(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
-    let player = player_queries::get_player_by_id(&state.db, player_id)
-        .await
-        .unwrap();
-    
-    if player.is_admin == 0 {
-        return "You don't have permission to do that.\n".to_string();
-    }
-    
-    if parts.len() < 3 {
-        return "Usage: @create <item_name> <description>\n".to_string();
+    if let Some(response) = socials::perform(&state, player_id, parts).await {
+        return response;
     }
-    
-    let item_name = parts[1];
-    let item_desc = parts[2..].join(" ");
-    
-    // Create item in current room
-    state.create_object(item_name, &item_desc, "item", Some(&player.current_location)).await.unwrap();
-    
-    format!("Created '{}'.\n", item_name)
-}
 
-async fn handle_admin_desc(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
-    let player = player_queries::get_player_by_id(&state.db, player_id)
-        .await
-        .unwrap();
-    
-    if player.is_admin == 0 {
-        return "You don't have permission to do that.\n".to_string();
-    }
-    
-    if parts.len() < 2 {
-        return "Usage: @desc <new description>\n".to_string();
-    }
-    
-    let new_desc = parts[1..].join(" ");
-    
-    sqlx::query("UPDATE game_objects SET description = ? WHERE id = ?")
-        .bind(new_desc)
-        .bind(&player.current_location)
-        .execute(&state.db)
-        .await
-        .unwrap();
-    
-    "Room description updated.\n".to_string()
+    "Unknown command. Type 'help' for available commands.\n".to_string()
 }
 
 // regular commands
-async fn handle_look(state: Arc<GameState>, player_id: &str) -> String {
+pub async fn handle_look(state: Arc<GameState>, player_id: &str) -> String {
     let player = player_queries::get_player_by_id(&state.db, player_id)
         .await
         .unwrap();
     let room = state.get_room(&player.current_location).await.unwrap();
     let objects = state.get_objects_in_container(&room.id).await.unwrap();
     let players = state.get_players_in_room(&room.id).await.unwrap();
+    let npcs = state.get_npcs_in_room(&room.id).await.unwrap();
     let exits = state.get_exits(&room.id).await.unwrap();
 
+    scripting::fire(&state, "object", &room.id, ScriptContext {
+        event: "on_look",
+        self_id: &room.id,
+        room_id: &room.id,
+        actor_id: Some(player_id),
+        message: "",
+    }).await;
+
+    if !rooms::can_see(&state, &player, &room).await.unwrap() {
+        return "It is pitch black. You can't see a thing.\n".to_string();
+    }
+
     let mut response = format!("{}\n{}\n", room.name, room.description);
+    if player.is_staff() {
+        response.push_str(&rooms::describe(&room));
+    }
     
     if !objects.is_empty() {
         response.push_str("You see:\n");
@@ -152,46 +193,164 @@ async fn handle_look(state: Arc<GameState>, player_id: &str) -> String {
     
     if !other_players.is_empty() {
         response.push_str("\nPlayers here:\n");
+        let sessions = state.sessions.read().await;
         for p in other_players {
-            response.push_str(&format!("  - {}\n", p.username));
+            match sessions.get(&p.id).and_then(|s| s.pose.as_ref()) {
+                Some(pose) => response.push_str(&format!("  - {} {}\n", p.username, pose)),
+                None => response.push_str(&format!("  - {}\n", p.username)),
+            }
+        }
+    }
+
+    if !npcs.is_empty() {
+        response.push_str("\nAlso here:\n");
+        for n in npcs {
+            response.push_str(&format!("  - {}\n", n.name));
         }
     }
 
-    // Show exits
-    if !exits.is_empty() {
+    // Show exits, leaving hidden ones out
+    let visible: Vec<_> = exits.iter().filter(|e| e.is_hidden == 0).collect();
+    if !visible.is_empty() {
         response.push_str("Exits: ");
-        let exit_list: Vec<String> = exits.iter().map(|(dir, _)| dir.clone()).collect();
+        let exit_list: Vec<String> = visible
+            .iter()
+            .map(|e| match e.is_passable() {
+                true => e.direction.clone(),
+                false => format!("{} (closed {})", e.direction, e.door()),
+            })
+            .collect();
         response.push_str(&exit_list.join(", "));
         response.push_str("\n\n");
     }
     // Show an empty line if no exits
-    if exits.is_empty() {
+    if visible.is_empty() {
         response.push_str("Exits: None");
         response.push_str("\n\n");
     }
 
+    let show_minimap = state.sessions.read().await.get(player_id).is_some_and(|s| s.minimap);
+    if show_minimap {
+        let lines = map::map_lines(&state, &room.id, map::MINIMAP_RADIUS).await.unwrap();
+        return map::beside(&lines, &response) + "\n";
+    }
+
     response
 }
 
-async fn handle_move(state: Arc<GameState>, player_id: &str, direction: &str) -> String {
+async fn handle_look_at(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let target = parts[1..].join(" ").to_lowercase();
+
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+    if let Some(n) = npcs.iter().find(|n| n.matches(&target)) {
+        scripting::fire(&state, "npc", &n.prototype_id.to_string(), ScriptContext {
+            event: "on_look",
+            self_id: &n.id,
+            room_id: &player.current_location,
+            actor_id: Some(player_id),
+            message: "",
+        }).await;
+        return format!("{}\n{}\n{} {}.\n", n.name, n.description, n.name, n.condition());
+    }
+
+    let players = state.get_players_in_room(&player.current_location).await.unwrap();
+    if let Some(p) = players.iter().find(|p| p.username.to_lowercase() == target) {
+        return format!("{}\n{}\n", p.username, p.description);
+    }
+
+    let objects = state.get_objects_in_container(&player.current_location).await.unwrap();
+    if let Some(o) = objects.iter().find(|o| o.name.to_lowercase() == target) {
+        scripting::fire(&state, "object", &o.id, ScriptContext {
+            event: "on_look",
+            self_id: &o.id,
+            room_id: &player.current_location,
+            actor_id: Some(player_id),
+            message: "",
+        }).await;
+        let mut response = format!("{}\n{}\n", o.name, o.description);
+        let contents = state.get_objects_in_container(&o.id).await.unwrap();
+        if !contents.is_empty() {
+            response.push_str("It contains:\n");
+            for item in contents {
+                response.push_str(&format!("  - {}\n", item.name));
+            }
+        }
+        return response;
+    }
+
+    "You don't see that here.\n".to_string()
+}
+
+/// Returns to the starting room, unless the current area forbids it.
+async fn handle_recall(state: Arc<GameState>, player_id: &str) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let room = state.get_room(&player.current_location).await.unwrap();
+    if room.room_flags().has("no-magic") {
+        return "Your prayer fizzles; magic doesn't work here.\n".to_string();
+    }
+    if state.get_room_area(&player.current_location).await.unwrap().no_recall == 1 {
+        return "Your prayer goes unanswered. You can't recall from here.\n".to_string();
+    }
+
+    state
+        .send_to_room(&player.current_location, &format!("{} disappears in a flash of light.\n", player.username), Some(player_id))
+        .await;
+    state.move_player_to_room(player_id, "room_start").await.unwrap();
+    state
+        .send_to_room("room_start", &format!("{} appears in a flash of light.\n", player.username), Some(player_id))
+        .await;
+
+    handle_look(state, player_id).await
+}
+
+pub async fn handle_move(state: Arc<GameState>, player_id: &str, direction: &str) -> String {
     let player = player_queries::get_player_by_id(&state.db, player_id)
         .await
         .unwrap();
     let room = state.get_room(&player.current_location).await.unwrap();
     let exits = state.get_exits(&room.id).await.unwrap();
     
-    if let Some((_, dest)) = exits.iter().find(|(dir, _)| dir == direction) {
+    if let Some(exit) = exits.iter().find(|e| e.direction == direction) {
+        if !exit.is_passable() {
+            return format!("The {} is closed.\n", exit.door());
+        }
+        let dest = &exit.destination_id;
+        let dest_room = state.get_room(dest).await.unwrap();
+
+        // private rooms, water and stamina
+        if let Err(e) = rooms::check_entry(&state, &player, &dest_room).await {
+            return e;
+        }
+
+        // on_enter scripts in the destination may refuse entry
+        if !scripting::fire_room_event(&state, "on_enter", dest, Some(player_id), "").await {
+            return "Something prevents you from going that way.\n".to_string();
+        }
+
         state.move_player_to_room(player_id, dest).await.unwrap();
+        rooms::spend_move_stamina(&state, &player, &dest_room).await.unwrap();
         
         // Notify others in old room
         let msg = format!("{} leaves {}.\n", player.username, direction);
         let _ = state.broadcast_tx.send(msg);
+
+        if let Some(death) = rooms::spring_death_trap(&state, &player, &dest_room).await {
+            if !player.is_staff() {
+                return death;
+            }
+            return handle_look(state.clone(), player_id).await + death.as_str();
+        }
         
         // Auto-look in new room
         handle_look(state.clone(), player_id).await
         // process_command(state.clone(), player_id, "look").await
     } else {
-        format!("You can't go that way.\n")
+        "You can't go that way.\n".to_string()
     }
 }
 
@@ -207,6 +366,7 @@ async fn handle_say(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> S
 
     let broadcast_msg = format!("{} says: {}\n", player.username, message);
     let _ = state.broadcast_tx.send(broadcast_msg.clone());
+    scripting::fire_room_event(&state, "on_say", &player.current_location, Some(player_id), &message).await;
     broadcast_msg
 }
 
@@ -223,14 +383,89 @@ async fn handle_inventory(state: Arc<GameState>, player_id: &str) -> String {
     }
 }
 
+async fn handle_get(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Get what?\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let room_objects = state.get_objects_in_container(&player.current_location).await.unwrap();
+
+    // "get <item> from <container>" reaches into a container in the room
+    let (item_name, source) = match parts.iter().position(|p| p.eq_ignore_ascii_case("from")) {
+        Some(i) if i > 1 && i + 1 < parts.len() => {
+            let container_name = parts[i + 1..].join(" ").to_lowercase();
+            match room_objects.iter().find(|o| o.name.to_lowercase() == container_name) {
+                Some(container) => (parts[1..i].join(" "), container.id.clone()),
+                None => return "You don't see that container here.\n".to_string(),
+            }
+        }
+        _ => (parts[1..].join(" "), player.current_location.clone()),
+    };
+
+    let candidates = if source == player.current_location {
+        room_objects
+    } else {
+        state.get_objects_in_container(&source).await.unwrap()
+    };
+
+    match candidates.iter().find(|o| o.name.to_lowercase() == item_name.to_lowercase()) {
+        Some(item) if item.object_type == "item" => {
+            let allowed = scripting::fire(&state, "object", &item.id, ScriptContext {
+                event: "on_get",
+                self_id: &item.id,
+                room_id: &player.current_location,
+                actor_id: Some(player_id),
+                message: "",
+            }).await;
+            if !allowed {
+                return format!("You can't pick up {}.\n", item.name);
+            }
+
+            object_queries::move_object(&state.db, &item.id, player_id).await.unwrap();
+            let _ = state.broadcast_tx.send(format!("{} picks up {}.\n", player.username, item.name));
+            format!("You pick up {}.\n", item.name)
+        }
+        Some(item) => format!("You can't pick up {}.\n", item.name),
+        None => "You don't see that here.\n".to_string(),
+    }
+}
+
+async fn handle_drop(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Drop what?\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let items = state.get_objects_in_container(player_id).await.unwrap();
+    let item_name = parts[1..].join(" ").to_lowercase();
+
+    match items.iter().find(|o| o.name.to_lowercase() == item_name) {
+        Some(item) => {
+            object_queries::move_object(&state.db, &item.id, &player.current_location).await.unwrap();
+            let _ = state.broadcast_tx.send(format!("{} drops {}.\n", player.username, item.name));
+            format!("You drop {}.\n", item.name)
+        }
+        None => "You aren't carrying that.\n".to_string(),
+    }
+}
+
 async fn handle_stats(state: Arc<GameState>, player_id: &str) -> String {
     let player = player_queries::get_player_by_id(&state.db, player_id)
         .await
         .unwrap();
+    let pronouns = player.pronouns();
 
     format!(
         "\n{}'s Stats\n\
         ================\n\
+        Pronouns: {}/{}/{}\n\
+        Level: {}\n\
+        Gold: {}\n\
         Health: {}/{}\n\
         Stamina: {}/{}\n\
         Dexterity: {}\n\
@@ -238,8 +473,12 @@ async fn handle_stats(state: Arc<GameState>, player_id: &str) -> String {
         Vitality: {}\n\
         Perception: {}\n\
         Willpower: {}\n\
-        Charisma: {}\n",
+        Charisma: {}\n\
+        \n{}\n",
         player.username,
+        pronouns.subject, pronouns.object, pronouns.possessive,
+        player.level,
+        player.gold,
         player.current_health, player.max_health,
         player.current_stamina, player.max_stamina,
         player.dexterity,
@@ -247,23 +486,106 @@ async fn handle_stats(state: Arc<GameState>, player_id: &str) -> String {
         player.vitality,
         player.perception,
         player.willpower,
-        player.charisma
+        player.charisma,
+        player.description
     )
 }
 
 async fn handle_help() -> String {
     "Available commands:\n\
-     - look: Examine your surroundings\n\
-     - move: type in a direction such as 'west' or 'w' if an exit exists\n\
+     - look [target]: Examine your surroundings, or someone or something in them\n\
+     - move: type in a direction such as 'west' or 'w' or 'ne' if an exit exists\n\
+     - go <exit>: Go through any exit, including named ones like 'portal' (which can also be typed on their own)\n\
+     - open/close <direction|door>: Open or close a door\n\
+     - lock/unlock <direction|door>: Lock or unlock a door you have the key for\n\
+     - pick <direction|door>: Try to pick a lock\n\
+     - search: Look for hidden exits\n\
+     - map [radius]: Show a map of the rooms around you; 'map on' or 'map off' toggles a minimap beside room descriptions\n\
+     - recall: Return to the starting room (not from no-recall areas or no-magic rooms)\n\
+     - walkto <room|landmark>: Walk the shortest way to a room, opening doors on the way; with no argument lists landmarks\n\
+     - run <path>: Walk a speedwalk path such as 3n2e or '2n portal e'; quote exit names that read as directions, e.g. \"news\"\n\
+     - stop: Stop walking\n\
      - say <message>: Speak to others in the room\n\
+     - emote <action>: Show an action to the room, e.g. 'emote scratches his head'\n\
+     - pose [text]: Set how others see you in the room, e.g. 'pose is leaning on the bar'; with no text clears it\n\
+     - socials: List socials like smile, bow and wave; use them alone or with a target, e.g. 'bow guard'\n\
+     - tell <player> <message>: Send a private message; players who are offline get it at their next login\n\
+     - reply <message>: Answer the last person who sent you a tell\n\
+     - ignore [player]: Stop or start seeing a player's tells and channel messages; with no name lists who you ignore\n\
+     - afk [message]: Mark yourself away, sending the message to anyone who tells you; type afk again when back\n\
+     - read [number]: Read the message board here, or one post on it\n\
+     - post <subject>: Write a post on the message board here in the line editor\n\
+     - remove <number>: Take one of your posts off the message board here\n\
+     - mail: List your mail. Also: mail send <player> <subject> (opens the editor), mail read <n>, mail delete <n>\n\
+     - who: See who is online, their titles and how long they have been idle\n\
+     - finger <player>: See when a player was last on, when they were created and their plan\n\
+     - title [text]: Set the title shown after your name in who, e.g. 'title the Brave'; with no text clears it\n\
+     - plan: Write the plan others see when they finger you, in the line editor\n\
+     - gossip/ooc/newbie <message>: Talk on a channel; with no message, shows what was said lately\n\
+     - channels: List channels and whether you are on them\n\
+     - channel <join|leave|mute|unmute|history> <name>: Join or leave a channel for good, or mute it until you log out\n\
      - inventory/inv: Check your inventory\n\
+     - get <item> [from <container>]: Pick up an item\n\
+     - drop <item>: Drop an item you are carrying\n\
      - stats/att/score: Check your player stats\n\
+     - attack/kill <npc>: Attack an NPC in the room\n\
+     - talk <npc> [topic]: Talk to an NPC, optionally asking about a topic\n\
+     - list: See what a shopkeeper has for sale\n\
+     - buy <item>: Buy an item from a shopkeeper\n\
+     - password <current> <new>: Change your password\n\
      - quit: Exit the game\n\
      - help: Show this message\n
      
-     Admin ONLY commands:\n\
-     - @dig: Creates a new room. Usage: @ dig <direction> <room_name> <room_description>\n\
+     Staff commands (helpers, builders, admins and owners; '@perms' shows which role each needs):\n\
+     - immortal/imm <message>: Talk on the staff channel\n\
+     - Builders can only change rooms in areas that list them with '@aset <area> builder +<name>'.\n\
+     - last: Shows recent logins, newest first. Usage: last [player] [count]\n\
+     - Names with spaces can be \"quoted\", e.g. @dig north \"Great Hall\" A vaulted hall.\n\
+     - @dig: Creates a new room. Usage: @dig <exit>[:<return_exit>] <room_name> <room_description>\n\
      - @create: Creates a new object. Usage: @create <item_name> <description>\n\
-     - #desc: Updates current room's description. Usage: @desc <description>\n".to_string()
+     - @desc: Sets a description. Usage: @desc <description>, @desc <target> = <description>\n\
+     - @desc with no text opens the line editor on the description. Usage: @desc, @desc <target> =\n\
+     - @desc +: Adds a line to a description. Usage: @desc + <line>, @desc <target> += <line>\n\
+     - @name: Renames a room or object. Usage: @name <here|object> <new name>\n\
+     - @link/@unlink: Adds or removes exits to existing rooms. Usage: @link <exit>[:<return_exit>] <room_id>, @unlink <exit> [both]\n\
+     - @destroy: Destroys an object, NPC or empty room. Usage: @destroy <object|npc|room_id>\n\
+     - @teleport: Moves you or a player. Usage: @teleport [player] <room_id|player>\n\
+     - @examine: Shows ids, type, container and properties. Usage: @examine [here|object|npc]\n\
+     - @oset: Marks an item as a light, boat or breathing aid for dark, water and underwater rooms. Usage: @oset <object> <light|boat|breathing> <on|off>\n\
+     - @clone: Copies an object. Usage: @clone <object>\n\
+     - @find: Searches objects by name. Usage: @find [room|item] <name>\n\
+     - @mcreate: Creates an NPC prototype, shared by every area, so admins only. Usage: @mcreate <npc_name> <description>\n\
+     - @mset: Edits an NPC prototype (admins only). Usage: @mset <prototype_id> <field> <value>\n\
+     - @mlist: Lists NPC prototypes\n\
+     - @mload: Places an NPC in the current room. Usage: @mload <prototype_id>\n\
+     - @reset: Repopulates an area you build in now (every area, for admins). Usage: @reset [area]\n\
+     - @reset item: Keeps an item here. Usage: @reset item <item_name> [in <container>] [max <n>]\n\
+     - @reset npc: Keeps an NPC here. Usage: @reset npc <prototype_id> [max <n>]\n\
+     - @reset list/remove: Shows this room's resets or deletes one. Usage: @reset list [area], @reset remove <reset_id>\n\
+     - @door: Sets up a door on an exit. Usage: @door <direction> <name|key|pick|hidden|remove> [value]\n\
+     - @script: Attaches event scripts. Usage: @script <list|show|set|clear> <here|object|#npc_id> [event] [code] (#npc_id is for admins)\n\
+     - @acreate: Creates an area. Usage: @acreate <name>\n\
+     - @alist/@astat: Lists areas or shows one (default: this room's). Usage: @alist, @astat [area]\n\
+     - @aset: Edits an area. Usage: @aset <area> <levels|reset|safe|norecall|pk|builder|vnums> <value>\n\
+     - @aroom: Moves this room into an area. Usage: @aroom <area>\n\
+     - @social: Shows, adds, edits or deletes a social. Usage: @social <name> [delete | <noarg_actor|noarg_room|actor|target|room> <message>]\n\
+     - @board: Puts a message board in this room. Usage: @board <name> <description>\n\
+     - @rset: Sets this room's flags (dark, indoors, no-combat, no-magic, private, death-trap) or sector. Usage: @rset <flag> <on|off>, @rset sector <type>\n\
+     - @path: Shows the route from here to a room, as a player or an NPC would walk it. Usage: @path [npc] <room_id|landmark|room name>\n\
+     - @landmark: Names this room as a walkto destination. Usage: @landmark <name>|-\n\
+     - @promote/@demote: Raises or lowers a player one rank, or to the role given. Usage: @promote <player> [player|helper|builder|admin|owner]\n\
+     - @perms: Lists which role each command needs, changes one, or grants a player single commands. Usage: @perms, @perms <command> <role>, @perms <player> [+<command>|-<command>]\n\
+     - @audit: Shows recent privileged commands. Usage: @audit [player] [count]\n\
+     - @lockouts: Lists addresses and accounts with recent failed logins, or forgives one. Usage: @lockouts, @lockouts clear <address|name>\n\
+     - Durations below look like 30m, 12h, 7d or 2w; without one a ban lasts until lifted with 'off'.\n\
+     - @ban: Refuses connections from an address or network and disconnects anyone on from there. Usage: @ban <address|network/bits> [duration] [reason], @ban <address> off\n\
+     - @banplayer: Keeps an account from logging in. Usage: @banplayer <player> [duration] [reason], @banplayer <player> off\n\
+     - @freeze: Lets a player log in and look around, but not act. Usage: @freeze <player> [duration] [reason], @freeze <player> off\n\
+     - @silence: Takes a player off the channels. Usage: @silence <player> [duration] [reason], @silence <player> off\n\
+     - @boot: Disconnects a player. Usage: @boot <player> [reason]\n\
+     - @bans: Lists bans, freezes and silences in force. Usage: @bans [site|player|freeze|silence]\n\
+     - @generate: Builds a random area reached by a new exit here. Usage: @generate <grid|maze|cave> <width> <height> [seed <n>] [theme <dungeon|forest|cave|crypt>] [exit <direction>] [area <name>]\n\
+     - @export: Saves an area's rooms, exits, items, NPC prototypes and resets to world/<area>.json. Usage: @export [area]\n\
+     - @import: Loads a world file into its area with fresh ids. Usage: @import <name>\n".to_string()
 
 }
@@ -0,0 +1,77 @@
+// Compass directions, abbreviations and opposites
+
+use crate::config::ServerConfig;
+
+/// The ten compass directions with their abbreviations.
+pub const COMPASS: [(&str, &str); 10] = [
+    ("north", "n"),
+    ("northeast", "ne"),
+    ("east", "e"),
+    ("southeast", "se"),
+    ("south", "s"),
+    ("southwest", "sw"),
+    ("west", "w"),
+    ("northwest", "nw"),
+    ("up", "u"),
+    ("down", "d"),
+];
+
+/// Default opposite pairs; the server config can replace or extend these.
+pub fn default_opposites() -> Vec<(String, String)> {
+    [
+        ("north", "south"),
+        ("northeast", "southwest"),
+        ("east", "west"),
+        ("southeast", "northwest"),
+        ("up", "down"),
+        ("in", "out"),
+        ("enter", "leave"),
+    ]
+    .iter()
+    .map(|(a, b)| (a.to_string(), b.to_string()))
+    .collect()
+}
+
+pub fn is_compass(input: &str) -> bool {
+    let input = input.to_lowercase();
+    COMPASS.iter().any(|(full, short)| *full == input || *short == input)
+}
+
+/// Expands compass abbreviations ("ne" -> "northeast"); other exit names are just lowercased.
+pub fn normalize(input: &str) -> String {
+    let input = input.to_lowercase();
+    COMPASS
+        .iter()
+        .find(|(full, short)| *full == input || *short == input)
+        .map(|(full, _)| full.to_string())
+        .unwrap_or(input)
+}
+
+/// The exit name that leads back, if one is configured for this direction.
+pub fn opposite(config: &ServerConfig, direction: &str) -> Option<String> {
+    let direction = normalize(direction);
+    config.direction_opposites.iter().find_map(|(a, b)| {
+        if *a == direction {
+            Some(b.clone())
+        } else if *b == direction {
+            Some(a.clone())
+        } else {
+            None
+        }
+    })
+}
+
+/// Grid step for the eight flat compass directions (x grows east, y grows south).
+pub fn offset(direction: &str) -> Option<(i32, i32)> {
+    match normalize(direction).as_str() {
+        "north" => Some((0, -1)),
+        "northeast" => Some((1, -1)),
+        "east" => Some((1, 0)),
+        "southeast" => Some((1, 1)),
+        "south" => Some((0, 1)),
+        "southwest" => Some((-1, 1)),
+        "west" => Some((-1, 0)),
+        "northwest" => Some((-1, -1)),
+        _ => None,
+    }
+}
@@ -0,0 +1,221 @@
+// Doors, locks and keys on room exits
+
+use crate::database::{exit_queries, player_queries};
+use crate::game::{permissions, GameState};
+use crate::models::dice_rolls::random_distribution_roll_result;
+use crate::models::{Player, RoomExit};
+use std::sync::Arc;
+
+/// Finds the exit a door command refers to, by direction or door name.
+async fn find_door(state: &Arc<GameState>, player: &Player, target: &str) -> Result<RoomExit, String> {
+    let exits = state.get_exits(&player.current_location).await?;
+    match exits.into_iter().find(|e| e.matches(target)) {
+        Some(exit) if exit.has_door() => Ok(exit),
+        Some(_) => Err("There is no door that way.\n".to_string()),
+        None => Err("You don't see that here.\n".to_string()),
+    }
+}
+
+async fn carries_key(state: &Arc<GameState>, player: &Player, exit: &RoomExit) -> bool {
+    let key_id = match &exit.key_id {
+        Some(id) => id,
+        None => return false,
+    };
+    let items = state.get_objects_in_container(&player.id).await.unwrap_or_default();
+    items.iter().any(|i| &i.id == key_id)
+}
+
+pub async fn handle_door_command(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let verb = parts[0].to_lowercase();
+    if parts.len() < 2 {
+        return format!("{} what?\n", capitalize(&verb));
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let mut exit = match find_door(&state, &player, &parts[1..].join(" ")).await {
+        Ok(exit) => exit,
+        Err(e) => return e,
+    };
+    let door = exit.door().to_string();
+
+    let outcome = match verb.as_str() {
+        "open" => {
+            if exit.is_closed == 0 {
+                return format!("The {} is already open.\n", door);
+            }
+            if exit.is_locked == 1 {
+                return format!("The {} is locked.\n", door);
+            }
+            exit.is_closed = 0;
+            "opens"
+        }
+        "close" => {
+            if exit.is_closed == 1 {
+                return format!("The {} is already closed.\n", door);
+            }
+            exit.is_closed = 1;
+            "closes"
+        }
+        "lock" => {
+            if exit.is_closed == 0 {
+                return format!("You have to close the {} first.\n", door);
+            }
+            if exit.is_locked == 1 {
+                return format!("The {} is already locked.\n", door);
+            }
+            if !carries_key(&state, &player, &exit).await {
+                return "You don't have the key.\n".to_string();
+            }
+            exit.is_locked = 1;
+            "locks"
+        }
+        "unlock" => {
+            if exit.is_locked == 0 {
+                return format!("The {} isn't locked.\n", door);
+            }
+            if !carries_key(&state, &player, &exit).await {
+                return "You don't have the key.\n".to_string();
+            }
+            exit.is_locked = 0;
+            "unlocks"
+        }
+        "pick" => {
+            if exit.is_locked == 0 {
+                return format!("The {} isn't locked.\n", door);
+            }
+            if exit.pick_difficulty <= 0 {
+                return format!("The lock on the {} can't be picked.\n", door);
+            }
+            let roll = random_distribution_roll_result(player.dexterity as f64, 15.0).await;
+            if roll < exit.pick_difficulty {
+                return format!("You fail to pick the lock on the {}.\n", door);
+            }
+            exit.is_locked = 0;
+            "picks the lock on"
+        }
+        _ => return "Unknown door command.\n".to_string(),
+    };
+
+    state.update_door(&exit).await.unwrap();
+    state
+        .send_to_room(
+            &player.current_location,
+            &format!("{} {} the {}.\n", player.username, outcome, door),
+            Some(player_id),
+        )
+        .await;
+
+    let first_person = match outcome {
+        "picks the lock on" => "pick the lock on",
+        other => other.trim_end_matches('s'),
+    };
+    format!("You {} the {}.\n", first_person, door)
+}
+
+/// Looks for hidden exits with a perception roll.
+pub async fn handle_search(state: Arc<GameState>, player_id: &str) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let exits = state.get_exits(&player.current_location).await.unwrap();
+
+    let mut found = Vec::new();
+    for exit in exits.iter().filter(|e| e.is_hidden == 1) {
+        let roll = random_distribution_roll_result(player.perception as f64, 15.0).await;
+        if roll >= 100 {
+            found.push(match &exit.door_name {
+                Some(door) => format!("a hidden {} leading {}", door, exit.direction),
+                None => format!("a hidden exit leading {}", exit.direction),
+            });
+        }
+    }
+
+    if found.is_empty() {
+        "You search but find nothing unusual.\n".to_string()
+    } else {
+        format!("You find {}!\n", found.join(" and "))
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+// admin commands
+pub async fn handle_admin_door(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let usage = "Usage: @door <direction> name <door_name>\n\
+                 \x20      @door <direction> key <item_name>\n\
+                 \x20      @door <direction> pick <difficulty, 0 for unpickable>\n\
+                 \x20      @door <direction> hidden <on|off>\n\
+                 \x20      @door <direction> remove\n";
+
+    if parts.len() < 3 {
+        return usage.to_string();
+    }
+
+    let exits = state.get_exits(&player.current_location).await.unwrap();
+    let mut exit = match exits.into_iter().find(|e| e.matches(parts[1])) {
+        Some(exit) => exit,
+        None => return "There is no exit that way.\n".to_string(),
+    };
+
+    match parts[2].to_lowercase().as_str() {
+        "name" if parts.len() > 3 => {
+            // New doors start closed
+            if !exit.has_door() {
+                exit.is_closed = 1;
+            }
+            exit.door_name = Some(parts[3..].join(" ").to_lowercase());
+        }
+        "key" if parts.len() > 3 => {
+            if !exit.has_door() {
+                return "Give the exit a door first with @door <direction> name <door_name>.\n".to_string();
+            }
+            let name = parts[3..].join(" ").to_lowercase();
+            let mut items = state.get_objects_in_container(player_id).await.unwrap();
+            items.extend(state.get_objects_in_container(&player.current_location).await.unwrap());
+            match items.into_iter().find(|i| i.name.to_lowercase() == name) {
+                Some(key) => exit.key_id = Some(key.id),
+                None => return "You need to carry the key or have it in the room.\n".to_string(),
+            }
+        }
+        "pick" if parts.len() > 3 => match parts[3].parse::<i64>() {
+            Ok(n) if n >= 0 => exit.pick_difficulty = n,
+            _ => return "Difficulty must be a positive number.\n".to_string(),
+        },
+        "hidden" if parts.len() > 3 => exit.is_hidden = matches!(parts[3], "on" | "yes" | "1") as i64,
+        "remove" => {
+            exit.door_name = None;
+            exit.is_closed = 0;
+            exit.is_locked = 0;
+            exit.key_id = None;
+            exit.pick_difficulty = 0;
+        }
+        _ => return usage.to_string(),
+    }
+
+    // The other side may be in an area this builder can't change; leave it alone then
+    let reverse = exit_queries::get_reverse_exit(&state.db, &exit).await.unwrap();
+    if let Some(reverse) = reverse {
+        if !permissions::can_build_in(&state, &player, &reverse.room_id).await.unwrap() {
+            exit_queries::update_door(&state.db, &exit).await.unwrap();
+            return format!(
+                "Exit {} updated. The way back is in an area you can't build in, so it was left as it was.\n",
+                exit.direction
+            );
+        }
+    }
+
+    state.update_door(&exit).await.unwrap();
+    format!("Exit {} updated.\n", exit.direction)
+}
@@ -0,0 +1,124 @@
+// Line-editor mode for composing multi-line text
+
+use crate::database::{object_queries, player_queries};
+use crate::game::{mail, moderation, permissions, scripting, GameState};
+use crate::models::{EditTarget, LineEditor};
+use std::sync::Arc;
+
+const EDITOR_HELP: &str = "Editor commands:\n\
+    .s      - save and exit\n\
+    .q      - abort without saving\n\
+    .l      - list the buffer\n\
+    .d N    - delete line N\n\
+    .i N x  - insert x before line N\n\
+    .f      - reflow the text\n\
+    .h      - this help\n\
+    Anything else is added as a new line.\n";
+
+pub async fn is_editing(state: &Arc<GameState>, player_id: &str) -> bool {
+    let sessions = state.sessions.read().await;
+    sessions.get(player_id).is_some_and(|s| s.editor.is_some())
+}
+
+/// Puts a player's session into editor mode, starting from `initial`.
+pub async fn start(state: &Arc<GameState>, player_id: &str, target: EditTarget, initial: &str) -> String {
+    let editor = LineEditor::new(target, initial);
+    let listing = editor.listing();
+
+    let mut sessions = state.sessions.write().await;
+    match sessions.get_mut(player_id) {
+        Some(session) => {
+            session.editor = Some(editor);
+            format!(
+                "Entering the editor. Type .h for help, .s to save, .q to abort.\n{}",
+                listing
+            )
+        }
+        None => "You need to be connected to use the editor.\n".to_string(),
+    }
+}
+
+/// Handles one line of input while a player is in editor mode.
+pub async fn handle_input(state: Arc<GameState>, player_id: &str, line: &str) -> String {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let mut parts = line.trim().splitn(3, ' ');
+    let command = parts.next().unwrap_or("");
+
+    let mut sessions = state.sessions.write().await;
+    let session = match sessions.get_mut(player_id) {
+        Some(session) => session,
+        None => return String::new(),
+    };
+    let editor = match session.editor.as_mut() {
+        Some(editor) => editor,
+        None => return String::new(),
+    };
+
+    match command {
+        ".s" => {
+            let editor = session.editor.take().unwrap();
+            drop(sessions);
+            save(&state, player_id, editor).await
+        }
+        ".q" => {
+            session.editor = None;
+            "Aborted. Nothing was saved.\n".to_string()
+        }
+        ".l" => editor.listing(),
+        ".h" => EDITOR_HELP.to_string(),
+        ".f" => {
+            editor.reflow();
+            editor.listing()
+        }
+        ".d" => match parts.next().and_then(|n| n.parse().ok()) {
+            Some(n) => match editor.delete(n) {
+                Ok(removed) => format!("Deleted line {}: {}\n", n, removed),
+                Err(e) => format!("{}\n", e),
+            },
+            None => "Usage: .d <line number>\n".to_string(),
+        },
+        ".i" => match parts.next().and_then(|n| n.parse().ok()) {
+            Some(n) => match editor.insert(n, parts.next().unwrap_or("")) {
+                Ok(()) => format!("Inserted at line {}.\n", n),
+                Err(e) => format!("{}\n", e),
+            },
+            None => "Usage: .i <line number> <text>\n".to_string(),
+        },
+        c if c.starts_with('.') && c.len() == 2 => "Unknown editor command. Type .h for help.\n".to_string(),
+        _ => match editor.append(line) {
+            Ok(()) => String::new(),
+            Err(e) => format!("{}\n", e),
+        },
+    }
+}
+
+/// Saves the buffer, checking again that the player may: they may have been frozen, or lost
+/// building rights, since they started.
+async fn save(state: &Arc<GameState>, player_id: &str, editor: LineEditor) -> String {
+    if let Some(refusal) = moderation::frozen_refusal(state, player_id, ".s").await {
+        return format!("{}Nothing was saved.\n", refusal);
+    }
+    let text = editor.text();
+
+    match editor.target {
+        EditTarget::Description { object_id } => {
+            let player = player_queries::get_player_by_id(&state.db, player_id).await.unwrap();
+            let room_id = scripting::object_room(state, &object_id).await.unwrap_or(player.current_location.clone());
+            let allowed = permissions::can_use(state, &player, "@desc").await.unwrap_or(false)
+                && permissions::can_build_in(state, &player, &room_id).await.unwrap_or(false);
+            if !allowed {
+                return "You can no longer change that description. Nothing was saved.\n".to_string();
+            }
+            match object_queries::update_object_description(&state.db, &object_id, &text).await {
+                Ok(()) => "Description saved.\n".to_string(),
+                Err(e) => format!("Error: {}\n", e),
+            }
+        }
+        EditTarget::BoardPost { board_id, subject } => mail::save_post(state, player_id, &board_id, &subject, &text).await,
+        EditTarget::Mail { recipient_id, subject } => mail::save_mail(state, player_id, &recipient_id, &subject, &text).await,
+        EditTarget::Plan { player_id } => match player_queries::update_player_plan(&state.db, &player_id, &text).await {
+            Ok(()) => "Plan saved.\n".to_string(),
+            Err(e) => format!("Error: {}\n", e),
+        },
+    }
+}
@@ -0,0 +1,253 @@
+// Bulletin boards and player mail
+
+use crate::database::{mail_queries, player_queries};
+use crate::game::{editor, GameState};
+use crate::models::{EditTarget, GameObject};
+use std::sync::Arc;
+
+/// Most mail a player's mailbox holds.
+const MAX_MAILBOX: usize = 50;
+const MAX_SUBJECT_LENGTH: usize = 60;
+
+fn format_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%b %d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+async fn find_board(state: &Arc<GameState>, room_id: &str) -> Option<GameObject> {
+    let objects = state.get_objects_in_container(room_id).await.ok()?;
+    objects.into_iter().find(|o| o.object_type == "board")
+}
+
+fn check_subject(subject: &str) -> Result<(), String> {
+    if subject.is_empty() {
+        return Err("You need a subject.\n".to_string());
+    }
+    if subject.chars().count() > MAX_SUBJECT_LENGTH {
+        return Err(format!("Please keep the subject under {} characters.\n", MAX_SUBJECT_LENGTH));
+    }
+    Ok(())
+}
+
+/// Saves a finished board post from the editor.
+pub async fn save_post(state: &Arc<GameState>, player_id: &str, board_id: &str, subject: &str, body: &str) -> String {
+    if body.trim().is_empty() {
+        return "Your post was empty, so nothing was posted.\n".to_string();
+    }
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    match mail_queries::create_board_post(&state.db, board_id, player_id, &player.username, subject, body).await {
+        Ok(()) => {
+            state
+                .send_to_room(&player.current_location, &format!("{} pins a note to the board.\n", player.username), Some(player_id))
+                .await;
+            "Posted.\n".to_string()
+        }
+        Err(e) => format!("Error: {}\n", e),
+    }
+}
+
+/// Sends finished mail from the editor.
+pub async fn save_mail(state: &Arc<GameState>, player_id: &str, recipient_id: &str, subject: &str, body: &str) -> String {
+    if body.trim().is_empty() {
+        return "Your letter was empty, so nothing was sent.\n".to_string();
+    }
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    match mail_queries::send_mail(&state.db, recipient_id, player_id, &player.username, subject, body).await {
+        Ok(()) => {
+            state.send_to_player(recipient_id, &format!("You have new mail from {}.\n", player.username)).await;
+            "Mail sent.\n".to_string()
+        }
+        Err(e) => format!("Error: {}\n", e),
+    }
+}
+
+/// The unread mail line shown at login, if there is any.
+pub async fn login_notice(state: &Arc<GameState>, player_id: &str) -> Result<String, String> {
+    let unread = mail_queries::count_unread_mail(&state.db, player_id).await?;
+    Ok(match unread {
+        0 => String::new(),
+        1 => "You have 1 unread mail message. Type 'mail' to see it.\n".to_string(),
+        n => format!("You have {} unread mail messages. Type 'mail' to see them.\n", n),
+    })
+}
+
+// regular commands
+/// `read` lists the posts on the board here; `read <n>` shows one.
+pub async fn handle_read(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let board = match find_board(&state, &player.current_location).await {
+        Some(b) => b,
+        None => return "There is no board here.\n".to_string(),
+    };
+    let posts = mail_queries::get_board_posts(&state.db, &board.id).await.unwrap();
+
+    match parts.get(1).map(|n| n.parse::<usize>()) {
+        None => {
+            if posts.is_empty() {
+                return format!("{} is empty.\n", board.name);
+            }
+            let mut response = format!("{}:\n", board.name);
+            for (i, post) in posts.iter().enumerate() {
+                response.push_str(&format!(
+                    "  {:>3}. {:<40} {:<12} {}\n",
+                    i + 1,
+                    post.subject,
+                    post.author_name,
+                    format_time(post.posted_at)
+                ));
+            }
+            response.push_str("Type 'read <number>' to read a post.\n");
+            response
+        }
+        Some(Ok(n)) if n >= 1 && n <= posts.len() => {
+            let post = &posts[n - 1];
+            format!(
+                "Post {} by {} on {}\nSubject: {}\n\n{}\n",
+                n,
+                post.author_name,
+                format_time(post.posted_at),
+                post.subject,
+                post.body
+            )
+        }
+        _ => "There is no post with that number.\n".to_string(),
+    }
+}
+
+/// `post <subject>` opens the editor to write a post on the board here.
+pub async fn handle_post(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let board = match find_board(&state, &player.current_location).await {
+        Some(b) => b,
+        None => return "There is no board here.\n".to_string(),
+    };
+
+    let subject = parts[1..].join(" ");
+    if let Err(e) = check_subject(&subject) {
+        return format!("{}Usage: post <subject>\n", e);
+    }
+
+    let target = EditTarget::BoardPost { board_id: board.id, subject };
+    editor::start(&state, player_id, target, "").await
+}
+
+/// `remove <n>` takes down a post; authors can remove their own, staff any.
+pub async fn handle_remove(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let board = match find_board(&state, &player.current_location).await {
+        Some(b) => b,
+        None => return "There is no board here.\n".to_string(),
+    };
+    let posts = mail_queries::get_board_posts(&state.db, &board.id).await.unwrap();
+
+    let post = match parts.get(1).and_then(|n| n.parse::<usize>().ok()) {
+        Some(n) if n >= 1 && n <= posts.len() => &posts[n - 1],
+        Some(_) => return "There is no post with that number.\n".to_string(),
+        None => return "Usage: remove <post number>\n".to_string(),
+    };
+    if post.author_id != player.id && !player.is_staff() {
+        return "You can only remove your own posts.\n".to_string();
+    }
+
+    mail_queries::delete_board_post(&state.db, post.id).await.unwrap();
+    format!("Removed '{}'.\n", post.subject)
+}
+
+/// `mail` lists the mailbox; `mail send <player> <subject>`, `mail read <n>` and
+/// `mail delete <n>` do the rest.
+pub async fn handle_mail(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let mailbox = mail_queries::get_mailbox(&state.db, player_id).await.unwrap();
+    let number = parts.get(2).and_then(|n| n.parse::<usize>().ok());
+
+    match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+        None | Some("list") => {
+            if mailbox.is_empty() {
+                return "Your mailbox is empty.\n".to_string();
+            }
+            let mut response = "Your mail:\n".to_string();
+            for (i, mail) in mailbox.iter().enumerate() {
+                response.push_str(&format!(
+                    "  {:>3}. {} {:<40} {:<12} {}\n",
+                    i + 1,
+                    if mail.is_read == 0 { "*" } else { " " },
+                    mail.subject,
+                    mail.sender_name,
+                    format_time(mail.sent_at)
+                ));
+            }
+            response.push_str("(* unread) Type 'mail read <number>' to read a letter.\n");
+            response
+        }
+        Some("read") => match number {
+            Some(n) if n >= 1 && n <= mailbox.len() => {
+                let mail = &mailbox[n - 1];
+                mail_queries::mark_mail_read(&state.db, mail.id).await.unwrap();
+                format!(
+                    "From: {}\nDate: {}\nSubject: {}\n\n{}\n",
+                    mail.sender_name,
+                    format_time(mail.sent_at),
+                    mail.subject,
+                    mail.body
+                )
+            }
+            _ => "There is no letter with that number.\n".to_string(),
+        },
+        Some("delete") => match number {
+            Some(n) if n >= 1 && n <= mailbox.len() => {
+                mail_queries::delete_mail(&state.db, mailbox[n - 1].id).await.unwrap();
+                format!("Deleted '{}'.\n", mailbox[n - 1].subject)
+            }
+            _ => "There is no letter with that number.\n".to_string(),
+        },
+        Some("send") if parts.len() >= 4 => {
+            let recipient = match player_queries::find_player_by_username(&state.db, parts[2]).await.unwrap() {
+                Some(p) => p,
+                None => return "There is no player by that name.\n".to_string(),
+            };
+            let subject = parts[3..].join(" ");
+            if let Err(e) = check_subject(&subject) {
+                return e;
+            }
+            let recipient_box = mail_queries::get_mailbox(&state.db, &recipient.id).await.unwrap();
+            if recipient_box.len() >= MAX_MAILBOX {
+                return format!("{}'s mailbox is full.\n", recipient.username);
+            }
+
+            let target = EditTarget::Mail { recipient_id: recipient.id, subject };
+            editor::start(&state, player_id, target, "").await
+        }
+        _ => "Usage: mail [list], mail send <player> <subject>, mail read <n>, mail delete <n>\n".to_string(),
+    }
+}
+
+// admin commands
+/// Puts a new message board in the room.
+pub async fn handle_admin_board(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if parts.len() < 3 {
+        return "Usage: @board <name> <description>\n".to_string();
+    }
+
+    let name = parts[1].replace('_', " ");
+    state
+        .create_object(&name, &parts[2..].join(" "), "board", Some(&player.current_location))
+        .await
+        .unwrap();
+    format!("Created board '{}'. Players can 'read', 'post' and 'remove' here.\n", name)
+}
@@ -0,0 +1,177 @@
+// ASCII maps built from room exits
+
+use crate::database::player_queries;
+use crate::game::{directions, GameState};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// Default and largest number of rooms the map reaches out from the player.
+pub const MAP_RADIUS: i32 = 4;
+pub const MAX_MAP_RADIUS: i32 = 8;
+/// Radius of the minimap shown beside room descriptions.
+pub const MINIMAP_RADIUS: i32 = 2;
+
+const LEGEND: &str = "@ you  ^ up  v down  | up and down  + door\n";
+
+struct MapRoom {
+    is_player: bool,
+    up: bool,
+    down: bool,
+    links: Vec<((i32, i32), bool)>, // neighbouring cell, and whether a door is in the way
+}
+
+/// Walks compass exits breadth-first from `start`, giving each room a grid cell. Rooms that
+/// would land on an already mapped cell (non-euclidean layouts) are left off.
+async fn explore(state: &Arc<GameState>, start: &str, radius: i32) -> Result<HashMap<(i32, i32), MapRoom>, String> {
+    let mut cells: HashMap<(i32, i32), MapRoom> = HashMap::new();
+    let mut placed: HashMap<String, (i32, i32)> = HashMap::from([(start.to_string(), (0, 0))]);
+    let mut queue = VecDeque::from([(start.to_string(), (0, 0))]);
+
+    while let Some((room_id, (x, y))) = queue.pop_front() {
+        let exits = state.get_exits(&room_id).await?;
+        let mut room = MapRoom {
+            is_player: room_id == start,
+            up: false,
+            down: false,
+            links: Vec::new(),
+        };
+
+        for exit in exits.iter().filter(|e| e.is_hidden == 0) {
+            match exit.direction.as_str() {
+                "up" => room.up = true,
+                "down" => room.down = true,
+                _ => {}
+            }
+            let (dx, dy) = match directions::offset(&exit.direction) {
+                Some(step) => step,
+                None => continue,
+            };
+            let cell = (x + dx, y + dy);
+            if cell.0.abs() > radius || cell.1.abs() > radius {
+                continue;
+            }
+
+            match placed.get(&exit.destination_id) {
+                Some(&at) if at == cell => room.links.push((cell, exit.has_door())),
+                Some(_) => {}
+                None if placed.values().any(|&at| at == cell) => {}
+                None => {
+                    placed.insert(exit.destination_id.clone(), cell);
+                    queue.push_back((exit.destination_id.clone(), cell));
+                    room.links.push((cell, exit.has_door()));
+                }
+            }
+        }
+
+        cells.insert((x, y), room);
+    }
+
+    Ok(cells)
+}
+
+/// Draws the mapped rooms as `[ ]` cells joined by `-`, `|`, `/` and `\`, trimmed to the rooms found.
+fn render(cells: &HashMap<(i32, i32), MapRoom>) -> Vec<String> {
+    let min_x = cells.keys().map(|c| c.0).min().unwrap_or(0);
+    let max_x = cells.keys().map(|c| c.0).max().unwrap_or(0);
+    let min_y = cells.keys().map(|c| c.1).min().unwrap_or(0);
+    let max_y = cells.keys().map(|c| c.1).max().unwrap_or(0);
+
+    let width = ((max_x - min_x + 1) * 4) as usize;
+    let height = ((max_y - min_y) * 2 + 1) as usize;
+    let mut grid = vec![vec![' '; width]; height];
+
+    // text position of a room's centre character
+    let at = |(x, y): (i32, i32)| (((x - min_x) * 4 + 1) as usize, ((y - min_y) * 2) as usize);
+
+    for (&cell, room) in cells {
+        let (col, row) = at(cell);
+        grid[row][col - 1] = '[';
+        grid[row][col + 1] = ']';
+        grid[row][col] = match (room.is_player, room.up, room.down) {
+            (true, _, _) => '@',
+            (_, true, true) => '|',
+            (_, true, false) => '^',
+            (_, false, true) => 'v',
+            _ => ' ',
+        };
+
+        for &(other, door) in &room.links {
+            let (other_col, other_row) = at(other);
+            let col = (col + other_col) / 2;
+            let row = (row + other_row) / 2;
+            let line = match (other.0 - cell.0, other.1 - cell.1) {
+                _ if door => '+',
+                (0, _) => '|',
+                (_, 0) => '-',
+                (dx, dy) if dx == dy => '\\',
+                _ => '/',
+            };
+            grid[row][col] = match (grid[row][col], line) {
+                ('\\', '/') | ('/', '\\') => 'X',
+                _ => line,
+            };
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+/// Map lines around a room, for the `map` command or the minimap.
+pub async fn map_lines(state: &Arc<GameState>, room_id: &str, radius: i32) -> Result<Vec<String>, String> {
+    let cells = explore(state, room_id, radius).await?;
+    Ok(render(&cells))
+}
+
+/// Puts a minimap to the left of a room description.
+pub fn beside(map: &[String], text: &str) -> String {
+    let map_width = map.iter().map(|l| l.chars().count()).max().unwrap_or(0) + 2;
+    let text_lines: Vec<&str> = text.lines().collect();
+    let rows = map.len().max(text_lines.len());
+
+    let mut out = String::new();
+    for i in 0..rows {
+        let left = map.get(i).map(String::as_str).unwrap_or("");
+        let right = text_lines.get(i).copied().unwrap_or("");
+        let line = format!("{:<width$}{}", left, right, width = map_width);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+// regular commands
+pub async fn handle_map(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+        Some("on") | Some("off") => {
+            let enabled = parts[1].eq_ignore_ascii_case("on");
+            let mut sessions = state.sessions.write().await;
+            if let Some(session) = sessions.get_mut(player_id) {
+                session.minimap = enabled;
+            }
+            return if enabled {
+                "The minimap will be shown beside room descriptions.\n".to_string()
+            } else {
+                "Minimap hidden.\n".to_string()
+            };
+        }
+        _ => {}
+    }
+
+    let radius = match parts.get(1).map(|p| p.parse::<i32>()) {
+        Some(Ok(r)) if (1..=MAX_MAP_RADIUS).contains(&r) => r,
+        Some(_) => return format!("Usage: map [1-{}] or map on|off\n", MAX_MAP_RADIUS),
+        None => MAP_RADIUS,
+    };
+
+    let lines = map_lines(&state, &player.current_location, radius).await.unwrap();
+    let mut response = lines.join("\n");
+    response.push('\n');
+    response.push_str(LEGEND);
+    response
+}
@@ -3,6 +3,31 @@
 pub mod state;
 pub mod commands;
 pub mod world;
+pub mod npc;
+pub mod combat;
+pub mod resets;
+pub mod behavior;
+pub mod tick;
+pub mod scripting;
+pub mod doors;
+pub mod directions;
+pub mod building;
+pub mod editor;
+pub mod world_file;
+pub mod areas;
+pub mod map;
+pub mod pathfinding;
+pub mod travel;
+pub mod rooms;
+pub mod channels;
+pub mod tells;
+pub mod socials;
+pub mod mail;
+pub mod who;
+pub mod permissions;
+pub mod accounts;
+pub mod validation;
+pub mod moderation;
 
 pub use state::GameState;
 pub use commands::process_command;
@@ -0,0 +1,333 @@
+// Site bans, player bans, freezes, silences and booting players off
+
+use crate::database::{player_queries, sanction_queries};
+use crate::game::who::format_date;
+use crate::game::{travel, GameState};
+use crate::models::sanction::{format_network, network_contains, parse_network};
+use crate::models::{Player, Role, Sanction, SanctionKind};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// What a frozen player can still do.
+const FROZEN_COMMANDS: &[&str] = &["look", "l", "who", "help", "stop", "quit"];
+
+fn no_permission() -> String {
+    "You don't have permission to do that.\n".to_string()
+}
+
+/// Seconds in durations like "30m", "12h", "7d" or "2w".
+fn parse_duration(text: &str) -> Option<i64> {
+    let unit = text.chars().last()?;
+    let number: i64 = text[..text.len() - unit.len_utf8()].parse().ok()?;
+    let seconds = match unit.to_ascii_lowercase() {
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        'w' => 604800,
+        _ => return None,
+    };
+    if number <= 0 {
+        return None;
+    }
+    number.checked_mul(seconds)
+}
+
+/// Splits the words after the target into an expiry time and a reason. Without a duration,
+/// or with "perm", the sanction lasts until it is lifted.
+fn parse_terms(args: &[&str]) -> (Option<i64>, String) {
+    match args.first() {
+        Some(word) if word.eq_ignore_ascii_case("perm") => (None, args[1..].join(" ")),
+        Some(word) => match parse_duration(word) {
+            Some(secs) => (Some(chrono::Utc::now().timestamp() + secs), args[1..].join(" ")),
+            None => (None, args.join(" ")),
+        },
+        None => (None, String::new()),
+    }
+}
+
+fn describe_expiry(expires_at: Option<i64>) -> String {
+    match expires_at {
+        Some(t) => format!("until {}", format_date(t)),
+        None => "until further notice".to_string(),
+    }
+}
+
+fn describe_reason(reason: &str) -> String {
+    match reason.is_empty() {
+        true => String::new(),
+        false => format!(": {}", reason),
+    }
+}
+
+/// The site ban covering an address, if there is one.
+pub async fn site_ban(state: &Arc<GameState>, address: &IpAddr) -> Option<Sanction> {
+    match sanction_queries::get_active_sanctions(&state.db, Some(SanctionKind::Site.as_str())).await {
+        Ok(bans) => bans.into_iter().find(|b| network_contains(&b.target, address)),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+async fn player_sanction(state: &Arc<GameState>, kind: SanctionKind, player_id: &str) -> Option<Sanction> {
+    match sanction_queries::get_active_sanction(&state.db, kind.as_str(), player_id).await {
+        Ok(sanction) => sanction,
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+/// Refuses a login for a banned account.
+pub async fn check_login(state: &Arc<GameState>, player: &Player) -> Result<(), String> {
+    match player_sanction(state, SanctionKind::Player, &player.id).await {
+        Some(ban) => Err(format!("You are banned {}{}.", describe_expiry(ban.expires_at), describe_reason(&ban.reason))),
+        None => Ok(()),
+    }
+}
+
+/// Checked before every command. Returns the refusal if the player is frozen.
+pub async fn frozen_refusal(state: &Arc<GameState>, player_id: &str, command: &str) -> Option<String> {
+    if FROZEN_COMMANDS.contains(&command.to_lowercase().as_str()) {
+        return None;
+    }
+    let freeze = player_sanction(state, SanctionKind::Freeze, player_id).await?;
+    Some(format!("You are frozen {} and can't do that.\n", describe_expiry(freeze.expires_at)))
+}
+
+/// Returns the refusal if the player may not talk on channels.
+pub async fn silenced_refusal(state: &Arc<GameState>, player_id: &str) -> Option<String> {
+    let silence = player_sanction(state, SanctionKind::Silence, player_id).await?;
+    Some(format!("You are silenced {} and can't use channels.\n", describe_expiry(silence.expires_at)))
+}
+
+/// Sends a player a last message and disconnects them. Returns false if they aren't online.
+pub async fn boot(state: &Arc<GameState>, player_id: &str, message: &str) -> bool {
+    let sessions = state.sessions.read().await;
+    match sessions.get(player_id) {
+        Some(session) => {
+            let _ = session.tx.send(message.to_string());
+            session.boot.notify_one();
+            true
+        }
+        None => false,
+    }
+}
+
+/// The player a sanction is aimed at. Only owners can act on someone of their own rank or higher.
+async fn find_target(state: &Arc<GameState>, actor: &Player, name: &str) -> Result<Player, String> {
+    let target = match player_queries::find_player_by_username(&state.db, name).await? {
+        Some(p) => p,
+        None => return Err("There is no player by that name.\n".to_string()),
+    };
+    if target.id == actor.id {
+        return Err("You can't do that to yourself.\n".to_string());
+    }
+    if actor.role() != Role::Owner && target.role() >= actor.role() {
+        return Err(no_permission());
+    }
+    Ok(target)
+}
+
+/// Shared by @banplayer, @freeze and @silence.
+async fn handle_player_sanction(state: Arc<GameState>, player_id: &str, parts: &[&str], kind: SanctionKind) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if parts.len() < 2 {
+        return format!(
+            "Usage: {} <player> [duration] [reason], {} <player> off\nDurations look like 30m, 12h, 7d or 2w; without one it lasts until lifted.\n",
+            parts[0], parts[0]
+        );
+    }
+    let target = match find_target(&state, &player, parts[1]).await {
+        Ok(p) => p,
+        Err(e) => return e,
+    };
+
+    if parts.len() == 3 && parts[2].eq_ignore_ascii_case("off") {
+        let lifted = sanction_queries::remove_sanction(&state.db, kind.as_str(), &target.id)
+            .await
+            .unwrap();
+        if !lifted {
+            return format!("{} isn't {}.\n", target.username, kind.label());
+        }
+        state.send_to_player(&target.id, &format!("You are no longer {}.\n", kind.label())).await;
+        return format!("{} is no longer {}.\n", target.username, kind.label());
+    }
+
+    let (expires_at, reason) = parse_terms(&parts[2..]);
+    sanction_queries::add_sanction(&state.db, kind.as_str(), &target.id, &target.username, &reason, &player.username, expires_at)
+        .await
+        .unwrap();
+
+    let notice = format!("You have been {} {}{}.\n", kind.label(), describe_expiry(expires_at), describe_reason(&reason));
+    match kind {
+        SanctionKind::Player => {
+            boot(&state, &target.id, &notice).await;
+        }
+        SanctionKind::Freeze => {
+            travel::interrupt(&state, &target.id).await;
+            state.send_to_player(&target.id, &notice).await;
+        }
+        _ => {
+            state.send_to_player(&target.id, &notice).await;
+        }
+    }
+    format!("{} is now {} {}.\n", target.username, kind.label(), describe_expiry(expires_at))
+}
+
+// admin commands
+/// `@ban <address|network/bits> [duration] [reason]` refuses connections from an address or
+/// network and disconnects anyone already on from there; `@ban <address> off` lifts it.
+pub async fn handle_admin_ban(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let usage = "Usage: @ban <address|network/bits> [duration] [reason], @ban <address|network/bits> off\n";
+    if parts.len() < 2 {
+        return usage.to_string();
+    }
+    let network = match parse_network(parts[1]) {
+        Some(n) => format_network(n),
+        None => return format!("'{}' isn't an address or network.\n{}", parts[1], usage),
+    };
+
+    if parts.len() == 3 && parts[2].eq_ignore_ascii_case("off") {
+        return match sanction_queries::remove_sanction(&state.db, SanctionKind::Site.as_str(), &network).await.unwrap() {
+            true => format!("Connections from {} are allowed again.\n", network),
+            false => format!("{} isn't banned.\n", network),
+        };
+    }
+
+    let online: Vec<(String, IpAddr)> = {
+        let sessions = state.sessions.read().await;
+        sessions.values().map(|s| (s.player_id.clone(), s.address)).collect()
+    };
+    if online.iter().any(|(id, address)| *id == player.id && network_contains(&network, address)) {
+        return "That would ban your own address.\n".to_string();
+    }
+
+    let (expires_at, reason) = parse_terms(&parts[2..]);
+    sanction_queries::add_sanction(&state.db, SanctionKind::Site.as_str(), &network, &network, &reason, &player.username, expires_at)
+        .await
+        .unwrap();
+
+    let mut booted = 0;
+    for (id, address) in online {
+        if network_contains(&network, &address) && boot(&state, &id, "Connections from your address are banned.\n").await {
+            booted += 1;
+        }
+    }
+    let mut response = format!("Connections from {} are banned {}.\n", network, describe_expiry(expires_at));
+    if booted > 0 {
+        response.push_str(&format!("Disconnected {} player{} already on from there.\n", booted, if booted == 1 { "" } else { "s" }));
+    }
+    response
+}
+
+/// `@banplayer <player> [duration] [reason]` keeps an account from logging in.
+pub async fn handle_admin_banplayer(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    handle_player_sanction(state, player_id, parts, SanctionKind::Player).await
+}
+
+/// `@freeze <player> [duration] [reason]` lets a player log in and look around, but not act.
+pub async fn handle_admin_freeze(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    handle_player_sanction(state, player_id, parts, SanctionKind::Freeze).await
+}
+
+/// `@silence <player> [duration] [reason]` takes a player off the channels.
+pub async fn handle_admin_silence(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    handle_player_sanction(state, player_id, parts, SanctionKind::Silence).await
+}
+
+/// `@boot <player> [reason]` disconnects a player. They can log straight back in.
+pub async fn handle_admin_boot(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if parts.len() < 2 {
+        return "Usage: @boot <player> [reason]\n".to_string();
+    }
+    let target = match find_target(&state, &player, parts[1]).await {
+        Ok(p) => p,
+        Err(e) => return e,
+    };
+
+    let reason = parts[2..].join(" ");
+    let notice = format!("You have been disconnected by {}{}.\n", player.username, describe_reason(&reason));
+    match boot(&state, &target.id, &notice).await {
+        true => format!("{} has been disconnected.\n", target.username),
+        false => format!("{} isn't online.\n", target.username),
+    }
+}
+
+/// `@bans [site|player|freeze|silence]` lists bans, freezes and silences still in force.
+pub async fn handle_admin_bans(state: Arc<GameState>, parts: &[&str]) -> String {
+    let kind = match parts.get(1) {
+        Some(name) => match SanctionKind::parse(name) {
+            Some(k) => Some(k),
+            None => return "Usage: @bans [site|player|freeze|silence]\n".to_string(),
+        },
+        None => None,
+    };
+
+    let sanctions = sanction_queries::get_active_sanctions(&state.db, kind.map(|k| k.as_str()))
+        .await
+        .unwrap();
+    if sanctions.is_empty() {
+        return "No bans, freezes or silences in force.\n".to_string();
+    }
+
+    let mut response = "In force:\n".to_string();
+    for s in sanctions {
+        let expires = match s.expires_at {
+            Some(t) => format_date(t),
+            None => "never".to_string(),
+        };
+        response.push_str(&format!(
+            "  {:<9} {:<20} expires {:<17} by {:<12} {}\n",
+            s.kind().label(),
+            s.target_name,
+            expires,
+            s.issued_by,
+            if s.reason.is_empty() { "(no reason)" } else { &s.reason }
+        ));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30m"), Some(1800));
+        assert_eq!(parse_duration("12H"), Some(43200));
+        assert_eq!(parse_duration("7d"), Some(604800));
+        assert_eq!(parse_duration("2w"), Some(1209600));
+        assert_eq!(parse_duration("0d"), None);
+        assert_eq!(parse_duration("-1d"), None);
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration("spamming"), None);
+        assert_eq!(parse_duration("3é"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn terms_split_into_expiry_and_reason() {
+        assert_eq!(parse_terms(&[]), (None, String::new()));
+        assert_eq!(parse_terms(&["perm", "spamming"]), (None, "spamming".to_string()));
+        assert_eq!(parse_terms(&["being", "rude"]), (None, "being rude".to_string()));
+        let (expires_at, reason) = parse_terms(&["1h", "cheating"]);
+        assert!(expires_at.is_some_and(|t| t > chrono::Utc::now().timestamp()));
+        assert_eq!(reason, "cheating");
+    }
+}
@@ -0,0 +1,286 @@
+// NPC commands: builder tools and dialogue
+
+use crate::database::{npc_queries, player_queries};
+use crate::game::behavior::BehaviorRegistry;
+use crate::game::GameState;
+use crate::models::{LootEntry, ShopItem};
+use std::sync::Arc;
+
+// admin commands
+pub async fn handle_admin_mcreate(state: Arc<GameState>, parts: &[&str]) -> String {
+    if parts.len() < 3 {
+        return "Usage: @mcreate <npc_name> <description>\n".to_string();
+    }
+
+    let name = parts[1];
+    let description = parts[2..].join(" ");
+
+    let id = npc_queries::create_prototype(&state.db, name, &description).await.unwrap();
+
+    format!("NPC prototype #{} '{}' created. Use @mset to edit it and @mload {} to place one.\n", id, name, id)
+}
+
+pub async fn handle_admin_mload(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let prototype_id = match parts.get(1).and_then(|p| p.trim_start_matches('#').parse::<i64>().ok()) {
+        Some(id) => id,
+        None => return "Usage: @mload <prototype_id>\n".to_string(),
+    };
+
+    match state.spawn_npc(prototype_id, &player.current_location, None).await {
+        Ok(npc) => {
+            let _ = state.broadcast_tx.send(format!("{} appears.\n", npc.name));
+            format!("You load {}.\n", npc.name)
+        }
+        Err(e) => format!("{}.\n", e),
+    }
+}
+
+pub async fn handle_admin_mlist(state: Arc<GameState>) -> String {
+    let prototypes = npc_queries::list_prototypes(&state.db).await.unwrap();
+    if prototypes.is_empty() {
+        return "No NPC prototypes exist yet.\n".to_string();
+    }
+
+    let mut response = "NPC prototypes:\n".to_string();
+    for p in prototypes {
+        response.push_str(&format!("  #{:<4} {} (level {}, {} hp)\n", p.id, p.name, p.level, p.max_health));
+    }
+    response
+}
+
+pub async fn handle_admin_mset(state: Arc<GameState>, parts: &[&str]) -> String {
+    let usage = "Usage: @mset <prototype_id> <field> <value>\n\
+                 Fields: name, desc, level, health, defense, armor, dexterity, strength,\n\
+                 vitality, perception, willpower, charisma,\n\
+                 dialogue <topic> <response>, loot <chance%> <item_name> <description>,\n\
+                 behavior <name> [key=value ...], behavior -<name>, emote <text>,\n\
+                 stock <price> <item_name> <description>\n";
+
+    if parts.len() < 4 {
+        return usage.to_string();
+    }
+
+    let prototype_id = match parts[1].trim_start_matches('#').parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => return usage.to_string(),
+    };
+    let prototype = match npc_queries::get_prototype(&state.db, prototype_id).await {
+        Ok(p) => p,
+        Err(e) => return format!("{}.\n", e),
+    };
+
+    let field = parts[2].to_lowercase();
+    let value = parts[3..].join(" ");
+
+    let result = match field.as_str() {
+        "name" => npc_queries::update_prototype_field(&state.db, prototype_id, "name", value).await,
+        "desc" | "description" => {
+            npc_queries::update_prototype_field(&state.db, prototype_id, "description", value).await
+        }
+        "level" | "health" | "defense" | "armor" | "dexterity" | "strength" | "vitality"
+        | "perception" | "willpower" | "charisma" => {
+            let number = match parts[3].parse::<i64>() {
+                Ok(n) if n >= 0 => n,
+                _ => return format!("{} must be a positive number.\n", field),
+            };
+            let column = match field.as_str() {
+                "health" => "max_health",
+                "defense" => "physical_defense",
+                "armor" => "physical_armor",
+                other => other,
+            };
+            npc_queries::update_prototype_field(&state.db, prototype_id, column, number).await
+        }
+        "dialogue" => {
+            if parts.len() < 5 {
+                return "Usage: @mset <prototype_id> dialogue <topic> <response>\n".to_string();
+            }
+            let mut topics = prototype.dialogue_topics();
+            topics.insert(parts[3].to_lowercase(), parts[4..].join(" "));
+            let json = serde_json::to_string(&topics).unwrap();
+            npc_queries::update_prototype_field(&state.db, prototype_id, "dialogue", json).await
+        }
+        "loot" => {
+            let chance = parts[3].trim_end_matches('%').parse::<u32>().ok().filter(|c| (1..=100).contains(c));
+            match (chance, parts.len() >= 6) {
+                (Some(chance), true) => {
+                    let mut loot = prototype.loot();
+                    loot.push(LootEntry {
+                        name: parts[4].to_string(),
+                        description: parts[5..].join(" "),
+                        chance,
+                    });
+                    let json = serde_json::to_string(&loot).unwrap();
+                    npc_queries::update_prototype_field(&state.db, prototype_id, "loot_table", json).await
+                }
+                _ => return "Usage: @mset <prototype_id> loot <chance 1-100> <item_name> <description>\n".to_string(),
+            }
+        }
+        "behavior" => {
+            let mut behaviors = prototype.behavior_map();
+            let name = parts[3].to_lowercase();
+
+            if let Some(removed) = name.strip_prefix('-') {
+                if behaviors.remove(removed).is_none() {
+                    return format!("That NPC doesn't have the '{}' behavior.\n", removed);
+                }
+            } else {
+                let known = BehaviorRegistry::with_defaults().names();
+                if !known.contains(&name.as_str()) {
+                    return format!("Unknown behavior. Known behaviors: {}\n", known.join(", "));
+                }
+
+                let entry = behaviors.entry(name).or_insert_with(|| serde_json::json!({}));
+                for pair in &parts[4..] {
+                    if let Some((key, raw)) = pair.split_once('=') {
+                        let value = raw.parse::<i64>()
+                            .map(serde_json::Value::from)
+                            .unwrap_or_else(|_| serde_json::Value::from(raw));
+                        entry[key] = value;
+                    }
+                }
+            }
+
+            let json = serde_json::to_string(&behaviors).unwrap();
+            npc_queries::update_prototype_field(&state.db, prototype_id, "behaviors", json).await
+        }
+        "emote" => {
+            let mut behaviors = prototype.behavior_map();
+            let entry = behaviors.entry("emote").or_insert_with(|| serde_json::json!({}));
+            if !entry["lines"].is_array() {
+                entry["lines"] = serde_json::json!([]);
+            }
+            entry["lines"].as_array_mut().unwrap().push(serde_json::Value::from(value));
+
+            let json = serde_json::to_string(&behaviors).unwrap();
+            npc_queries::update_prototype_field(&state.db, prototype_id, "behaviors", json).await
+        }
+        "stock" => {
+            let price = parts[3].parse::<i64>().ok().filter(|p| *p >= 0);
+            match (price, parts.len() >= 6) {
+                (Some(price), true) => {
+                    let mut behaviors = prototype.behavior_map();
+                    let entry = behaviors.entry("shopkeeper").or_insert_with(|| serde_json::json!({}));
+                    if !entry["stock"].is_array() {
+                        entry["stock"] = serde_json::json!([]);
+                    }
+                    let item = ShopItem {
+                        name: parts[4].to_string(),
+                        description: parts[5..].join(" "),
+                        price,
+                    };
+                    entry["stock"].as_array_mut().unwrap().push(serde_json::to_value(item).unwrap());
+
+                    let json = serde_json::to_string(&behaviors).unwrap();
+                    npc_queries::update_prototype_field(&state.db, prototype_id, "behaviors", json).await
+                }
+                _ => return "Usage: @mset <prototype_id> stock <price> <item_name> <description>\n".to_string(),
+            }
+        }
+        _ => return usage.to_string(),
+    };
+
+    match result {
+        Ok(()) => format!("NPC prototype #{} updated.\n", prototype_id),
+        Err(e) => format!("{}\n", e),
+    }
+}
+
+// regular commands
+pub async fn handle_talk(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Talk to whom?\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+
+    let npc = match npcs.into_iter().find(|n| n.matches(parts[1])) {
+        Some(npc) => npc,
+        None => return "You don't see them here.\n".to_string(),
+    };
+
+    let topics = npc.dialogue_topics();
+    match parts.get(2) {
+        Some(_) => {
+            let topic = parts[2..].join(" ").to_lowercase();
+            match topics.get(&topic) {
+                Some(reply) => format!("{} says: {}\n", npc.name, reply),
+                None => format!("{} doesn't know anything about that.\n", npc.name),
+            }
+        }
+        None => {
+            let mut response = match topics.get("greeting") {
+                Some(greeting) => format!("{} says: {}\n", npc.name, greeting),
+                None => format!("{} has nothing to say to you.\n", npc.name),
+            };
+
+            let mut others: Vec<&String> = topics.keys().filter(|t| *t != "greeting").collect();
+            others.sort();
+            if !others.is_empty() {
+                let list: Vec<String> = others.iter().map(|t| t.to_string()).collect();
+                response.push_str(&format!("You could ask about: {}\n", list.join(", ")));
+            }
+            response
+        }
+    }
+}
+
+pub async fn handle_list(state: Arc<GameState>, player_id: &str) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+
+    let (shopkeeper, stock) = match npcs.iter().find_map(|n| n.shop_stock().map(|s| (n, s))) {
+        Some(found) => found,
+        None => return "There is no shopkeeper here.\n".to_string(),
+    };
+
+    if stock.is_empty() {
+        return format!("{} has nothing for sale.\n", shopkeeper.name);
+    }
+
+    let mut response = format!("{} has for sale:\n", shopkeeper.name);
+    for item in stock {
+        response.push_str(&format!("  {:<20} {} gold\n", item.name, item.price));
+    }
+    response
+}
+
+pub async fn handle_buy(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Buy what?\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let npcs = state.get_npcs_in_room(&player.current_location).await.unwrap();
+
+    let (shopkeeper, stock) = match npcs.iter().find_map(|n| n.shop_stock().map(|s| (n, s))) {
+        Some(found) => found,
+        None => return "There is no shopkeeper here.\n".to_string(),
+    };
+
+    let wanted = parts[1..].join(" ").to_lowercase();
+    let item = match stock.iter().find(|i| i.name.to_lowercase() == wanted) {
+        Some(item) => item,
+        None => return format!("{} doesn't sell that.\n", shopkeeper.name),
+    };
+
+    if player.gold < item.price {
+        return format!("{} says: You can't afford that.\n", shopkeeper.name);
+    }
+
+    player_queries::update_player_gold(&state.db, player_id, player.gold - item.price).await.unwrap();
+    state.create_object(&item.name, &item.description, "item", Some(player_id)).await.unwrap();
+
+    format!("You buy {} from {} for {} gold.\n", item.name, shopkeeper.name, item.price)
+}
@@ -0,0 +1,300 @@
+// Pathfinding over the room exit graph
+
+use crate::database::object_queries;
+use crate::game::{directions, GameState};
+use crate::models::{GameObject, RoomExit};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+
+/// Longest speedwalk or route a player can queue.
+pub const MAX_STEPS: usize = 100;
+
+/// What a traveller is able to pass and what it prefers to avoid.
+#[derive(Debug, Clone)]
+pub struct PathOptions {
+    pub open_doors: bool, // closed (unlocked) doors can be opened on the way
+    pub use_hidden: bool, // hidden exits count as exits
+    pub stay_in_area: bool, // never leave the starting room's area
+    pub max_rooms: usize, // give up after exploring this many rooms
+}
+
+impl PathOptions {
+    /// A player walking: opens doors, doesn't know hidden exits, may change areas.
+    pub fn player() -> Self {
+        Self {
+            open_doors: true,
+            use_hidden: false,
+            stay_in_area: false,
+            max_rooms: 5000,
+        }
+    }
+
+    /// An NPC: can't open doors and stays home.
+    pub fn npc() -> Self {
+        Self {
+            open_doors: false,
+            use_hidden: false,
+            stay_in_area: true,
+            max_rooms: 500,
+        }
+    }
+}
+
+// extra cost for routes that pass a closed door or cross into another area
+const DOOR_COST: u32 = 2;
+const AREA_COST: u32 = 3;
+
+pub struct Path {
+    pub steps: Vec<String>, // exit names, in order
+    pub cost: u32,
+    pub explored: usize,
+}
+
+/// Cost of taking an exit, or None if the traveller can't use it.
+fn exit_cost(exit: &RoomExit, options: &PathOptions) -> Option<u32> {
+    if exit.is_hidden == 1 && !options.use_hidden {
+        return None;
+    }
+    if exit.is_locked == 1 {
+        return None;
+    }
+    if exit.is_closed == 1 && exit.has_door() {
+        return options.open_doors.then_some(1 + DOOR_COST);
+    }
+    Some(1)
+}
+
+/// Cheapest route from one room to another (Dijkstra; plain BFS when every exit costs 1).
+pub async fn find_path(state: &Arc<GameState>, from: &str, to: &str, options: &PathOptions) -> Result<Option<Path>, String> {
+    if from == to {
+        return Ok(Some(Path { steps: Vec::new(), cost: 0, explored: 0 }));
+    }
+
+    let mut areas: HashMap<String, Option<i64>> = HashMap::new();
+    let start_area = state.get_room(from).await?.area_id;
+    areas.insert(from.to_string(), start_area);
+
+    let mut best: HashMap<String, u32> = HashMap::from([(from.to_string(), 0)]);
+    let mut came_from: HashMap<String, (String, String)> = HashMap::new(); // room -> (previous room, exit)
+    let mut queue = BinaryHeap::from([Reverse((0u32, from.to_string()))]);
+    let mut explored = 0;
+
+    while let Some(Reverse((cost, room))) = queue.pop() {
+        if room == to {
+            let mut steps = Vec::new();
+            let mut current = room;
+            while let Some((previous, exit)) = came_from.get(&current) {
+                steps.push(exit.clone());
+                current = previous.clone();
+            }
+            steps.reverse();
+            return Ok(Some(Path { steps, cost, explored }));
+        }
+        if best.get(&room).is_some_and(|&b| cost > b) {
+            continue;
+        }
+        explored += 1;
+        if explored > options.max_rooms {
+            break;
+        }
+
+        let room_area = areas.get(&room).copied().flatten();
+        for exit in state.get_exits(&room).await? {
+            let mut step_cost = match exit_cost(&exit, options) {
+                Some(c) => c,
+                None => continue,
+            };
+
+            let dest_area = match areas.get(&exit.destination_id) {
+                Some(area) => *area,
+                None => {
+                    let dest = match state.get_room(&exit.destination_id).await {
+                        Ok(r) => r,
+                        Err(_) => continue, // exit into a destroyed room
+                    };
+                    // never route through death traps
+                    if dest.room_flags().has("death-trap") && exit.destination_id != to {
+                        continue;
+                    }
+                    areas.insert(exit.destination_id.clone(), dest.area_id);
+                    dest.area_id
+                }
+            };
+            if dest_area != room_area {
+                if options.stay_in_area && dest_area != start_area {
+                    continue;
+                }
+                step_cost += AREA_COST;
+            }
+
+            let next_cost = cost + step_cost;
+            if best.get(&exit.destination_id).is_none_or(|&b| next_cost < b) {
+                best.insert(exit.destination_id.clone(), next_cost);
+                came_from.insert(exit.destination_id.clone(), (room.clone(), exit.direction.clone()));
+                queue.push(Reverse((next_cost, exit.destination_id.clone())));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds a room by id, landmark name or exact room name.
+pub async fn resolve_destination(state: &Arc<GameState>, input: &str) -> Result<GameObject, String> {
+    if let Ok(room) = state.get_room(input).await {
+        return Ok(room);
+    }
+    if let Some(room) = object_queries::get_landmark(&state.db, input).await? {
+        return Ok(room);
+    }
+
+    let rooms = object_queries::get_rooms_named(&state.db, input).await?;
+    match rooms.len() {
+        0 => Err("You don't know of any place by that name.\n".to_string()),
+        1 => Ok(rooms.into_iter().next().unwrap()),
+        n => Err(format!("{} rooms are called that; use a landmark or room id.\n", n)),
+    }
+}
+
+/// Parses a speedwalk like `3n2e` or `2n portal e` into single steps. Diagonals bind first,
+/// so `ne` is northeast; words that aren't compass runs are taken as named exits, and
+/// "quoted" words always are, for names like `news` that would read as directions.
+pub fn parse_speedwalk(input: &str) -> Result<Vec<String>, String> {
+    let mut steps = Vec::new();
+
+    for word in input.split_whitespace() {
+        let quoted = word.len() > 2 && word.starts_with('"') && word.ends_with('"');
+        match parse_compass_run(word) {
+            _ if quoted => steps.push(word[1..word.len() - 1].to_lowercase()),
+            Some(mut run) => steps.append(&mut run),
+            None if word.starts_with(|c: char| c.is_ascii_digit()) => {
+                return Err(format!("'{}' isn't a path; use counts and directions like 3n2e.\n", word));
+            }
+            None => steps.push(word.to_lowercase()),
+        }
+        if steps.len() > MAX_STEPS {
+            return Err(format!("That's too far to walk in one go (at most {} steps).\n", MAX_STEPS));
+        }
+    }
+
+    if steps.is_empty() {
+        return Err("Usage: run <path>, e.g. run 3n2e\n".to_string());
+    }
+    Ok(steps)
+}
+
+fn parse_compass_run(word: &str) -> Option<Vec<String>> {
+    let chars: Vec<char> = word.to_lowercase().chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let mut count = 0usize;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            count = count * 10 + chars[i].to_digit(10).unwrap() as usize;
+            i += 1;
+            if count > MAX_STEPS {
+                return None;
+            }
+        }
+
+        let two: String = chars[i..].iter().take(2).collect();
+        let one: String = chars[i..].iter().take(1).collect();
+        let (direction, width) = if ["ne", "nw", "se", "sw"].contains(&two.as_str()) {
+            (directions::normalize(&two), 2)
+        } else if ["n", "s", "e", "w", "u", "d"].contains(&one.as_str()) {
+            (directions::normalize(&one), 1)
+        } else {
+            return None;
+        };
+        i += width;
+
+        for _ in 0..count.max(1) {
+            steps.push(direction.clone());
+        }
+    }
+
+    Some(steps)
+}
+
+/// Writes steps back as a compact speedwalk: `north, north, east, portal` -> `2n e portal`.
+/// Named exits are repeated rather than counted, and quoted if they'd read as directions.
+pub fn to_speedwalk(steps: &[String]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < steps.len() {
+        let short = directions::COMPASS.iter().find(|(full, _)| *full == steps[i]).map(|(_, short)| *short);
+        match short {
+            Some(short) => {
+                let mut run = 1;
+                while i + run < steps.len() && steps[i + run] == steps[i] {
+                    run += 1;
+                }
+                parts.push(if run > 1 { format!("{}{}", run, short) } else { short.to_string() });
+                i += run;
+            }
+            None => {
+                match parse_compass_run(&steps[i]) {
+                    Some(_) => parts.push(format!("\"{}\"", steps[i])),
+                    None => parts.push(steps[i].clone()),
+                }
+                i += 1;
+            }
+        }
+    }
+
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn speedwalk_counts_and_diagonals() {
+        assert_eq!(parse_speedwalk("3n2e").unwrap(), steps(&["north", "north", "north", "east", "east"]));
+        assert_eq!(parse_speedwalk("ne 2sw u").unwrap(), steps(&["northeast", "southwest", "southwest", "up"]));
+        // diagonals bind first
+        assert_eq!(parse_speedwalk("nes").unwrap(), steps(&["northeast", "south"]));
+    }
+
+    #[test]
+    fn speedwalk_named_exits() {
+        assert_eq!(parse_speedwalk("2n portal e").unwrap(), steps(&["north", "north", "portal", "east"]));
+        assert_eq!(parse_speedwalk("Gate").unwrap(), steps(&["gate"]));
+        // read as directions unless quoted
+        assert_eq!(parse_speedwalk("news").unwrap(), steps(&["northeast", "west", "south"]));
+        assert_eq!(parse_speedwalk("\"news\" n").unwrap(), steps(&["news", "north"]));
+    }
+
+    #[test]
+    fn speedwalk_rejects_bad_input() {
+        assert!(parse_speedwalk("").is_err());
+        assert!(parse_speedwalk("3portal").is_err());
+        assert!(parse_speedwalk(&format!("{}n", MAX_STEPS)).is_ok());
+        assert!(parse_speedwalk(&format!("{}n", MAX_STEPS + 1)).is_err());
+        assert!(parse_speedwalk(&format!("{}n e", MAX_STEPS)).is_err());
+    }
+
+    #[test]
+    fn compass_runs() {
+        assert_eq!(parse_compass_run("2d"), Some(steps(&["down", "down"])));
+        assert_eq!(parse_compass_run("12w").map(|r| r.len()), Some(12));
+        assert_eq!(parse_compass_run("north"), None);
+        assert_eq!(parse_compass_run("3"), None);
+    }
+
+    #[test]
+    fn speedwalk_round_trip() {
+        let path = steps(&["north", "north", "east", "portal", "portal", "news", "northwest", "up", "up", "up"]);
+        let written = to_speedwalk(&path);
+        assert_eq!(written, "2n e portal portal \"news\" nw 3u");
+        assert_eq!(parse_speedwalk(&written).unwrap(), path);
+    }
+}
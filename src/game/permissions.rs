@@ -0,0 +1,311 @@
+// Roles, per-command permissions, builder areas and the audit log
+
+use crate::database::{permission_queries, player_queries};
+use crate::game::GameState;
+use crate::models::role::{canonical_command, default_command_role};
+use crate::models::{Player, Role, AREA_COMMANDS, COMMAND_ROLES};
+use std::sync::Arc;
+
+/// Entries `@audit` shows when not given a count.
+const DEFAULT_AUDIT_COUNT: i64 = 20;
+
+fn no_permission() -> String {
+    "You don't have permission to do that.\n".to_string()
+}
+
+/// The role a command needs: its @perms setting, else its default. None for commands
+/// every player can use.
+pub async fn required_role(state: &Arc<GameState>, command: &str) -> Result<Option<Role>, String> {
+    let default = match default_command_role(command) {
+        Some(role) => role,
+        None => return Ok(None),
+    };
+    let changed = permission_queries::get_command_role(&state.db, command).await?;
+    Ok(Some(changed.and_then(|r| Role::parse(&r)).unwrap_or(default)))
+}
+
+/// True if the player's role is high enough for a command, or it was granted to them.
+pub async fn can_use(state: &Arc<GameState>, player: &Player, command: &str) -> Result<bool, String> {
+    match required_role(state, command).await? {
+        None => Ok(true),
+        Some(role) if player.role() >= role => Ok(true),
+        Some(_) => Ok(permission_queries::get_grants(&state.db, &player.id).await?.iter().any(|c| c == command)),
+    }
+}
+
+/// Admins can build anywhere; everyone else only in areas that list them as a builder.
+pub async fn can_build_in(state: &Arc<GameState>, player: &Player, room_id: &str) -> Result<bool, String> {
+    if player.role() >= Role::Admin {
+        return Ok(true);
+    }
+    let area = state.get_room_area(room_id).await?;
+    Ok(area.is_builder(&player.username))
+}
+
+/// Checked before every command. Returns the refusal if the player may not run it;
+/// privileged commands that are allowed are written to the audit log.
+pub async fn gate(state: &Arc<GameState>, player_id: &str, line: &str) -> Option<String> {
+    let command = canonical_command(line.split_whitespace().next()?);
+    match required_role(state, &command).await {
+        Ok(required) => required?,
+        Err(_) => return Some(no_permission()),
+    };
+
+    let player = player_queries::get_player_by_id(&state.db, player_id).await.ok()?;
+    if !can_use(state, &player, &command).await.unwrap_or(false) {
+        return Some(no_permission());
+    }
+    if AREA_COMMANDS.contains(&command.as_str()) && !can_build_in(state, &player, &player.current_location).await.unwrap_or(false) {
+        return Some("You can only build in areas you are a builder of.\n".to_string());
+    }
+
+    if let Err(e) = permission_queries::log_action(&state.db, &player.id, &player.username, &player.current_location, line.trim()).await {
+        eprintln!("{}", e);
+    }
+    None
+}
+
+/// Shared checks for @promote and @demote. Only owners can act on someone of their own
+/// rank or hand out a rank equal to their own.
+async fn change_role(state: &Arc<GameState>, actor: &Player, name: &str, role: Option<Role>, promote: bool) -> String {
+    let target = match player_queries::find_player_by_username(&state.db, name).await.unwrap() {
+        Some(p) => p,
+        None => return "There is no player by that name.\n".to_string(),
+    };
+    let current = target.role();
+    let new_role = match role.or(if promote { current.next() } else { current.previous() }) {
+        Some(r) => r,
+        None if promote => return format!("{} can't be promoted any further.\n", target.username),
+        None => return format!("{} can't be demoted any further.\n", target.username),
+    };
+
+    if promote && new_role <= current {
+        return format!("{} is already {}.\n", target.username, current.as_str());
+    }
+    if !promote && new_role >= current {
+        return format!("{} is only {}.\n", target.username, current.as_str());
+    }
+    if actor.role() != Role::Owner && (current >= actor.role() || new_role >= actor.role()) {
+        return no_permission();
+    }
+    if current == Role::Owner && target.id == actor.id {
+        return "Have another owner demote you.\n".to_string();
+    }
+
+    permission_queries::update_player_role(&state.db, &target.id, new_role.as_str())
+        .await
+        .unwrap();
+    state
+        .send_to_player(&target.id, &format!("{} has made you {}.\n", actor.username, new_role.as_str()))
+        .await;
+    format!("{} is now {}.\n", target.username, new_role.as_str())
+}
+
+// admin commands
+/// `@promote <player> [role]` raises a player one rank, or to the role given.
+pub async fn handle_admin_promote(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    handle_role_command(state, player_id, parts, true).await
+}
+
+/// `@demote <player> [role]` lowers a player one rank, or to the role given.
+pub async fn handle_admin_demote(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    handle_role_command(state, player_id, parts, false).await
+}
+
+async fn handle_role_command(state: Arc<GameState>, player_id: &str, parts: &[&str], promote: bool) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let usage = format!(
+        "Usage: {} <player> [{}]\n",
+        parts[0],
+        Role::ALL.map(|r| r.as_str()).join("|")
+    );
+    let role = match parts.get(2) {
+        Some(name) => match Role::parse(name) {
+            Some(r) => Some(r),
+            None => return usage,
+        },
+        None => None,
+    };
+    match parts.get(1) {
+        Some(name) if parts.len() <= 3 => change_role(&state, &player, name, role, promote).await,
+        _ => usage,
+    }
+}
+
+/// `@perms` lists privileged commands; `@perms <command> <role>` changes the role one needs;
+/// `@perms <player>` shows their role and grants; `@perms <player> +<command>|-<command>`
+/// grants or takes away a single command.
+pub async fn handle_admin_perms(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let usage = "Usage: @perms, @perms <command> <role>, @perms <player> [+<command>|-<command>]\n".to_string();
+
+    if parts.len() < 2 {
+        let mut response = "Commands and the lowest role that can use them (* changed from the default):\n".to_string();
+        for (command, default) in COMMAND_ROLES {
+            let role = required_role(&state, command).await.unwrap().unwrap_or(*default);
+            let changed = if role != *default { "*" } else { "" };
+            response.push_str(&format!("  {:<12} {}{}\n", command, role.as_str(), changed));
+        }
+        return response;
+    }
+
+    let command = canonical_command(parts[1]);
+    if let Some(current) = required_role(&state, &command).await.unwrap() {
+        let role = match parts.get(2).and_then(|r| Role::parse(r)) {
+            Some(r) if parts.len() == 3 => r,
+            _ => return format!("{} needs {}.\n{}", command, current.as_str(), usage),
+        };
+        if player.role() != Role::Owner && (current > player.role() || role > player.role()) {
+            return no_permission();
+        }
+        permission_queries::set_command_role(&state.db, &command, role.as_str())
+            .await
+            .unwrap();
+        return format!("{} now needs {}.\n", command, role.as_str());
+    }
+
+    let target = match player_queries::find_player_by_username(&state.db, parts[1]).await.unwrap() {
+        Some(p) => p,
+        None => return format!("'{}' is neither a privileged command nor a player.\n{}", parts[1], usage),
+    };
+
+    if parts.len() == 2 {
+        let grants = permission_queries::get_grants(&state.db, &target.id).await.unwrap();
+        return format!(
+            "{} is {}.\n  Extra commands: {}\n",
+            target.username,
+            target.role().as_str(),
+            if grants.is_empty() { "none".to_string() } else { grants.join(", ") }
+        );
+    }
+
+    let (granted, command) = match parts[2].split_at(1) {
+        ("+", command) => (true, canonical_command(command)),
+        ("-", command) => (false, canonical_command(command)),
+        _ => return usage,
+    };
+    if default_command_role(&command).is_none() {
+        return format!("'{}' isn't a privileged command.\n", command);
+    }
+    // you can only hand out what you can use yourself, to people below you
+    if player.role() != Role::Owner && (target.role() >= player.role() || !can_use(&state, &player, &command).await.unwrap()) {
+        return no_permission();
+    }
+
+    permission_queries::set_grant(&state.db, &target.id, &command, granted)
+        .await
+        .unwrap();
+    match granted {
+        true => format!("{} can now use {}.\n", target.username, command),
+        false => format!("{} can no longer use {} unless their role allows it.\n", target.username, command),
+    }
+}
+
+/// `@audit [player] [count]` shows recent privileged commands, newest first.
+pub async fn handle_admin_audit(state: Arc<GameState>, parts: &[&str]) -> String {
+    let mut who = None;
+    let mut count = DEFAULT_AUDIT_COUNT;
+    for arg in &parts[1..] {
+        match arg.parse::<i64>() {
+            Ok(n) if n > 0 => count = n,
+            _ => match player_queries::find_player_by_username(&state.db, arg).await.unwrap() {
+                Some(p) => who = Some(p),
+                None => return "There is no player by that name.\n".to_string(),
+            },
+        }
+    }
+
+    let entries = permission_queries::get_audit_log(&state.db, who.as_ref().map(|p| p.id.as_str()), count)
+        .await
+        .unwrap();
+    if entries.is_empty() {
+        return "The audit log is empty.\n".to_string();
+    }
+
+    let mut response = "Recent privileged commands:\n".to_string();
+    for entry in entries {
+        let when = chrono::DateTime::from_timestamp(entry.created_at, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%b %d %H:%M").to_string())
+            .unwrap_or_default();
+        response.push_str(&format!("  [{}] {:<12} {:<14} {}\n", when, entry.username, entry.room_id, entry.command));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::area_queries;
+
+    async fn list_builder(state: &Arc<GameState>, area: &str, username: &str) {
+        let area = area_queries::get_area_by_name(&state.db, area).await.unwrap().unwrap();
+        let builders = serde_json::to_string(&[username]).unwrap();
+        area_queries::update_area_field(&state.db, area.id, "builders", builders).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn player_commands_are_open_and_not_logged() {
+        let state = GameState::in_memory().await;
+        let player = state.add_test_player("alice", Role::Player).await;
+
+        assert_eq!(gate(&state, &player.id, "look").await, None);
+        assert_eq!(gate(&state, &player.id, "say hello").await, None);
+        assert!(permission_queries::get_audit_log(&state.db, None, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn staff_commands_need_a_role_or_grant() {
+        let state = GameState::in_memory().await;
+        let player = state.add_test_player("alice", Role::Player).await;
+        let helper = state.add_test_player("hank", Role::Helper).await;
+        let admin = state.add_test_player("ada", Role::Admin).await;
+
+        assert_eq!(gate(&state, &player.id, "@bans").await, Some(no_permission()));
+        assert_eq!(gate(&state, &helper.id, "@bans").await, None);
+        assert_eq!(gate(&state, &helper.id, "@ban 10.0.0.1").await, Some(no_permission()));
+        assert_eq!(gate(&state, &admin.id, "@ban 10.0.0.1").await, None);
+
+        // @perms can raise a command's role, and grant it to one player
+        permission_queries::set_command_role(&state.db, "@bans", "admin").await.unwrap();
+        assert_eq!(gate(&state, &helper.id, "@bans").await, Some(no_permission()));
+        permission_queries::set_grant(&state.db, &player.id, "@bans", true).await.unwrap();
+        assert_eq!(gate(&state, &player.id, "@bans").await, None);
+
+        let logged = permission_queries::get_audit_log(&state.db, None, 10).await.unwrap();
+        let commands: Vec<&str> = logged.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands.len(), 3);
+        assert!(commands.contains(&"@ban 10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn builders_stay_in_their_areas() {
+        let state = GameState::in_memory().await;
+        let builder = state.add_test_player("bob", Role::Builder).await;
+        let admin = state.add_test_player("ada", Role::Admin).await;
+        let refusal = Some("You can only build in areas you are a builder of.\n".to_string());
+
+        assert!(!can_build_in(&state, &builder, "room_start").await.unwrap());
+        assert!(can_build_in(&state, &admin, "room_start").await.unwrap());
+        assert_eq!(gate(&state, &builder.id, "@dig north Hall A hall.").await, refusal);
+        // commands that don't change the room aren't tied to an area
+        assert_eq!(gate(&state, &builder.id, "@find hall").await, None);
+
+        list_builder(&state, "default", "Bob").await;
+        assert!(can_build_in(&state, &builder, "room_start").await.unwrap());
+        assert_eq!(gate(&state, &builder.id, "@dig north Hall A hall.").await, None);
+
+        // being listed on one area doesn't reach into another
+        area_queries::create_area(&state.db, "zone").await.unwrap();
+        let zone = area_queries::get_area_by_name(&state.db, "zone").await.unwrap().unwrap();
+        let yard = state.create_object("Yard", "A yard.", "room", None).await.unwrap();
+        area_queries::set_room_area(&state.db, &yard.id, &zone).await.unwrap();
+        assert!(!can_build_in(&state, &builder, &yard.id).await.unwrap());
+        assert!(can_build_in(&state, &admin, &yard.id).await.unwrap());
+    }
+}
@@ -0,0 +1,419 @@
+// Area resets: repopulating rooms and containers with objects and NPCs
+
+use crate::database::{area_queries, object_queries, player_queries, reset_queries};
+use crate::game::{permissions, GameState};
+use crate::models::{GameObject, Player, Reset, Role};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the scheduler checks whether any area is due for a reset.
+const RESET_CHECK_SECS: u64 = 30;
+
+#[derive(Default)]
+pub struct ResetSummary {
+    pub objects: i64,
+    pub npcs: i64,
+    pub failures: Vec<String>, // resets that couldn't fill up, e.g. a deleted NPC prototype
+}
+
+/// Tops every reset (or every reset in one area) back up to its max count.
+pub async fn run_resets(state: &Arc<GameState>, area: Option<&str>) -> Result<ResetSummary, String> {
+    let resets = reset_queries::get_resets(&state.db, area).await?;
+    let mut summary = ResetSummary::default();
+
+    for reset in resets {
+        match reset.reset_type.as_str() {
+            "object" => summary.objects += reset_object(state, &reset).await?,
+            "npc" => {
+                let (spawned, failure) = reset_npc(state, &reset).await;
+                summary.npcs += spawned;
+                summary.failures.extend(failure);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+async fn reset_object(state: &Arc<GameState>, reset: &Reset) -> Result<i64, String> {
+    // The room or container may have been destroyed since the reset was defined
+    if object_queries::get_object(&state.db, reset.target_id()).await.is_err() {
+        return Ok(0);
+    }
+
+    let existing = reset_queries::count_reset_objects(&state.db, reset).await?;
+    let missing = (reset.max_count - existing).max(0);
+
+    for _ in 0..missing {
+        let mut obj = GameObject::new(
+            Uuid::new_v4().to_string(),
+            reset.name.clone(),
+            reset.description.clone(),
+            reset.object_type.clone(),
+            Some(reset.target_id().to_string()),
+        );
+        obj.properties = tag_properties(&reset.properties, reset.id);
+        object_queries::create_object(&state.db, &obj).await?;
+    }
+
+    Ok(missing)
+}
+
+/// Returns how many NPCs were spawned, and why the rest couldn't be, if any failed.
+async fn reset_npc(state: &Arc<GameState>, reset: &Reset) -> (i64, Option<String>) {
+    let prototype_id = match reset.npc_prototype_id {
+        Some(id) => id,
+        None => return (0, None),
+    };
+
+    let existing = match reset_queries::count_reset_npcs(&state.db, reset.id).await {
+        Ok(n) => n,
+        Err(e) => return (0, Some(format!("Reset #{}: {}", reset.id, e))),
+    };
+    let missing = (reset.max_count - existing).max(0);
+
+    for spawned in 0..missing {
+        // A deleted prototype or room leaves this reset idle until it is fixed or removed
+        if let Err(e) = state.spawn_npc(prototype_id, &reset.room_id, Some(reset.id)).await {
+            return (spawned, Some(format!("Reset #{} (NPC #{} in {}): {}", reset.id, prototype_id, reset.room_id, e)));
+        }
+    }
+
+    (missing, None)
+}
+
+/// Adds `reset_id` to an object's JSON properties so the reset can count its copies.
+fn tag_properties(properties: &str, reset_id: i64) -> String {
+    let mut value: serde_json::Value = serde_json::from_str(properties)
+        .unwrap_or_else(|_| serde_json::json!({}));
+    if !value.is_object() {
+        value = serde_json::json!({});
+    }
+    value["reset_id"] = serde_json::json!(reset_id);
+    value.to_string()
+}
+
+/// Runs each area's resets on its own interval (or `reset_interval_secs` when the area has
+/// none), for the life of the server.
+pub async fn reset_scheduler(state: Arc<GameState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(RESET_CHECK_SECS));
+    let mut last_reset: HashMap<String, i64> = HashMap::new();
+
+    loop {
+        interval.tick().await;
+        let now = chrono::Utc::now().timestamp();
+
+        let areas = match reset_queries::get_reset_areas(&state.db).await {
+            Ok(areas) => areas,
+            Err(e) => {
+                eprintln!("Area reset failed: {}", e);
+                continue;
+            }
+        };
+
+        for name in areas {
+            let every = match area_queries::get_area_by_name(&state.db, &name).await {
+                Ok(Some(area)) if area.reset_interval_secs > 0 => area.reset_interval_secs,
+                _ => state.config.reset_interval_secs as i64,
+            };
+            // areas reset as soon as the server starts, then on their interval
+            if last_reset.get(&name).is_some_and(|&at| now - at < every) {
+                continue;
+            }
+            last_reset.insert(name.clone(), now);
+
+            match run_resets(&state, Some(&name)).await {
+                Ok(summary) => {
+                    if summary.objects > 0 || summary.npcs > 0 {
+                        println!(
+                            "Area reset ({}): {} objects and {} NPCs repopulated",
+                            name, summary.objects, summary.npcs
+                        );
+                    }
+                    for failure in summary.failures {
+                        eprintln!("Area reset ({}) failed: {}", name, failure);
+                    }
+                }
+                Err(e) => eprintln!("Area reset failed: {}", e),
+            }
+        }
+    }
+}
+
+// admin commands
+pub async fn handle_admin_reset(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    match parts.get(1).map(|p| p.to_lowercase()).as_deref() {
+        Some("item") | Some("object") => add_object_reset(&state, &player.current_location, &parts[2..]).await,
+        Some("npc") => add_npc_reset(&state, &player.current_location, &parts[2..]).await,
+        Some("list") => list_resets(&state, &player.current_location, parts.get(2).copied()).await,
+        Some("remove") => {
+            let reset_id = match parts.get(2).and_then(|p| p.trim_start_matches('#').parse::<i64>().ok()) {
+                Some(id) => id,
+                None => return "Usage: @reset remove <reset_id>\n".to_string(),
+            };
+            let reset = match reset_queries::get_reset(&state.db, reset_id).await {
+                Ok(Some(reset)) => reset,
+                Ok(None) => return format!("Reset #{} not found.\n", reset_id),
+                Err(e) => return format!("{}\n", e),
+            };
+            match permissions::can_build_in(&state, &player, &reset.room_id).await {
+                Ok(true) => {}
+                Ok(false) => return format!("Reset #{} is in an area you can't build in.\n", reset_id),
+                Err(e) => return format!("{}\n", e),
+            }
+            match reset_queries::delete_reset(&state.db, reset_id).await {
+                Ok(true) => format!("Reset #{} removed.\n", reset_id),
+                Ok(false) => format!("Reset #{} not found.\n", reset_id),
+                Err(e) => format!("{}\n", e),
+            }
+        }
+        area => {
+            if let Some(refusal) = check_reset_area(&state, &player, area).await {
+                return refusal;
+            }
+            match run_resets(&state, area).await {
+                Ok(summary) => {
+                    let mut response = format!(
+                        "Reset {}: {} objects and {} NPCs repopulated.\n",
+                        area.unwrap_or("all areas"),
+                        summary.objects,
+                        summary.npcs
+                    );
+                    for failure in summary.failures {
+                        response.push_str(&format!("  Failed: {}\n", failure));
+                    }
+                    response
+                }
+                Err(e) => format!("{}\n", e),
+            }
+        }
+    }
+}
+
+/// Builders may only repopulate areas they build in; every area at once takes an Admin.
+async fn check_reset_area(state: &Arc<GameState>, player: &Player, area: Option<&str>) -> Option<String> {
+    if player.role() >= Role::Admin {
+        return None;
+    }
+    let name = match area {
+        Some(name) => name,
+        None => return Some("Only admins can reset every area; name one of yours.\n".to_string()),
+    };
+    match area_queries::get_area_by_name(&state.db, name).await {
+        Ok(Some(area)) if area.is_builder(&player.username) => None,
+        Ok(_) => Some(format!("You aren't a builder of '{}'.\n", name)),
+        Err(e) => Some(format!("{}\n", e)),
+    }
+}
+
+/// Reads the optional `in <container>` and `max <n>` words after a reset target.
+fn parse_reset_options(args: &[&str]) -> (Option<String>, i64) {
+    let mut container = None;
+    let mut max_count = 1;
+
+    let mut i = 0;
+    while i + 1 < args.len() {
+        match args[i].to_lowercase().as_str() {
+            "in" => container = Some(args[i + 1].to_lowercase()),
+            "max" => max_count = args[i + 1].parse().unwrap_or(1).max(1),
+            _ => {
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    (container, max_count)
+}
+
+async fn add_object_reset(state: &Arc<GameState>, room_id: &str, args: &[&str]) -> String {
+    let usage = "Usage: @reset item <item_name> [in <container>] [max <n>]\n";
+    let item_name = match args.first() {
+        Some(name) => name.to_lowercase(),
+        None => return usage.to_string(),
+    };
+    let (container_name, max_count) = parse_reset_options(&args[1..]);
+    let area = state.get_room_area(room_id).await.unwrap().name;
+
+    let objects = state.get_objects_in_container(room_id).await.unwrap();
+    let item = match objects.iter().find(|o| o.name.to_lowercase() == item_name) {
+        Some(item) => item.clone(),
+        None => return "There is no such item here to use as the template.\n".to_string(),
+    };
+
+    let container_id = match container_name {
+        Some(name) => match objects.iter().find(|o| o.name.to_lowercase() == name && o.id != item.id) {
+            Some(container) => Some(container.id.clone()),
+            None => return "There is no such container here.\n".to_string(),
+        },
+        None => None,
+    };
+
+    let reset_id = reset_queries::create_reset(
+        &state.db,
+        &area,
+        room_id,
+        container_id.as_deref(),
+        "object",
+        None,
+        &item.name,
+        &item.description,
+        &item.object_type,
+        &item.properties,
+        max_count,
+    )
+    .await
+    .unwrap();
+
+    // The template item becomes the first copy the reset is responsible for
+    let target = container_id.as_deref().unwrap_or(room_id);
+    object_queries::update_object_properties(&state.db, &item.id, &tag_properties(&item.properties, reset_id))
+        .await
+        .unwrap();
+    object_queries::move_object(&state.db, &item.id, target).await.unwrap();
+
+    format!("Reset #{} added: up to {} x {} (area '{}').\n", reset_id, max_count, item.name, area)
+}
+
+async fn add_npc_reset(state: &Arc<GameState>, room_id: &str, args: &[&str]) -> String {
+    let prototype_id = match args.first().and_then(|p| p.trim_start_matches('#').parse::<i64>().ok()) {
+        Some(id) => id,
+        None => return "Usage: @reset npc <prototype_id> [max <n>]\n".to_string(),
+    };
+    let (_, max_count) = parse_reset_options(&args[1..]);
+    let area = state.get_room_area(room_id).await.unwrap().name;
+
+    let prototype = match crate::database::npc_queries::get_prototype(&state.db, prototype_id).await {
+        Ok(p) => p,
+        Err(e) => return format!("{}.\n", e),
+    };
+
+    let reset_id = reset_queries::create_reset(
+        &state.db,
+        &area,
+        room_id,
+        None,
+        "npc",
+        Some(prototype_id),
+        &prototype.name,
+        "",
+        "npc",
+        "{}",
+        max_count,
+    )
+    .await
+    .unwrap();
+
+    format!("Reset #{} added: up to {} x {} (area '{}').\n", reset_id, max_count, prototype.name, area)
+}
+
+async fn list_resets(state: &Arc<GameState>, room_id: &str, area: Option<&str>) -> String {
+    let resets = match area {
+        Some(area) => reset_queries::get_resets(&state.db, Some(area)).await.unwrap(),
+        None => reset_queries::get_resets_in_room(&state.db, room_id).await.unwrap(),
+    };
+
+    if resets.is_empty() {
+        return "No resets defined.\n".to_string();
+    }
+
+    let mut response = "Resets:\n".to_string();
+    for r in resets {
+        let place = match &r.container_id {
+            Some(container) => format!("container {}", container),
+            None => format!("room {}", r.room_id),
+        };
+        response.push_str(&format!(
+            "  #{:<4} [{}] {} {} x{} in {}\n",
+            r.id, r.area, r.reset_type, r.name, r.max_count, place
+        ));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::npc_queries;
+
+    async fn add_object_reset(state: &Arc<GameState>, container_id: Option<&str>, max_count: i64) -> i64 {
+        reset_queries::create_reset(
+            &state.db, "default", "room_start", container_id, "object", None, "coin", "A coin.", "item", "{}", max_count,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn objects_are_topped_up_to_max_count() {
+        let state = GameState::in_memory().await;
+        let reset_id = add_object_reset(&state, None, 3).await;
+
+        let summary = run_resets(&state, None).await.unwrap();
+        assert_eq!(summary.objects, 3);
+        assert_eq!(run_resets(&state, None).await.unwrap().objects, 0);
+
+        // Taking one away brings back just that one
+        let coins = state.get_objects_in_container("room_start").await.unwrap();
+        assert_eq!(coins.len(), 3);
+        assert!(coins.iter().all(|c| c.properties.contains(&format!("\"reset_id\":{}", reset_id))));
+        object_queries::delete_object(&state.db, &coins[0].id).await.unwrap();
+        assert_eq!(run_resets(&state, None).await.unwrap().objects, 1);
+        assert_eq!(state.get_objects_in_container("room_start").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn objects_go_into_their_container() {
+        let state = GameState::in_memory().await;
+        let chest = state.create_object("chest", "A chest.", "item", Some("room_start")).await.unwrap();
+        add_object_reset(&state, Some(&chest.id), 2).await;
+
+        assert_eq!(run_resets(&state, None).await.unwrap().objects, 2);
+        assert_eq!(state.get_objects_in_container(&chest.id).await.unwrap().len(), 2);
+
+        // Copies carried off elsewhere no longer count
+        let coins = state.get_objects_in_container(&chest.id).await.unwrap();
+        object_queries::move_object(&state.db, &coins[0].id, "room_start").await.unwrap();
+        assert_eq!(run_resets(&state, None).await.unwrap().objects, 1);
+    }
+
+    #[tokio::test]
+    async fn npcs_count_wherever_they_wander() {
+        let state = GameState::in_memory().await;
+        let prototype_id = npc_queries::create_prototype(&state.db, "rat", "A rat.").await.unwrap();
+        reset_queries::create_reset(
+            &state.db, "default", "room_start", None, "npc", Some(prototype_id), "rat", "", "npc", "{}", 2,
+        )
+        .await
+        .unwrap();
+
+        let summary = run_resets(&state, None).await.unwrap();
+        assert_eq!(summary.npcs, 2);
+        assert!(summary.failures.is_empty());
+
+        let hall = state.create_object("Hall", "A hall.", "room", None).await.unwrap();
+        let rats = state.get_npcs_in_room("room_start").await.unwrap();
+        npc_queries::move_npc(&state.db, &rats[0].id, &hall.id).await.unwrap();
+        assert_eq!(run_resets(&state, None).await.unwrap().npcs, 0);
+
+        npc_queries::delete_npc(&state.db, &rats[1].id).await.unwrap();
+        assert_eq!(run_resets(&state, None).await.unwrap().npcs, 1);
+    }
+
+    #[tokio::test]
+    async fn only_the_named_area_resets() {
+        let state = GameState::in_memory().await;
+        add_object_reset(&state, None, 1).await;
+
+        assert_eq!(run_resets(&state, Some("elsewhere")).await.unwrap().objects, 0);
+        assert_eq!(run_resets(&state, Some("default")).await.unwrap().objects, 1);
+    }
+}
@@ -0,0 +1,158 @@
+// Room flags and sectors: light, entry rules, stamina and death traps
+
+use crate::database::{npc_queries, object_queries, player_queries};
+use crate::game::GameState;
+use crate::models::{GameObject, Player, Sector, PRIVATE_ROOM_LIMIT, ROOM_FLAGS};
+use std::sync::Arc;
+
+const START_ROOM: &str = "room_start";
+
+/// True if the player can see in this room: it isn't dark, or someone here carries a light.
+/// Staff always see.
+pub async fn can_see(state: &Arc<GameState>, player: &Player, room: &GameObject) -> Result<bool, String> {
+    if player.is_staff() || !room.room_flags().is_dark() {
+        return Ok(true);
+    }
+    if state.carries_property(&player.id, "light").await? {
+        return Ok(true);
+    }
+    for other in state.get_players_in_room(&room.id).await? {
+        if other.id != player.id && state.is_online(&other.id).await && state.carries_property(&other.id, "light").await? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Checks whether the player may walk into a room, returning the refusal if not.
+pub async fn check_entry(state: &Arc<GameState>, player: &Player, room: &GameObject) -> Result<(), String> {
+    if player.is_staff() {
+        return Ok(());
+    }
+    let flags = room.room_flags();
+
+    if flags.has("private") {
+        let mut occupants = 0;
+        for other in state.get_players_in_room(&room.id).await? {
+            if state.is_online(&other.id).await {
+                occupants += 1;
+            }
+        }
+        if occupants >= PRIVATE_ROOM_LIMIT {
+            return Err("That room is private, and there's no space for you.\n".to_string());
+        }
+    }
+
+    match flags.sector {
+        Sector::Water if !state.carries_property(&player.id, "boat").await? => {
+            Err("You need a boat to go there.\n".to_string())
+        }
+        Sector::Underwater if !state.carries_property(&player.id, "breathing").await? => {
+            Err("You'd drown without something to breathe with.\n".to_string())
+        }
+        _ if player.current_stamina < flags.sector.move_cost() => {
+            Err("You are too exhausted to go on.\n".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Charges the stamina it takes to walk into a room.
+pub async fn spend_move_stamina(state: &Arc<GameState>, player: &Player, room: &GameObject) -> Result<(), String> {
+    if player.is_staff() {
+        return Ok(());
+    }
+    let stamina = (player.current_stamina - room.room_flags().sector.move_cost()).max(0);
+    player_queries::update_player_stat(&state.db, &player.id, "current_stamina", stamina).await
+}
+
+/// Kills a player who has walked into a death trap and sends them back to the start.
+/// Returns None if the room isn't a death trap; staff are only warned.
+pub async fn spring_death_trap(state: &Arc<GameState>, player: &Player, room: &GameObject) -> Option<String> {
+    if !room.room_flags().has("death-trap") {
+        return None;
+    }
+    if player.is_staff() {
+        return Some("[This room is a death trap; you are spared.]\n".to_string());
+    }
+
+    player_queries::update_player_health(&state.db, &player.id, player.max_health).await.ok()?;
+    player_queries::update_player_location(&state.db, &player.id, START_ROOM).await.ok()?;
+    npc_queries::clear_targets_on(&state.db, &player.id).await.ok()?;
+    let _ = state.broadcast_tx.send(format!("{} has met a grisly end!\n", player.username));
+    Some(format!(
+        "{}\n{}\nYou have been killed! You awaken back where your adventure began.\n",
+        room.name, room.description
+    ))
+}
+
+/// Gives every online player back a twentieth of their stamina each tick, twice that
+/// when they are sheltered indoors.
+pub async fn regenerate_stamina(state: &Arc<GameState>) -> Result<(), String> {
+    let player_ids: Vec<String> = state.sessions.read().await.keys().cloned().collect();
+    for player_id in player_ids {
+        let player = player_queries::get_player_by_id(&state.db, &player_id).await?;
+        if player.current_stamina < player.max_stamina {
+            let mut gain = (player.max_stamina / 20).max(1);
+            if state.get_room(&player.current_location).await?.room_flags().is_indoors() {
+                gain *= 2;
+            }
+            let stamina = (player.current_stamina + gain).min(player.max_stamina);
+            player_queries::update_player_stat(&state.db, &player.id, "current_stamina", stamina).await?;
+        }
+    }
+    Ok(())
+}
+
+/// One line listing a room's flags and sector, shown to staff when they look.
+pub fn describe(room: &GameObject) -> String {
+    let flags = room.room_flags();
+    format!(
+        "[Sector: {}  Flags: {}]\n",
+        flags.sector.as_str(),
+        if flags.flags.is_empty() { "none".to_string() } else { flags.flags.join(", ") }
+    )
+}
+
+// admin commands
+/// Sets flags and the sector on the current room.
+pub async fn handle_admin_rset(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let room = state.get_room(&player.current_location).await.unwrap();
+    let sectors: Vec<&str> = Sector::ALL.iter().map(|s| s.as_str()).collect();
+    let usage = format!(
+        "Usage: @rset <flag> <on|off>, @rset sector <type>\nFlags: {}\nSectors: {}\n",
+        ROOM_FLAGS.join(", "),
+        sectors.join(", ")
+    );
+
+    if parts.len() != 3 {
+        return format!("{}{}", describe(&room), usage);
+    }
+
+    let mut flags = room.room_flags();
+    let field = parts[1].to_lowercase();
+    if field == "sector" {
+        match Sector::parse(parts[2]) {
+            Some(sector) => flags.sector = sector,
+            None => return usage,
+        }
+    } else if ROOM_FLAGS.contains(&field.as_str()) {
+        match parts[2].to_lowercase().as_str() {
+            "on" | "yes" | "1" => flags.set(&field, true),
+            "off" | "no" | "0" => flags.set(&field, false),
+            _ => return usage,
+        }
+    } else {
+        return usage;
+    }
+
+    object_queries::update_object_properties(&state.db, &room.id, &flags.apply_to(&room.properties))
+        .await
+        .unwrap();
+    let room = state.get_room(&room.id).await.unwrap();
+    format!("Room updated. {}", describe(&room))
+}
@@ -0,0 +1,409 @@
+// Embedded Rhai scripting for rooms, objects and NPCs
+//
+// Scripts never touch the database directly. Each run gets a snapshot of the players involved,
+// queues its side effects through the API functions below, and the queue is applied once the
+// script has finished. A script that returns `false` blocks the action that triggered it
+// (entering a room, picking up an item).
+//
+// Script API:
+//   send(player_id, text)            message one player
+//   send_room(room_id, text)         message everyone in a room
+//   move_player(player_id, room_id)  move a player involved in the event
+//   create_object(name, description, type, container_id) -> id
+//   get_stat(player_id, stat) -> int read a stat of a player involved in the event
+//   set_stat(player_id, stat, value) change a stat of a player involved in the event
+// Players involved are the actor and everyone in the room; move_player and set_stat ignore anyone else.
+// Scope: actor (map with id, name and stats; id is "" when there is none), self_id, room_id,
+// message (text spoken for on_say), event.
+
+use crate::database::{npc_queries, object_queries, player_queries, script_queries};
+use crate::game::GameState;
+use crate::models::script::SCRIPT_EVENTS;
+use crate::models::{GameObject, Player, Role};
+use rhai::{Dynamic, Engine, Map, Scope};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const MAX_ACTIONS: usize = 50;
+
+enum ScriptAction {
+    Send { player_id: String, text: String },
+    SendRoom { room_id: String, text: String },
+    MovePlayer { player_id: String, room_id: String },
+    CreateObject { id: String, name: String, description: String, object_type: String, container_id: String },
+    SetStat { player_id: String, stat: String, value: i64 },
+}
+
+pub struct ScriptContext<'a> {
+    pub event: &'a str,
+    pub self_id: &'a str,
+    pub room_id: &'a str,
+    pub actor_id: Option<&'a str>,
+    pub message: &'a str,
+}
+
+fn player_stats(p: &Player) -> HashMap<String, i64> {
+    HashMap::from([
+        ("current_health".to_string(), p.current_health),
+        ("max_health".to_string(), p.max_health),
+        ("current_stamina".to_string(), p.current_stamina),
+        ("max_stamina".to_string(), p.max_stamina),
+        ("dexterity".to_string(), p.dexterity),
+        ("strength".to_string(), p.strength),
+        ("vitality".to_string(), p.vitality),
+        ("perception".to_string(), p.perception),
+        ("willpower".to_string(), p.willpower),
+        ("charisma".to_string(), p.charisma),
+        ("level".to_string(), p.level),
+        ("gold".to_string(), p.gold),
+    ])
+}
+
+/// Compiles and runs one script in a locked-down engine. Entirely synchronous so the
+/// (non-Send) engine never lives across an await.
+fn run_script(
+    code: &str,
+    ctx: &ScriptContext,
+    actor: Option<&Player>,
+    snapshot: HashMap<String, HashMap<String, i64>>,
+    max_operations: u64,
+) -> Result<(bool, Vec<ScriptAction>), String> {
+    let actions: Rc<RefCell<Vec<ScriptAction>>> = Rc::new(RefCell::new(Vec::new()));
+    let snapshot = Rc::new(snapshot);
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(max_operations);
+    engine.set_max_call_levels(16);
+    engine.set_max_expr_depths(32, 16);
+    engine.set_max_string_size(4096);
+    engine.set_max_array_size(256);
+    engine.set_max_map_size(256);
+    engine.disable_symbol("eval");
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+
+    let queue = actions.clone();
+    engine.register_fn("send", move |player_id: &str, text: &str| {
+        push_action(&queue, ScriptAction::Send { player_id: player_id.to_string(), text: text.to_string() });
+    });
+    let queue = actions.clone();
+    engine.register_fn("send_room", move |room_id: &str, text: &str| {
+        push_action(&queue, ScriptAction::SendRoom { room_id: room_id.to_string(), text: text.to_string() });
+    });
+    let queue = actions.clone();
+    let involved = snapshot.clone();
+    engine.register_fn("move_player", move |player_id: &str, room_id: &str| {
+        if involved.contains_key(player_id) {
+            push_action(&queue, ScriptAction::MovePlayer { player_id: player_id.to_string(), room_id: room_id.to_string() });
+        }
+    });
+    let queue = actions.clone();
+    engine.register_fn("create_object", move |name: &str, description: &str, object_type: &str, container_id: &str| {
+        let id = Uuid::new_v4().to_string();
+        push_action(&queue, ScriptAction::CreateObject {
+            id: id.clone(),
+            name: name.to_string(),
+            description: description.to_string(),
+            object_type: object_type.to_string(),
+            container_id: container_id.to_string(),
+        });
+        id
+    });
+    let stats = snapshot.clone();
+    engine.register_fn("get_stat", move |player_id: &str, stat: &str| -> i64 {
+        stats.get(player_id).and_then(|s| s.get(stat)).copied().unwrap_or(0)
+    });
+    let queue = actions.clone();
+    let involved = snapshot.clone();
+    engine.register_fn("set_stat", move |player_id: &str, stat: &str, value: i64| {
+        if involved.contains_key(player_id) {
+            push_action(&queue, ScriptAction::SetStat { player_id: player_id.to_string(), stat: stat.to_string(), value });
+        }
+    });
+
+    let mut actor_map = Map::new();
+    actor_map.insert("id".into(), Dynamic::from(ctx.actor_id.unwrap_or("").to_string()));
+    if let Some(p) = actor {
+        actor_map.insert("name".into(), Dynamic::from(p.username.clone()));
+        for (stat, value) in player_stats(p) {
+            actor_map.insert(stat.into(), Dynamic::from(value));
+        }
+    }
+
+    let mut scope = Scope::new();
+    scope.push_constant("actor", actor_map);
+    scope.push_constant("self_id", ctx.self_id.to_string());
+    scope.push_constant("room_id", ctx.room_id.to_string());
+    scope.push_constant("message", ctx.message.to_string());
+    scope.push_constant("event", ctx.event.to_string());
+
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, code)
+        .map_err(|e| e.to_string())?;
+    let allowed = result.as_bool().unwrap_or(true);
+
+    drop(engine);
+    let actions = Rc::try_unwrap(actions)
+        .map(|cell| cell.into_inner())
+        .unwrap_or_default();
+    Ok((allowed, actions))
+}
+
+fn push_action(queue: &Rc<RefCell<Vec<ScriptAction>>>, action: ScriptAction) {
+    let mut queue = queue.borrow_mut();
+    if queue.len() < MAX_ACTIONS {
+        queue.push(action);
+    }
+}
+
+async fn apply_actions(state: &Arc<GameState>, actions: Vec<ScriptAction>) -> Result<(), String> {
+    for action in actions {
+        match action {
+            ScriptAction::Send { player_id, text } => {
+                state.send_to_player(&player_id, &format!("{}\n", text)).await;
+            }
+            ScriptAction::SendRoom { room_id, text } => {
+                state.send_to_room(&room_id, &format!("{}\n", text), None).await;
+            }
+            ScriptAction::MovePlayer { player_id, room_id } => {
+                state.move_player_to_room(&player_id, &room_id).await?;
+            }
+            ScriptAction::CreateObject { id, name, description, object_type, container_id } => {
+                if object_type == "room" {
+                    return Err("Scripts can't create rooms".to_string());
+                }
+                let obj = GameObject::new(id, name, description, object_type, Some(container_id));
+                object_queries::create_object(&state.db, &obj).await?;
+            }
+            ScriptAction::SetStat { player_id, stat, value } => {
+                player_queries::update_player_stat(&state.db, &player_id, &stat, value).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the script `owner` has for `ctx.event`, if any. Returns false if the script blocked the action.
+pub async fn fire(state: &Arc<GameState>, owner_type: &str, owner_id: &str, ctx: ScriptContext<'_>) -> bool {
+    let script = match script_queries::get_script(&state.db, owner_type, owner_id, ctx.event).await {
+        Ok(Some(script)) => script,
+        _ => return true,
+    };
+
+    // Everyone in the room plus the actor, who may be arriving from elsewhere
+    let mut players = state.get_players_in_room(ctx.room_id).await.unwrap_or_default();
+    if let Some(actor_id) = ctx.actor_id {
+        if !players.iter().any(|p| p.id == actor_id) {
+            if let Ok(actor) = player_queries::get_player_by_id(&state.db, actor_id).await {
+                players.push(actor);
+            }
+        }
+    }
+    let actor = ctx.actor_id.and_then(|id| players.iter().find(|p| p.id == id)).cloned();
+    let snapshot = players.iter().map(|p| (p.id.clone(), player_stats(p))).collect();
+
+    let outcome = run_script(&script.code, &ctx, actor.as_ref(), snapshot, state.config.script_max_operations);
+    match outcome {
+        Ok((allowed, actions)) => {
+            if let Err(e) = apply_actions(state, actions).await {
+                eprintln!("Script #{} ({} {}) failed: {}", script.id, owner_type, owner_id, e);
+            }
+            allowed
+        }
+        Err(e) => {
+            eprintln!("Script #{} ({} {}) failed: {}", script.id, owner_type, owner_id, e);
+            true
+        }
+    }
+}
+
+/// Fires an event on a room, every object in it and every NPC in it.
+pub async fn fire_room_event(
+    state: &Arc<GameState>,
+    event: &str,
+    room_id: &str,
+    actor_id: Option<&str>,
+    message: &str,
+) -> bool {
+    let mut allowed = fire(state, "object", room_id, ScriptContext {
+        event,
+        self_id: room_id,
+        room_id,
+        actor_id,
+        message,
+    }).await;
+
+    for obj in state.get_objects_in_container(room_id).await.unwrap_or_default() {
+        allowed &= fire(state, "object", &obj.id, ScriptContext {
+            event,
+            self_id: &obj.id,
+            room_id,
+            actor_id,
+            message,
+        }).await;
+    }
+
+    for npc in state.get_npcs_in_room(room_id).await.unwrap_or_default() {
+        allowed &= fire(state, "npc", &npc.prototype_id.to_string(), ScriptContext {
+            event,
+            self_id: &npc.id,
+            room_id,
+            actor_id,
+            message,
+        }).await;
+    }
+
+    allowed
+}
+
+/// Runs every on_tick script. Objects are placed in the room they (or their holder) are in.
+pub async fn run_tick_scripts(state: &Arc<GameState>) -> Result<(), String> {
+    let scripts = script_queries::get_scripts_for_event(&state.db, "on_tick").await?;
+    if scripts.is_empty() {
+        return Ok(());
+    }
+
+    let npcs = npc_queries::get_all_npcs(&state.db).await?;
+
+    for script in scripts {
+        match script.owner_type.as_str() {
+            "npc" => {
+                for npc in npcs.iter().filter(|n| n.prototype_id.to_string() == script.owner_id) {
+                    fire(state, "npc", &script.owner_id, ScriptContext {
+                        event: "on_tick",
+                        self_id: &npc.id,
+                        room_id: &npc.room_id,
+                        actor_id: None,
+                        message: "",
+                    }).await;
+                }
+            }
+            _ => {
+                let room_id = match object_room(state, &script.owner_id).await {
+                    Some(room_id) => room_id,
+                    None => continue,
+                };
+                fire(state, "object", &script.owner_id, ScriptContext {
+                    event: "on_tick",
+                    self_id: &script.owner_id,
+                    room_id: &room_id,
+                    actor_id: None,
+                    message: "",
+                }).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The room an object is in: itself for rooms, its container, or the location of whoever holds it.
+pub async fn object_room(state: &Arc<GameState>, object_id: &str) -> Option<String> {
+    let obj = object_queries::get_object(&state.db, object_id).await.ok()?;
+    if obj.object_type == "room" {
+        return Some(obj.id);
+    }
+
+    let container = obj.container_id?;
+    if state.get_room(&container).await.is_ok() {
+        return Some(container);
+    }
+    match player_queries::get_player_by_id(&state.db, &container).await {
+        Ok(holder) => Some(holder.current_location),
+        Err(_) => None,
+    }
+}
+
+// admin commands
+pub async fn handle_admin_script(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let usage = "Usage: @script list <target>\n\
+                 \x20      @script show <target> <event>\n\
+                 \x20      @script set <target> <event> <code>\n\
+                 \x20      @script clear <target> <event>\n\
+                 Targets: here (this room), an object name, or #<npc_prototype_id>\n";
+
+    if parts.len() < 3 {
+        return usage.to_string();
+    }
+
+    let (owner_type, owner_id) = match resolve_owner(&state, &player, parts[2]).await {
+        Ok(owner) => owner,
+        Err(e) => return e,
+    };
+
+    let event = parts.get(3).map(|e| e.to_lowercase());
+    if let Some(event) = &event {
+        if !SCRIPT_EVENTS.contains(&event.as_str()) {
+            return format!("Unknown event. Events: {}\n", SCRIPT_EVENTS.join(", "));
+        }
+    }
+
+    match (parts[1].to_lowercase().as_str(), event) {
+        ("list", _) => {
+            let scripts = script_queries::get_scripts_for_owner(&state.db, &owner_type, &owner_id).await.unwrap();
+            if scripts.is_empty() {
+                return "No scripts attached.\n".to_string();
+            }
+            let mut response = "Scripts:\n".to_string();
+            for s in scripts {
+                response.push_str(&format!("  {:<9} {} chars\n", s.event, s.code.len()));
+            }
+            response
+        }
+        ("show", Some(event)) => {
+            match script_queries::get_script(&state.db, &owner_type, &owner_id, &event).await.unwrap() {
+                Some(script) => format!("{}:\n{}\n", event, script.code),
+                None => "No script for that event.\n".to_string(),
+            }
+        }
+        ("set", Some(event)) if parts.len() > 4 => {
+            let code = parts[4..].join(" ");
+            // Compile once so builders see syntax errors straight away
+            if let Err(e) = Engine::new().compile(&code) {
+                return format!("Script error: {}\n", e);
+            }
+            script_queries::set_script(&state.db, &owner_type, &owner_id, &event, &code).await.unwrap();
+            format!("Script for {} saved.\n", event)
+        }
+        ("clear", Some(event)) => {
+            match script_queries::delete_script(&state.db, &owner_type, &owner_id, &event).await.unwrap() {
+                true => format!("Script for {} removed.\n", event),
+                false => "No script for that event.\n".to_string(),
+            }
+        }
+        _ => usage.to_string(),
+    }
+}
+
+async fn resolve_owner(state: &Arc<GameState>, player: &Player, target: &str) -> Result<(String, String), String> {
+    if target.eq_ignore_ascii_case("here") {
+        return Ok(("object".to_string(), player.current_location.clone()));
+    }
+
+    if let Some(id) = target.strip_prefix('#') {
+        // Prototypes are shared by every area, so only admins may script them
+        if player.role() < Role::Admin {
+            return Err("Only admins can script NPC prototypes.\n".to_string());
+        }
+        let prototype_id = id.parse::<i64>().map_err(|_| "Invalid NPC prototype id.\n".to_string())?;
+        npc_queries::get_prototype(&state.db, prototype_id)
+            .await
+            .map_err(|e| format!("{}.\n", e))?;
+        return Ok(("npc".to_string(), prototype_id.to_string()));
+    }
+
+    let mut objects = state.get_objects_in_container(&player.current_location).await.unwrap_or_default();
+    objects.extend(state.get_objects_in_container(&player.id).await.unwrap_or_default());
+    objects
+        .into_iter()
+        .find(|o| o.name.eq_ignore_ascii_case(target))
+        .map(|o| ("object".to_string(), o.id))
+        .ok_or_else(|| "You don't see that here.\n".to_string())
+}
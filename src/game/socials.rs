@@ -0,0 +1,246 @@
+// Emotes, poses and socials
+
+use crate::database::{player_queries, social_queries};
+use crate::game::GameState;
+use crate::models::character::Pronouns;
+use crate::models::{Gender, Social, SOCIAL_FIELDS};
+use std::sync::Arc;
+
+const MAX_EMOTE_LENGTH: usize = 240;
+const MAX_POSE_LENGTH: usize = 80;
+
+/// Someone named in a social message.
+struct Party {
+    name: String,
+    pronouns: Pronouns,
+}
+
+/// Fills in `$n $e $m $s` for the actor and `$N $E $M $S` for the target.
+fn substitute(template: &str, actor: &Party, target: Option<&Party>) -> String {
+    let mut out = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+        let code = match chars.next() {
+            Some(code) => code,
+            None => {
+                out.push('$');
+                break;
+            }
+        };
+        let party = match code.is_ascii_uppercase() {
+            true => target.unwrap_or(actor),
+            false => actor,
+        };
+        match code.to_ascii_lowercase() {
+            'n' => out.push_str(&party.name),
+            'e' => out.push_str(party.pronouns.subject),
+            'm' => out.push_str(party.pronouns.object),
+            's' => out.push_str(party.pronouns.possessive),
+            _ => {
+                out.push('$');
+                out.push(code);
+            }
+        }
+    }
+
+    match out.is_empty() {
+        true => out,
+        false => out + "\n",
+    }
+}
+
+/// Performs a social if one by this name exists. Returns None so the caller can try
+/// something else when it doesn't.
+pub async fn perform(state: &Arc<GameState>, player_id: &str, parts: &[&str]) -> Option<String> {
+    let social = social_queries::get_social(&state.db, parts[0]).await.ok()??;
+    let player = player_queries::get_player_by_id(&state.db, player_id).await.ok()?;
+    let actor = Party {
+        name: player.username.clone(),
+        pronouns: player.pronouns(),
+    };
+
+    if parts.len() < 2 {
+        let room_message = substitute(&social.no_target_room, &actor, None);
+        if !room_message.is_empty() {
+            state.send_to_room(&player.current_location, &room_message, Some(player_id)).await;
+        }
+        return Some(substitute(&social.no_target_actor, &actor, None));
+    }
+
+    // a player in the room, then an NPC
+    let name = parts[1..].join(" ").to_lowercase();
+    let players = state.get_players_in_room(&player.current_location).await.ok()?;
+    let mut target_id = None;
+    let target = match players.iter().find(|p| p.username.to_lowercase() == name) {
+        Some(p) if state.is_online(&p.id).await => {
+            target_id = Some(p.id.clone());
+            Party {
+                name: p.username.clone(),
+                pronouns: p.pronouns(),
+            }
+        }
+        _ => {
+            let npcs = state.get_npcs_in_room(&player.current_location).await.ok()?;
+            match npcs.into_iter().find(|n| n.matches(&name)) {
+                Some(n) => Party {
+                    name: n.name,
+                    pronouns: Gender::Neutral.pronouns(),
+                },
+                None => return Some("They aren't here.\n".to_string()),
+            }
+        }
+    };
+
+    if target_id.as_deref() == Some(player_id) {
+        return Some(substitute(&social.no_target_actor, &actor, None));
+    }
+
+    if let Some(id) = &target_id {
+        let target_message = substitute(&social.target, &actor, Some(&target));
+        if !target_message.is_empty() {
+            state.send_to_player(id, &target_message).await;
+        }
+    }
+    let room_message = substitute(&social.room, &actor, Some(&target));
+    if !room_message.is_empty() {
+        for p in players {
+            if p.id != player_id && Some(&p.id) != target_id.as_ref() {
+                state.send_to_player(&p.id, &room_message).await;
+            }
+        }
+    }
+    Some(substitute(&social.actor, &actor, Some(&target)))
+}
+
+// regular commands
+/// `emote waves hello` shows "Bob waves hello" to the room.
+pub async fn handle_emote(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Emote what?\n".to_string();
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let text = parts[1..].join(" ");
+    if text.chars().count() > MAX_EMOTE_LENGTH {
+        return "That's too long.\n".to_string();
+    }
+
+    let message = format!("{} {}\n", player.username, text);
+    state.send_to_room(&player.current_location, &message, Some(player_id)).await;
+    message
+}
+
+/// Sets how the player appears in room descriptions, e.g. `pose is leaning against the wall`.
+/// With no text, clears it.
+pub async fn handle_pose(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let pose = parts[1..].join(" ");
+    if pose.chars().count() > MAX_POSE_LENGTH {
+        return format!("Please keep your pose under {} characters.\n", MAX_POSE_LENGTH);
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let mut sessions = state.sessions.write().await;
+    let session = match sessions.get_mut(player_id) {
+        Some(s) => s,
+        None => return String::new(),
+    };
+
+    match pose.is_empty() {
+        true => {
+            session.pose = None;
+            "Pose cleared.\n".to_string()
+        }
+        false => {
+            let response = format!("Others now see: {} {}\n", player.username, pose);
+            session.pose = Some(pose);
+            response
+        }
+    }
+}
+
+pub async fn handle_socials(state: Arc<GameState>) -> String {
+    let socials = social_queries::list_socials(&state.db).await.unwrap();
+    if socials.is_empty() {
+        return "There are no socials.\n".to_string();
+    }
+
+    let names: Vec<String> = socials.into_iter().map(|s| s.name).collect();
+    let mut response = "Socials (use them alone or with a target, e.g. 'smile bob'):\n".to_string();
+    for row in names.chunks(8) {
+        response.push_str(&format!("  {}\n", row.iter().map(|n| format!("{:<10}", n)).collect::<String>().trim_end()));
+    }
+    response
+}
+
+// admin commands
+/// `@social <name>` shows a social; `@social <name> <field> <message>` creates or edits one;
+/// `@social <name> delete` removes it.
+pub async fn handle_admin_social(state: Arc<GameState>, parts: &[&str]) -> String {
+    let usage = format!(
+        "Usage: @social <name> [delete | <field> <message>]\nFields: {}\n\
+         In messages, $n/$N are the actor's/target's names and $e $m $s ($E $M $S) their pronouns.\n",
+        SOCIAL_FIELDS.join(", ")
+    );
+    if parts.len() < 2 {
+        return usage;
+    }
+
+    let name = parts[1].to_lowercase();
+    let existing = social_queries::get_social(&state.db, &name).await.unwrap();
+
+    if parts.len() == 2 {
+        return match existing {
+            Some(s) => format!(
+                "Social '{}':\n  noarg_actor: {}\n  noarg_room:  {}\n  actor:       {}\n  target:      {}\n  room:        {}\n",
+                s.name, s.no_target_actor, s.no_target_room, s.actor, s.target, s.room
+            ),
+            None => format!("There is no social called '{}'.\n{}", name, usage),
+        };
+    }
+
+    if parts[2].eq_ignore_ascii_case("delete") {
+        return match social_queries::delete_social(&state.db, &name).await.unwrap() {
+            true => format!("Social '{}' deleted.\n", name),
+            false => format!("There is no social called '{}'.\n", name),
+        };
+    }
+
+    let column = match parts[2].to_lowercase().as_str() {
+        "noarg_actor" => "no_target_actor",
+        "noarg_room" => "no_target_room",
+        "actor" => "actor",
+        "target" => "target",
+        "room" => "room",
+        _ => return usage,
+    };
+    if !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return "Social names must be a single word of letters.\n".to_string();
+    }
+
+    if existing.is_none() {
+        let social = Social {
+            name: name.clone(),
+            no_target_actor: String::new(),
+            no_target_room: String::new(),
+            actor: String::new(),
+            target: String::new(),
+            room: String::new(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        social_queries::create_social(&state.db, &social).await.unwrap();
+    }
+
+    social_queries::update_social_field(&state.db, &name, column, &parts[3..].join(" "))
+        .await
+        .unwrap();
+    format!("Social '{}' updated.\n", name)
+}
@@ -2,10 +2,12 @@
 
 use crate::config::ServerConfig;
 use crate::database;
-use crate::models::{Player, GameObject, Session};
+use crate::game::accounts::{self, LoginFailures};
+use crate::game::validation;
+use crate::models::{Area, CharacterDraft, Npc, Player, GameObject, Role, RoomExit, Session};
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use tokio::sync::{broadcast, RwLock};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -14,6 +16,10 @@ pub struct GameState {
     pub sessions: Arc<RwLock<HashMap<String, Session>>>,
     pub broadcast_tx: broadcast::Sender<String>,
     pub config: ServerConfig,
+    /// Recent messages per channel, oldest first, for replay.
+    pub channel_history: RwLock<HashMap<&'static str, VecDeque<String>>>,
+    /// Recent failed logins, keyed by "ip <address>" and "account <name>".
+    pub login_failures: RwLock<HashMap<String, LoginFailures>>,
 }
 
 impl GameState {
@@ -30,6 +36,7 @@ impl GameState {
 
         // Initialize database schema
         database::initialize_database(&db).await?;
+        accounts::bootstrap_owner(&db, &config).await?;
 
         let (tx, _) = broadcast::channel(100);
 
@@ -38,6 +45,8 @@ impl GameState {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             broadcast_tx: tx,
             config,
+            channel_history: RwLock::new(HashMap::new()),
+            login_failures: RwLock::new(HashMap::new()),
         })
     }
 
@@ -45,41 +54,33 @@ impl GameState {
         let player = database::player_queries::get_player_by_username(&self.db, username).await?;
 
         match player {
-            Some(p) => {
-                use argon2::password_hash::{PasswordHash, PasswordVerifier};
-                let parsed_hash = PasswordHash::new(&p.password_hash)
-                    .map_err(|_| "Invalid password hash".to_string())?;
-                
-                argon2::Argon2::default()
-                    .verify_password(password.as_bytes(), &parsed_hash)
-                    .map_err(|_| "Invalid credentials".to_string())?;
-                
-                Ok(p)
+            Some(p) if accounts::verify_password(&p.password_hash, password) => Ok(p),
+            Some(_) => Err("Invalid credentials".to_string()),
+            None => {
+                // as slow as a wrong password, so timing doesn't reveal which names exist
+                accounts::verify_password(accounts::dummy_hash(), password);
+                Err("Invalid credentials".to_string())
             }
-            None => Err("Invalid credentials".to_string()),
         }
     }
 
-    pub async fn register_player(&self, username: &str, password: &str, is_admin: i64) -> Result<Player, String> {
-        use argon2::{
-            password_hash::{
-                rand_core::OsRng,
-                PasswordHasher, SaltString
-            },
-            Argon2
-        };    
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = argon2::Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| format!("Password hashing failed: {}", e))?
-            .to_string();
+    pub async fn register_player(
+        &self,
+        username: &str,
+        password: &str,
+        role: Role,
+        draft: &CharacterDraft,
+    ) -> Result<Player, String> {
+        validation::check_username(self, username).await?;
+        validation::check_password(&self.config, username, password)?;
+        let password_hash = accounts::hash_password(password)?;
         let player = Player::new(
             Uuid::new_v4().to_string(),
             username.to_string(),
             password_hash,
-            is_admin,
-        ).await;
+            role,
+            draft,
+        );
 
         database::player_queries::create_player(&self.db, &player).await?;
 
@@ -90,10 +91,27 @@ impl GameState {
         database::object_queries::get_room(&self.db, room_id).await
     }
 
+    /// The area a room belongs to; rooms without one count as the default area.
+    pub async fn get_room_area(&self, room_id: &str) -> Result<Area, String> {
+        let room = self.get_room(room_id).await?;
+        match room.area_id {
+            Some(area_id) => database::area_queries::get_area(&self.db, area_id).await,
+            None => database::area_queries::get_area_by_name(&self.db, "default")
+                .await?
+                .ok_or_else(|| "Area not found".to_string()),
+        }
+    }
+
     pub async fn get_objects_in_container(&self, container_id: &str) -> Result<Vec<GameObject>, String> {
         database::object_queries::get_objects_in_container(&self.db, container_id).await
     }
 
+    /// True if something carried by `owner_id` has `property` set, e.g. "light" or "boat".
+    pub async fn carries_property(&self, owner_id: &str, property: &str) -> Result<bool, String> {
+        let items = self.get_objects_in_container(owner_id).await?;
+        Ok(items.iter().any(|item| item.has_property(property)))
+    }
+
     pub async fn get_players_in_room(&self, room_id: &str) -> Result<Vec<Player>, String> {
         database::player_queries::get_players_in_room(&self.db, room_id).await
     }
@@ -102,30 +120,29 @@ impl GameState {
         // Verify both rooms exist
         self.get_room(room_id).await?;
         self.get_room(destination_id).await?;
-        
-        sqlx::query(
-            "INSERT OR REPLACE INTO room_exits (room_id, direction, destination_id) VALUES (?, ?, ?)"
-        )
-        .bind(room_id)
-        .bind(direction.to_lowercase())
-        .bind(destination_id)
-        .execute(&self.db)
-        .await
-        .map_err(|e| format!("Failed to add exit: {}", e))?;
-        
-        Ok(())
+
+        database::exit_queries::create_exit(&self.db, room_id, direction, destination_id).await
     }
     
-    pub async fn get_exits(&self, room_id: &str) -> Result<Vec<(String, String)>, String> {
-        let exits: Vec<(String, String)> = sqlx::query_as(
-            "SELECT direction, destination_id FROM room_exits WHERE room_id = ?"
-        )
-        .bind(room_id)
-        .fetch_all(&self.db)
-        .await
-        .map_err(|e| format!("Failed to get exits: {}", e))?;
-        
-        Ok(exits)
+    pub async fn get_exits(&self, room_id: &str) -> Result<Vec<RoomExit>, String> {
+        database::exit_queries::get_exits(&self.db, room_id).await
+    }
+
+    /// Saves door changes on an exit and mirrors them onto the exit leading back, if there is one.
+    /// Hidden is per side, so it is not mirrored.
+    pub async fn update_door(&self, exit: &RoomExit) -> Result<(), String> {
+        database::exit_queries::update_door(&self.db, exit).await?;
+
+        if let Some(mut reverse) = database::exit_queries::get_reverse_exit(&self.db, exit).await? {
+            reverse.door_name = exit.door_name.clone();
+            reverse.is_closed = exit.is_closed;
+            reverse.is_locked = exit.is_locked;
+            reverse.key_id = exit.key_id.clone();
+            reverse.pick_difficulty = exit.pick_difficulty;
+            database::exit_queries::update_door(&self.db, &reverse).await?;
+        }
+
+        Ok(())
     }
     
     pub async fn move_player_to_room(&self, player_id: &str, new_room_id: &str) -> Result<(), String> {
@@ -160,4 +177,97 @@ impl GameState {
 
         Ok(obj)
     }
+
+    pub async fn get_npcs_in_room(&self, room_id: &str) -> Result<Vec<Npc>, String> {
+        database::npc_queries::get_npcs_in_room(&self.db, room_id).await
+    }
+
+    /// Places a new NPC in a room. `reset_id` is set when an area reset created it.
+    pub async fn spawn_npc(&self, prototype_id: i64, room_id: &str, reset_id: Option<i64>) -> Result<Npc, String> {
+        let prototype = database::npc_queries::get_prototype(&self.db, prototype_id).await?;
+        self.get_room(room_id).await?;
+
+        let id = Uuid::new_v4().to_string();
+        database::npc_queries::spawn_npc(&self.db, &id, &prototype, room_id, reset_id).await?;
+
+        database::npc_queries::get_npc(&self.db, &id).await
+    }
+
+    /// Sends a message to one player if they are online. Returns false if they are not.
+    pub async fn send_to_player(&self, player_id: &str, message: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        match sessions.get(player_id) {
+            Some(session) => session.tx.send(message.to_string()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Sends a message to every online player in a room, optionally skipping one of them.
+    pub async fn send_to_room(&self, room_id: &str, message: &str, exclude: Option<&str>) {
+        let players = match self.get_players_in_room(room_id).await {
+            Ok(players) => players,
+            Err(_) => return,
+        };
+
+        let sessions = self.sessions.read().await;
+        for p in players {
+            if exclude == Some(p.id.as_str()) {
+                continue;
+            }
+            if let Some(session) = sessions.get(&p.id) {
+                let _ = session.tx.send(message.to_string());
+            }
+        }
+    }
+
+    /// Id of the online player with this username, ignoring case.
+    pub async fn find_online_player(&self, username: &str) -> Option<String> {
+        let sessions = self.sessions.read().await;
+        sessions
+            .values()
+            .find(|s| s.username.eq_ignore_ascii_case(username))
+            .map(|s| s.player_id.clone())
+    }
+
+    pub async fn is_online(&self, player_id: &str) -> bool {
+        self.sessions.read().await.contains_key(player_id)
+    }
+}
+
+#[cfg(test)]
+impl GameState {
+    /// A fresh world on a private in-memory database, for tests.
+    pub async fn in_memory() -> Arc<Self> {
+        // every connection to sqlite::memory: is its own database, so keep exactly one open
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        database::initialize_database(&db).await.unwrap();
+
+        let (tx, _) = broadcast::channel(100);
+        Arc::new(Self {
+            db,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            broadcast_tx: tx,
+            config: ServerConfig::default(),
+            channel_history: RwLock::new(HashMap::new()),
+            login_failures: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Saves a player with this role, standing in the starting room.
+    pub async fn add_test_player(&self, username: &str, role: Role) -> Player {
+        let draft = CharacterDraft {
+            attributes: crate::models::Attributes::roll().await,
+            description: String::new(),
+            gender: crate::models::Gender::Neutral,
+        };
+        let player = Player::new(Uuid::new_v4().to_string(), username.to_string(), String::new(), role, &draft);
+        database::player_queries::create_player(&self.db, &player).await.unwrap();
+        player
+    }
 }
@@ -0,0 +1,161 @@
+// Private messages: tell, reply, ignore, afk and tells kept for offline players
+
+use crate::database::{player_queries, tell_queries};
+use crate::game::GameState;
+use crate::models::Player;
+use std::sync::Arc;
+
+/// Most tells kept for one offline player.
+const MAX_OFFLINE_TELLS: i64 = 20;
+
+/// Finds a player by name, online players first.
+async fn find_player(state: &Arc<GameState>, name: &str) -> Option<Player> {
+    if let Some(id) = state.find_online_player(name).await {
+        return player_queries::get_player_by_id(&state.db, &id).await.ok();
+    }
+    player_queries::find_player_by_username(&state.db, name).await.ok().flatten()
+}
+
+async fn send_tell(state: &Arc<GameState>, sender: &Player, recipient: &Player, message: &str) -> String {
+    if recipient.id == sender.id {
+        return "You mutter to yourself.\n".to_string();
+    }
+    if tell_queries::is_ignoring(&state.db, &recipient.id, &sender.id).await.unwrap() {
+        return format!("{} is ignoring you.\n", recipient.username);
+    }
+
+    if !state.is_online(&recipient.id).await {
+        let queued = tell_queries::count_offline_tells(&state.db, &recipient.id).await.unwrap();
+        if queued >= MAX_OFFLINE_TELLS {
+            return format!("{} has too many messages waiting already.\n", recipient.username);
+        }
+        tell_queries::queue_offline_tell(&state.db, &recipient.id, &sender.id, &sender.username, message)
+            .await
+            .unwrap();
+        return format!("{} is offline, and will get your message at their next login.\n", recipient.username);
+    }
+
+    state.send_to_player(&recipient.id, &format!("{} tells you: {}\n", sender.username, message)).await;
+
+    let afk = {
+        let mut sessions = state.sessions.write().await;
+        match sessions.get_mut(&recipient.id) {
+            Some(session) => {
+                session.reply_to = Some(sender.id.clone());
+                session.afk.clone()
+            }
+            None => None,
+        }
+    };
+
+    let mut response = format!("You tell {}: {}\n", recipient.username, message);
+    if let Some(away) = afk {
+        response.push_str(&format!("{} is AFK: {}\n", recipient.username, away));
+    }
+    response
+}
+
+/// Shows tells that arrived while the player was away; called at login.
+pub async fn deliver_offline_tells(state: &Arc<GameState>, player_id: &str) -> Result<String, String> {
+    let tells = tell_queries::take_offline_tells(&state.db, player_id).await?;
+    let last_sender = match tells.last() {
+        Some((sender_id, ..)) => sender_id.clone(),
+        None => return Ok(String::new()),
+    };
+
+    let mut response = format!("\nYou have {} message(s) from while you were away:\n", tells.len());
+    for (_, sender_name, message, sent_at) in tells {
+        let when = chrono::DateTime::from_timestamp(sent_at, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%b %d %H:%M").to_string())
+            .unwrap_or_default();
+        response.push_str(&format!("  [{}] {} told you: {}\n", when, sender_name, message));
+    }
+
+    let mut sessions = state.sessions.write().await;
+    if let Some(session) = sessions.get_mut(player_id) {
+        session.reply_to = Some(last_sender);
+    }
+    Ok(response)
+}
+
+// regular commands
+pub async fn handle_tell(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 3 {
+        return "Usage: tell <player> <message>\n".to_string();
+    }
+
+    let sender = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    match find_player(&state, parts[1]).await {
+        Some(recipient) => send_tell(&state, &sender, &recipient, &parts[2..].join(" ")).await,
+        None => "There is no player by that name.\n".to_string(),
+    }
+}
+
+/// Answers whoever sent the last tell.
+pub async fn handle_reply(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Reply what?\n".to_string();
+    }
+
+    let reply_to = state.sessions.read().await.get(player_id).and_then(|s| s.reply_to.clone());
+    let recipient = match reply_to {
+        Some(id) => match player_queries::get_player_by_id(&state.db, &id).await {
+            Ok(p) => p,
+            Err(_) => return "They are gone.\n".to_string(),
+        },
+        None => return "Nobody has sent you a tell yet.\n".to_string(),
+    };
+
+    let sender = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    send_tell(&state, &sender, &recipient, &parts[1..].join(" ")).await
+}
+
+/// `ignore` lists ignored players; `ignore <player>` starts or stops ignoring them.
+pub async fn handle_ignore(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        let names = tell_queries::get_ignored_names(&state.db, player_id).await.unwrap();
+        return match names.is_empty() {
+            true => "You aren't ignoring anyone.\n".to_string(),
+            false => format!("You are ignoring: {}\n", names.join(", ")),
+        };
+    }
+
+    let other = match find_player(&state, parts[1]).await {
+        Some(p) if p.id != player_id => p,
+        Some(_) => return "You can't ignore yourself.\n".to_string(),
+        None => return "There is no player by that name.\n".to_string(),
+    };
+
+    let ignoring = !tell_queries::is_ignoring(&state.db, player_id, &other.id).await.unwrap();
+    tell_queries::set_ignoring(&state.db, player_id, &other.id, ignoring).await.unwrap();
+    match ignoring {
+        true => format!("You are now ignoring {}.\n", other.username),
+        false => format!("You stop ignoring {}.\n", other.username),
+    }
+}
+
+/// Toggles away-from-keyboard, with an optional message sent back to anyone who tells.
+pub async fn handle_afk(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let mut sessions = state.sessions.write().await;
+    let session = match sessions.get_mut(player_id) {
+        Some(s) => s,
+        None => return String::new(),
+    };
+
+    if session.afk.is_some() && parts.len() < 2 {
+        session.afk = None;
+        return "You are back.\n".to_string();
+    }
+
+    let message = match parts.len() {
+        1 => "Away from keyboard.".to_string(),
+        _ => parts[1..].join(" "),
+    };
+    let response = format!("You are now AFK: {}\n", message);
+    session.afk = Some(message);
+    response
+}
@@ -0,0 +1,36 @@
+// Server tick: periodic world updates
+
+use crate::game::behavior::BehaviorRegistry;
+use crate::game::{rooms, scripting, travel, GameState};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Drives everything that happens without player input, once every `tick_millis`.
+/// Walking players take their steps on a faster pulse, every `travel_millis`.
+pub async fn run_game_loop(state: Arc<GameState>) {
+    let behaviors = BehaviorRegistry::with_defaults();
+    let mut interval = tokio::time::interval(Duration::from_millis(state.config.tick_millis));
+    let mut travel_interval = tokio::time::interval(Duration::from_millis(state.config.travel_millis));
+    let mut tick: u64 = 0;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = travel_interval.tick() => {
+                travel::run_travel_pulse(&state).await;
+                continue;
+            }
+        }
+        tick += 1;
+
+        if let Err(e) = behaviors.run_tick(&state, tick).await {
+            eprintln!("NPC behavior tick failed: {}", e);
+        }
+        if let Err(e) = scripting::run_tick_scripts(&state).await {
+            eprintln!("Script tick failed: {}", e);
+        }
+        if let Err(e) = rooms::regenerate_stamina(&state).await {
+            eprintln!("Stamina regeneration failed: {}", e);
+        }
+    }
+}
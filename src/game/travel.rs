@@ -0,0 +1,228 @@
+// Walking routes and speedwalks one step per travel pulse
+
+use crate::database::{object_queries, player_queries};
+use crate::game::pathfinding::{self, PathOptions};
+use crate::game::{commands, doors, moderation, GameState};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+async fn start_travel(state: &Arc<GameState>, player_id: &str, steps: Vec<String>) {
+    let mut sessions = state.sessions.write().await;
+    if let Some(session) = sessions.get_mut(player_id) {
+        session.travel = VecDeque::from(steps);
+    }
+}
+
+/// Drops any queued steps. Returns true if the player was on the move.
+pub async fn interrupt(state: &Arc<GameState>, player_id: &str) -> bool {
+    let mut sessions = state.sessions.write().await;
+    match sessions.get_mut(player_id) {
+        Some(session) if !session.travel.is_empty() => {
+            session.travel.clear();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Takes the next queued step for everyone who is travelling.
+pub async fn run_travel_pulse(state: &Arc<GameState>) {
+    // take the steps first so the sessions lock isn't held while moving (look reads sessions)
+    let steps: Vec<(String, String, bool)> = {
+        let mut sessions = state.sessions.write().await;
+        sessions
+            .iter_mut()
+            .filter_map(|(id, session)| {
+                let step = session.travel.pop_front()?;
+                Some((id.clone(), step, session.travel.is_empty()))
+            })
+            .collect()
+    };
+
+    for (player_id, step, last) in steps {
+        let mut output = take_step(state, &player_id, &step).await;
+        match &output {
+            Ok(_) if last => output = output.map(|o| o + "You have arrived.\n"),
+            Ok(_) => {}
+            Err(_) => {
+                interrupt(state, &player_id).await;
+            }
+        }
+        let message = match output {
+            Ok(o) => o,
+            Err(e) => e + "You stop.\n",
+        };
+        state.send_to_player(&player_id, &message).await;
+    }
+}
+
+/// Walks through one exit, opening an unlocked door on the way if needed.
+async fn take_step(state: &Arc<GameState>, player_id: &str, direction: &str) -> Result<String, String> {
+    // queued steps don't pass through process_command, so check for a freeze here
+    if let Some(refusal) = moderation::frozen_refusal(state, player_id, direction).await {
+        return Err(refusal);
+    }
+    let player = player_queries::get_player_by_id(&state.db, player_id).await?;
+    let exits = state.get_exits(&player.current_location).await?;
+    let exit = match exits.iter().find(|e| e.direction == direction) {
+        Some(exit) => exit,
+        None => return Err(format!("There is no exit {} from here.\n", direction)),
+    };
+
+    let mut output = String::new();
+    if !exit.is_passable() {
+        if exit.is_locked == 1 {
+            return Err(format!("The {} is locked.\n", exit.door()));
+        }
+        output.push_str(&doors::handle_door_command(state.clone(), player_id, &["open", direction]).await);
+    }
+
+    output.push_str(&commands::handle_move(state.clone(), player_id, direction).await);
+
+    let moved = player_queries::get_player_by_id(&state.db, player_id).await?;
+    if moved.current_location == player.current_location {
+        return Err(output);
+    }
+    Ok(output)
+}
+
+// regular commands
+pub async fn handle_walkto(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        let landmarks = object_queries::get_landmarks(&state.db).await.unwrap();
+        if landmarks.is_empty() {
+            return "Walk where? Usage: walkto <room or landmark>\n".to_string();
+        }
+        let mut response = "Walk where? Known landmarks:\n".to_string();
+        for (name, room) in landmarks {
+            response.push_str(&format!("  {:<16} {}\n", name, room.name));
+        }
+        return response;
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let destination = match pathfinding::resolve_destination(&state, &parts[1..].join(" ")).await {
+        Ok(room) => room,
+        Err(e) => return e,
+    };
+    if destination.id == player.current_location {
+        return "You are already there.\n".to_string();
+    }
+
+    let path = pathfinding::find_path(&state, &player.current_location, &destination.id, &PathOptions::player())
+        .await
+        .unwrap();
+    match path {
+        Some(path) if path.steps.len() > pathfinding::MAX_STEPS => {
+            "That's too far to walk in one go.\n".to_string()
+        }
+        Some(path) => {
+            let count = path.steps.len();
+            start_travel(&state, player_id, path.steps).await;
+            format!(
+                "You set off towards {} ({} step{}). Type 'stop' to stop.\n",
+                destination.name,
+                count,
+                if count == 1 { "" } else { "s" }
+            )
+        }
+        None => "You can't find a way there from here.\n".to_string(),
+    }
+}
+
+pub async fn handle_run(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let steps = match pathfinding::parse_speedwalk(&parts[1..].join(" ")) {
+        Ok(steps) => steps,
+        Err(e) => return e,
+    };
+
+    let count = steps.len();
+    start_travel(&state, player_id, steps).await;
+    format!("You start walking ({} step{}). Type 'stop' to stop.\n", count, if count == 1 { "" } else { "s" })
+}
+
+pub async fn handle_stop(state: Arc<GameState>, player_id: &str) -> String {
+    if interrupt(&state, player_id).await {
+        "You stop.\n".to_string()
+    } else {
+        "You aren't going anywhere.\n".to_string()
+    }
+}
+
+// admin commands
+/// Shows the route a player would take, with its cost and how much of the map was searched.
+pub async fn handle_admin_path(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if parts.len() < 2 {
+        return "Usage: @path [npc] <room_id|landmark|room name>\n".to_string();
+    }
+
+    let (options, target) = match parts[1].eq_ignore_ascii_case("npc") && parts.len() > 2 {
+        true => (PathOptions::npc(), parts[2..].join(" ")),
+        false => (PathOptions::player(), parts[1..].join(" ")),
+    };
+    let destination = match pathfinding::resolve_destination(&state, &target).await {
+        Ok(room) => room,
+        Err(e) => return e,
+    };
+
+    let path = pathfinding::find_path(&state, &player.current_location, &destination.id, &options)
+        .await
+        .unwrap();
+    match path {
+        Some(path) => format!(
+            "Path to {} ({}): {}\n  {} steps, cost {}, {} rooms searched\n",
+            destination.name,
+            destination.id,
+            if path.steps.is_empty() { "(here)".to_string() } else { pathfinding::to_speedwalk(&path.steps) },
+            path.steps.len(),
+            path.cost,
+            path.explored
+        ),
+        None => format!("No path to {} ({}).\n", destination.name, destination.id),
+    }
+}
+
+/// Names the current room as a `walkto` destination, or clears it with `-`.
+pub async fn handle_admin_landmark(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    if parts.len() != 2 {
+        return "Usage: @landmark <name>|-\n".to_string();
+    }
+
+    let room = state.get_room(&player.current_location).await.unwrap();
+    let mut properties: serde_json::Value = serde_json::from_str(&room.properties)
+        .unwrap_or_else(|_| serde_json::json!({}));
+    let name = parts[1].to_lowercase();
+
+    if name == "-" {
+        if let Some(map) = properties.as_object_mut() {
+            map.remove("landmark");
+        }
+    } else {
+        if let Some(other) = object_queries::get_landmark(&state.db, &name).await.unwrap() {
+            if other.id != room.id {
+                return format!("'{}' already marks {} ({}).\n", name, other.name, other.id);
+            }
+        }
+        properties["landmark"] = serde_json::Value::String(name.clone());
+    }
+
+    object_queries::update_object_properties(&state.db, &room.id, &properties.to_string())
+        .await
+        .unwrap();
+
+    if name == "-" {
+        "Landmark removed.\n".to_string()
+    } else {
+        format!("This room is now the landmark '{}'.\n", name)
+    }
+}
@@ -0,0 +1,119 @@
+// Rules for new usernames and passwords
+
+use crate::config::ServerConfig;
+use crate::database::{player_queries, social_queries};
+use crate::game::commands::COMMAND_WORDS;
+use crate::game::{directions, GameState};
+use crate::models::Channel;
+
+/// Passwords too common to allow, whatever their length.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "password1", "12345678", "123456789", "1234567890", "qwertyuiop", "qwerty123",
+    "iloveyou", "letmein1", "welcome1", "abcdefgh", "abc12345", "11111111", "00000000", "passw0rd",
+];
+
+/// Checks a name someone wants to register: letters only, within the length limits, not a
+/// command, social, reserved or offensive word, and not taken in any capitalization.
+pub async fn check_username(state: &GameState, name: &str) -> Result<(), String> {
+    check_name_rules(&state.config, name)?;
+
+    let lower = name.to_lowercase();
+    if social_queries::get_social(&state.db, &lower).await?.is_some() {
+        return Err(format!("'{}' is reserved. Please choose another name.", name));
+    }
+    if player_queries::find_player_by_username(&state.db, name).await?.is_some() {
+        return Err("That name is already taken.".to_string());
+    }
+    Ok(())
+}
+
+/// The parts of `check_username` that don't need the database.
+fn check_name_rules(config: &ServerConfig, name: &str) -> Result<(), String> {
+    let length = name.chars().count();
+    if length < config.username_min_length || length > config.username_max_length {
+        return Err(format!(
+            "Names must be {} to {} letters long.",
+            config.username_min_length, config.username_max_length
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("Names can only use the letters a to z, with no spaces, digits or symbols.".to_string());
+    }
+
+    let lower = name.to_lowercase();
+    let reserved = config.reserved_names.iter().any(|r| r.eq_ignore_ascii_case(&lower))
+        || COMMAND_WORDS.contains(&lower.as_str())
+        || directions::is_compass(&lower)
+        || Channel::find(&lower).is_some();
+    if reserved {
+        return Err(format!("'{}' is reserved. Please choose another name.", name));
+    }
+    if config.blocked_name_words.iter().any(|w| lower.contains(&w.to_lowercase())) {
+        return Err("That name isn't allowed. Please choose another.".to_string());
+    }
+    Ok(())
+}
+
+/// Checks a password someone is choosing: long enough, a mix of letters and digits or
+/// symbols, not common and not built from their name.
+pub fn check_password(config: &ServerConfig, username: &str, password: &str) -> Result<(), String> {
+    if password.chars().count() < config.password_min_length {
+        return Err(format!("Passwords must be at least {} characters.", config.password_min_length));
+    }
+    if password.chars().any(|c| c.is_control()) {
+        return Err("Passwords can't contain control characters.".to_string());
+    }
+
+    let has_letter = password.chars().any(|c| c.is_alphabetic());
+    let has_other = password.chars().any(|c| !c.is_alphabetic());
+    if !has_letter || !has_other {
+        return Err("Passwords need both letters and digits or symbols.".to_string());
+    }
+
+    let lower = password.to_lowercase();
+    if !username.is_empty() && lower.contains(&username.to_lowercase()) {
+        return Err("Passwords can't contain your name.".to_string());
+    }
+    if COMMON_PASSWORDS.contains(&lower.as_str()) {
+        return Err("That password is too common. Please choose another.".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_follow_the_rules() {
+        let config = ServerConfig::default();
+        assert!(check_name_rules(&config, "Bob").is_ok());
+        assert!(check_name_rules(&config, "ab").is_err());
+        assert!(check_name_rules(&config, &"a".repeat(config.username_max_length + 1)).is_err());
+        assert!(check_name_rules(&config, "bob2").is_err());
+        assert!(check_name_rules(&config, "Zoë").is_err());
+    }
+
+    #[test]
+    fn reserved_and_blocked_names() {
+        let config = ServerConfig::default();
+        for name in ["ADMIN", "look", "north", "gossip"] {
+            assert!(check_name_rules(&config, name).unwrap_err().contains("reserved"), "{}", name);
+        }
+        assert!(check_name_rules(&config, "Shitbag").unwrap_err().contains("isn't allowed"));
+    }
+
+    #[test]
+    fn passwords_follow_the_rules() {
+        let config = ServerConfig::default();
+        assert!(check_password(&config, "bob", "Tr4il-mix").is_ok());
+        assert!(check_password(&config, "bob", "short1").is_err());
+        assert!(check_password(&config, "bob", "abcdefghij").is_err());
+        assert!(check_password(&config, "bob", "1234567890").is_err());
+        assert!(check_password(&config, "bob", "Password1").is_err());
+        assert!(check_password(&config, "bob", "xBOB12345").is_err());
+        assert!(check_password(&config, "bob", "tab\there1").is_err());
+        // counted in characters, not bytes
+        assert!(check_password(&config, "bob", "ñandú1é").is_err());
+    }
+}
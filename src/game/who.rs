@@ -0,0 +1,175 @@
+// Who is online, finger, titles, plans and connection history
+
+use crate::database::{connection_queries, player_queries};
+use crate::game::{editor, GameState};
+use crate::models::{EditTarget, Player};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const MAX_TITLE_LENGTH: usize = 40;
+/// Connections `last` shows when not given a count.
+const DEFAULT_LAST_COUNT: i64 = 20;
+
+pub fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%b %d %Y %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Short durations like "45s", "12m", "3h" or "2d".
+fn format_duration(seconds: u64) -> String {
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+fn name_with_title(player: &Player) -> String {
+    match player.title.is_empty() {
+        true => player.username.clone(),
+        false => format!("{} {}", player.username, player.title),
+    }
+}
+
+// regular commands
+/// Lists who is online, with role markers, titles and idle times.
+pub async fn handle_who(state: Arc<GameState>) -> String {
+    // (player id, idle seconds, afk), copied out so the lock isn't held across queries
+    let mut online: Vec<(String, u64, bool)> = state
+        .sessions
+        .read()
+        .await
+        .values()
+        .map(|s| (s.player_id.clone(), s.last_input.elapsed().as_secs(), s.afk.is_some()))
+        .collect();
+    online.sort_by_key(|(_, idle, _)| *idle);
+
+    let mut response = "Players online:\n".to_string();
+    for (id, idle, afk) in &online {
+        let player = match player_queries::get_player_by_id(&state.db, id).await {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        let status = match (*afk, *idle) {
+            (true, _) => "AFK".to_string(),
+            (false, idle) if idle >= 60 => format!("idle {}", format_duration(idle)),
+            _ => String::new(),
+        };
+        let line = format!("  {:<8} {:<50} {}", player.role().marker(), name_with_title(&player), status);
+        response.push_str(line.trim_end());
+        response.push('\n');
+    }
+    response.push_str(&match online.len() {
+        1 => "1 player online.\n".to_string(),
+        n => format!("{} players online.\n", n),
+    });
+    response
+}
+
+/// Shows a player's title, when they were last on, when they were created and their plan.
+/// Works for offline players too.
+pub async fn handle_finger(state: Arc<GameState>, parts: &[&str]) -> String {
+    if parts.len() < 2 {
+        return "Usage: finger <player>\n".to_string();
+    }
+
+    let player = match player_queries::find_player_by_username(&state.db, parts[1]).await.unwrap() {
+        Some(p) => p,
+        None => return "There is no player by that name.\n".to_string(),
+    };
+
+    let session = state
+        .sessions
+        .read()
+        .await
+        .get(&player.id)
+        .map(|s| (s.connected_at.elapsed().as_secs(), s.last_input.elapsed().as_secs()));
+    let status = match session {
+        Some((on_for, idle)) => format!("Online for {}, idle {}", format_duration(on_for), format_duration(idle)),
+        None if player.last_login == 0 => "Offline, and has never logged in".to_string(),
+        None => format!("Offline, last logged in {}", format_date(player.last_login)),
+    };
+
+    let mut response = format!("{} {}", name_with_title(&player), player.role().marker()).trim_end().to_string() + "\n";
+    response.push_str(&format!("  {}\n", status));
+    response.push_str(&format!("  Created {}\n", format_date(player.created_at)));
+    response.push_str(&format!("  Level {}\n", player.level));
+    match player.plan.trim().is_empty() {
+        true => response.push_str("No plan.\n"),
+        false => response.push_str(&format!("Plan:\n{}\n", player.plan)),
+    }
+    response
+}
+
+/// Sets the title shown after the player's name in who and finger; with no text, clears it.
+pub async fn handle_title(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let title = parts[1..].join(" ");
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return format!("Please keep your title under {} characters.\n", MAX_TITLE_LENGTH);
+    }
+
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    player_queries::update_player_title(&state.db, player_id, &title)
+        .await
+        .unwrap();
+    match title.is_empty() {
+        true => "Title cleared.\n".to_string(),
+        false => format!("You are now '{} {}'.\n", player.username, title),
+    }
+}
+
+/// Opens the line editor on the player's plan, shown by finger.
+pub async fn handle_plan(state: Arc<GameState>, player_id: &str) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+    let target = EditTarget::Plan { player_id: player.id };
+    editor::start(&state, player_id, target, &player.plan).await
+}
+
+// admin commands
+/// `last [player] [count]` shows recent logins, newest first.
+pub async fn handle_admin_last(state: Arc<GameState>, parts: &[&str]) -> String {
+    let mut who = None;
+    let mut count = DEFAULT_LAST_COUNT;
+    for arg in &parts[1..] {
+        match arg.parse::<i64>() {
+            Ok(n) if n > 0 => count = n,
+            _ => match player_queries::find_player_by_username(&state.db, arg).await.unwrap() {
+                Some(p) => who = Some(p),
+                None => return "There is no player by that name.\n".to_string(),
+            },
+        }
+    }
+
+    let connections = connection_queries::get_connections(&state.db, who.as_ref().map(|p| p.id.as_str()), count)
+        .await
+        .unwrap();
+    if connections.is_empty() {
+        return "No connections recorded.\n".to_string();
+    }
+
+    // only a player's newest open connection can be the one they are on now
+    let mut seen = HashSet::new();
+    let mut response = format!("{:<16} {:<22} {:<18} {}\n", "Player", "Address", "Connected", "Duration");
+    for c in connections {
+        let newest = seen.insert(c.player_id.clone());
+        let duration = match c.disconnected_at {
+            Some(end) => format_duration((end - c.connected_at).max(0) as u64),
+            None if newest && state.is_online(&c.player_id).await => "still online".to_string(),
+            None => "unknown".to_string(),
+        };
+        response.push_str(&format!(
+            "{:<16} {:<22} {:<18} {}\n",
+            c.username,
+            c.address,
+            format_date(c.connected_at),
+            duration
+        ));
+    }
+    response
+}
@@ -1,18 +1,433 @@
-// World building utilities
+// World building utilities: procedural area generation
 
-// Future world-building utilities
-// Examples: procedural generation, area management, etc.
+use crate::database::{area_queries, player_queries};
+use crate::game::{directions, GameState};
+use crate::models::{Area, Role};
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
-// use crate::game::GameState;
+/// Largest width or height `@generate` will build, to keep a typo from making 10,000 rooms.
+pub const MAX_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    Grid, // every cell linked to its neighbours
+    Maze, // a spanning tree: exactly one path between any two rooms
+    Cave, // irregular open cells grown by cellular automaton
+}
+
+impl Layout {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "grid" => Some(Layout::Grid),
+            "maze" => Some(Layout::Maze),
+            "cave" | "caves" => Some(Layout::Cave),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Layout::Grid => "grid",
+            Layout::Maze => "maze",
+            Layout::Cave => "cave",
+        }
+    }
+}
+
+/// Words a generated area is described with.
+pub struct Theme {
+    pub name: &'static str,
+    pub adjectives: &'static [&'static str],
+    pub nouns: &'static [&'static str],
+    pub details: &'static [&'static str],
+}
+
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "dungeon",
+        adjectives: &["Damp", "Dark", "Narrow", "Crumbling", "Silent", "Cold"],
+        nouns: &["Corridor", "Cell", "Chamber", "Passage", "Guardroom", "Vault"],
+        details: &[
+            "Water drips somewhere out of sight.",
+            "Rusted chains hang from the walls.",
+            "The flagstones are worn smooth by countless feet.",
+            "A faint smell of smoke lingers in the air.",
+            "Scratches on the wall count days no one remembers.",
+            "Your footsteps echo back at you.",
+        ],
+    },
+    Theme {
+        name: "forest",
+        adjectives: &["Shaded", "Mossy", "Overgrown", "Quiet", "Sunlit", "Tangled"],
+        nouns: &["Glade", "Thicket", "Path", "Clearing", "Grove", "Hollow"],
+        details: &[
+            "Tall trees crowd close overhead.",
+            "Birdsong drifts down from the canopy.",
+            "Ferns brush against your legs.",
+            "A fallen log lies across the ground, soft with rot.",
+            "Sunlight dapples the leaf litter.",
+            "Something small rustles away through the undergrowth.",
+        ],
+    },
+    Theme {
+        name: "cave",
+        adjectives: &["Dripping", "Low", "Echoing", "Glittering", "Twisting", "Jagged"],
+        nouns: &["Cavern", "Tunnel", "Grotto", "Crawlway", "Gallery", "Pool"],
+        details: &[
+            "Stalactites hang from the ceiling like teeth.",
+            "Veins of quartz catch what little light there is.",
+            "The rock underfoot is slick with mineral deposits.",
+            "Cold air flows from a crack in the wall.",
+            "A still pool reflects the ceiling perfectly.",
+            "The walls narrow until you must turn sideways.",
+        ],
+    },
+    Theme {
+        name: "crypt",
+        adjectives: &["Dusty", "Forgotten", "Sealed", "Hallowed", "Bone-strewn", "Gloomy"],
+        nouns: &["Ossuary", "Tomb", "Niche", "Catacomb", "Sepulchre", "Stair"],
+        details: &[
+            "Skulls stare from niches cut into the walls.",
+            "Faded carvings show a procession of mourners.",
+            "The air is thick with old dust.",
+            "A stone sarcophagus lies with its lid askew.",
+            "Candle stubs have melted into the floor.",
+            "Names are chiselled into every surface.",
+        ],
+    },
+];
+
+pub fn find_theme(name: &str) -> Option<&'static Theme> {
+    THEMES.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+type Cell = (usize, usize);
+
+/// A generated layout before anything is written: room cells and the links between them.
+pub struct Plan {
+    pub cells: Vec<Cell>,
+    pub links: Vec<(Cell, &'static str, Cell)>, // from, direction, to
+}
+
+/// Room description produced from a theme.
+pub struct RoomText {
+    pub name: String,
+    pub description: String,
+}
+
+/// What `build` made: the room ids, and which room connects to the outside.
+pub struct GeneratedArea {
+    pub room_ids: Vec<String>,
+    pub entrance_id: String,
+    pub exits: usize,
+}
 
 pub struct WorldBuilder {
-    // Add world building state here
+    pub layout: Layout,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub theme: &'static Theme,
 }
 
 impl WorldBuilder {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(layout: Layout, width: usize, height: usize, seed: u64, theme: &'static Theme) -> Self {
+        Self {
+            layout,
+            width: width.clamp(1, MAX_SIZE),
+            height: height.clamp(1, MAX_SIZE),
+            seed,
+            theme,
+        }
+    }
+
+    /// Lays out the area. The same seed and size always give the same plan.
+    pub fn plan(&self) -> Plan {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        match self.layout {
+            Layout::Grid => self.plan_grid(),
+            Layout::Maze => self.plan_maze(&mut rng),
+            Layout::Cave => self.plan_cave(&mut rng),
+        }
+    }
+
+    fn neighbours(&self, (x, y): Cell) -> Vec<(&'static str, Cell)> {
+        let mut result = Vec::new();
+        if y > 0 {
+            result.push(("north", (x, y - 1)));
+        }
+        if x + 1 < self.width {
+            result.push(("east", (x + 1, y)));
+        }
+        if y + 1 < self.height {
+            result.push(("south", (x, y + 1)));
+        }
+        if x > 0 {
+            result.push(("west", (x - 1, y)));
+        }
+        result
     }
 
-    // Add methods for creating complex areas, dungeons, etc.
+    fn all_cells(&self) -> Vec<Cell> {
+        (0..self.height).flat_map(|y| (0..self.width).map(move |x| (x, y))).collect()
+    }
+
+    fn plan_grid(&self) -> Plan {
+        let cells = self.all_cells();
+        let links = cells
+            .iter()
+            .flat_map(|&c| {
+                self.neighbours(c)
+                    .into_iter()
+                    .filter(|(dir, _)| *dir == "east" || *dir == "south")
+                    .map(move |(dir, n)| (c, dir, n))
+            })
+            .collect();
+        Plan { cells, links }
+    }
+
+    /// Recursive backtracker: a random depth-first walk that never revisits a cell.
+    fn plan_maze(&self, rng: &mut StdRng) -> Plan {
+        let mut visited = HashSet::from([(0, 0)]);
+        let mut stack = vec![(0, 0)];
+        let mut links = Vec::new();
+
+        while let Some(&current) = stack.last() {
+            let mut options: Vec<_> = self
+                .neighbours(current)
+                .into_iter()
+                .filter(|(_, n)| !visited.contains(n))
+                .collect();
+            if options.is_empty() {
+                stack.pop();
+                continue;
+            }
+            options.shuffle(rng);
+            let (dir, next) = options[0];
+            links.push((current, dir, next));
+            visited.insert(next);
+            stack.push(next);
+        }
+
+        Plan { cells: self.all_cells(), links }
+    }
+
+    /// Random fill smoothed by a few automaton passes, keeping the largest connected region.
+    fn plan_cave(&self, rng: &mut StdRng) -> Plan {
+        let mut open: HashSet<Cell> = self.all_cells().into_iter().filter(|_| rng.random_bool(0.5)).collect();
+
+        for _ in 0..3 {
+            let mut next = HashSet::new();
+            for cell in self.all_cells() {
+                let (x, y) = (cell.0 as i64, cell.1 as i64);
+                let mut walls = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let (nx, ny) = (x + dx, y + dy);
+                        let outside = nx < 0 || ny < 0 || nx >= self.width as i64 || ny >= self.height as i64;
+                        if outside || !open.contains(&(nx as usize, ny as usize)) {
+                            walls += 1;
+                        }
+                    }
+                }
+                if walls < 5 {
+                    next.insert(cell);
+                }
+            }
+            open = next;
+        }
+
+        // flood fill each region and keep the biggest
+        let mut best: Vec<Cell> = Vec::new();
+        let mut seen = HashSet::new();
+        for start in self.all_cells() {
+            if !open.contains(&start) || seen.contains(&start) {
+                continue;
+            }
+            let mut region = Vec::new();
+            let mut queue = VecDeque::from([start]);
+            seen.insert(start);
+            while let Some(cell) = queue.pop_front() {
+                region.push(cell);
+                for (_, n) in self.neighbours(cell) {
+                    if open.contains(&n) && seen.insert(n) {
+                        queue.push_back(n);
+                    }
+                }
+            }
+            if region.len() > best.len() {
+                best = region;
+            }
+        }
+        if best.is_empty() {
+            best.push((0, 0));
+        }
+        best.sort_by_key(|&(x, y)| (y, x));
+
+        let region: HashSet<Cell> = best.iter().copied().collect();
+        let links = best
+            .iter()
+            .flat_map(|&c| {
+                self.neighbours(c)
+                    .into_iter()
+                    .filter(|(dir, n)| (*dir == "east" || *dir == "south") && region.contains(n))
+                    .map(move |(dir, n)| (c, dir, n))
+            })
+            .collect();
+
+        Plan { cells: best, links }
+    }
+
+    /// Names and describes each cell from the theme tables.
+    pub fn describe(&self, plan: &Plan) -> HashMap<Cell, RoomText> {
+        // a different stream from the layout, so re-theming keeps the same map
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(1));
+        let mut texts = HashMap::new();
+
+        for &cell in &plan.cells {
+            let adjective = self.theme.adjectives.choose(&mut rng).unwrap();
+            let noun = self.theme.nouns.choose(&mut rng).unwrap();
+            let details: Vec<&str> = self.theme.details.choose_multiple(&mut rng, 2).copied().collect();
+            texts.insert(
+                cell,
+                RoomText {
+                    name: format!("{} {}", adjective, noun),
+                    description: details.join(" "),
+                },
+            );
+        }
+
+        texts
+    }
+
+    /// Creates the rooms and exits of the plan in `area`. The entrance is the first cell of the
+    /// plan with no exit toward `entrance_side`, so the way back out doesn't replace a link.
+    pub async fn build(&self, state: &Arc<GameState>, area: &Area, entrance_side: &str) -> Result<GeneratedArea, String> {
+        let plan = self.plan();
+        let texts = self.describe(&plan);
+
+        let mut used: HashSet<(Cell, String)> = HashSet::new();
+        for (from, dir, to) in &plan.links {
+            used.insert((*from, dir.to_string()));
+            used.insert((*to, directions::opposite(&state.config, dir).unwrap_or_else(|| "back".to_string())));
+        }
+        let entrance = match plan.cells.iter().find(|c| !used.contains(&(**c, entrance_side.to_string()))) {
+            Some(cell) => *cell,
+            None => return Err(format!("No room in this layout has a free '{}' side for the way out.", entrance_side)),
+        };
+
+        let mut ids: HashMap<Cell, String> = HashMap::new();
+        let mut room_ids = Vec::new();
+        for cell in &plan.cells {
+            let text = &texts[cell];
+            let room = state.create_object(&text.name, &text.description, "room", None).await?;
+            area_queries::set_room_area(&state.db, &room.id, area).await?;
+            ids.insert(*cell, room.id.clone());
+            room_ids.push(room.id);
+        }
+
+        for (from, dir, to) in &plan.links {
+            let back = directions::opposite(&state.config, dir).unwrap_or_else(|| "back".to_string());
+            state.add_exit(&ids[from], dir, &ids[to]).await?;
+            state.add_exit(&ids[to], &back, &ids[from]).await?;
+        }
+
+        Ok(GeneratedArea {
+            entrance_id: ids[&entrance].clone(),
+            room_ids,
+            exits: plan.links.len() * 2,
+        })
+    }
+}
+
+// admin commands
+pub async fn handle_admin_generate(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let usage = format!(
+        "Usage: @generate <grid|maze|cave> <width> <height> [seed <n>] [theme <{}>] [exit <direction>] [area <name>]\n",
+        THEMES.iter().map(|t| t.name).collect::<Vec<_>>().join("|")
+    );
+    if parts.len() < 4 {
+        return usage;
+    }
+
+    let layout = match Layout::parse(parts[1]) {
+        Some(layout) => layout,
+        None => return usage,
+    };
+    let (width, height) = match (parts[2].parse::<usize>(), parts[3].parse::<usize>()) {
+        (Ok(w), Ok(h)) if (1..=MAX_SIZE).contains(&w) && (1..=MAX_SIZE).contains(&h) => (w, h),
+        _ => return format!("Width and height must be between 1 and {}.\n", MAX_SIZE),
+    };
+
+    let mut seed: u64 = rand::random();
+    let mut theme = &THEMES[0];
+    let mut exit = "down".to_string();
+    let mut area_name = None;
+    let mut i = 4;
+    while i + 1 < parts.len() {
+        match parts[i].to_lowercase().as_str() {
+            "seed" => match parts[i + 1].parse() {
+                Ok(n) => seed = n,
+                Err(_) => return "The seed must be a whole number.\n".to_string(),
+            },
+            "theme" => match find_theme(parts[i + 1]) {
+                Some(t) => theme = t,
+                None => return usage,
+            },
+            "exit" => exit = directions::normalize(parts[i + 1]),
+            "area" => area_name = Some(parts[i + 1]),
+            _ => return usage,
+        }
+        i += 2;
+    }
+
+    let exits = state.get_exits(&player.current_location).await.unwrap();
+    if exits.iter().any(|e| e.direction == exit) {
+        return format!("There is already an exit '{}' here; pick another with 'exit <direction>'.\n", exit);
+    }
+
+    let builder = WorldBuilder::new(layout, width, height, seed, theme);
+    let target_area = match area_name {
+        Some(name) => match area_queries::get_area_by_name(&state.db, name).await.unwrap() {
+            Some(area) => area,
+            None => return "There is no area by that name. Create it with @acreate first.\n".to_string(),
+        },
+        None => state.get_room_area(&player.current_location).await.unwrap(),
+    };
+    if player.role() < Role::Admin && !target_area.is_builder(&player.username) {
+        return format!("You aren't a builder of '{}'.\n", target_area.name);
+    }
+    let back = directions::opposite(&state.config, &exit).unwrap_or_else(|| "out".to_string());
+    let area = match builder.build(&state, &target_area, &back).await {
+        Ok(area) => area,
+        Err(e) => return format!("Error: {}\n", e),
+    };
+
+    state.add_exit(&player.current_location, &exit, &area.entrance_id).await.unwrap();
+    state.add_exit(&area.entrance_id, &back, &player.current_location).await.unwrap();
+
+    format!(
+        "Generated a {}x{} {} {} in area '{}': {} rooms and {} exits (seed {}). Exit '{}' leads in, '{}' leads back.\n",
+        width,
+        height,
+        theme.name,
+        layout.as_str(),
+        target_area.name,
+        area.room_ids.len(),
+        area.exits,
+        seed,
+        exit,
+        back
+    )
 }
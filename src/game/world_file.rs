@@ -0,0 +1,634 @@
+// World import/export to versioned JSON files
+
+use crate::database::{area_queries, exit_queries, npc_queries, object_queries, player_queries, reset_queries, script_queries};
+use crate::game::GameState;
+use crate::models::{Area, GameObject, NpcPrototype, RoomExit};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Sqlite, Transaction};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Bumped whenever the file layout changes in a way older servers can't read.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// An area as stored on disk. Ids in the file are only keys for cross-references;
+/// everything gets fresh ids when imported.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldFile {
+    pub format_version: u32,
+    pub area: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area_settings: Option<AreaSettings>,
+    #[serde(default)]
+    pub rooms: Vec<RoomEntry>,
+    #[serde(default)]
+    pub objects: Vec<ObjectEntry>,
+    #[serde(default)]
+    pub npc_prototypes: Vec<PrototypeEntry>,
+    #[serde(default)]
+    pub resets: Vec<ResetEntry>,
+}
+
+/// Applied when the import creates the area; an existing area keeps its own settings.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AreaSettings {
+    pub min_level: i64,
+    pub max_level: i64,
+    #[serde(default)]
+    pub reset_interval_secs: i64,
+    #[serde(default)]
+    pub flags: Vec<String>,
+    #[serde(default)]
+    pub builders: Vec<String>,
+    #[serde(default)]
+    pub vnum_low: i64,
+    #[serde(default)]
+    pub vnum_high: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomEntry {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default = "empty_map")]
+    pub properties: Value,
+    #[serde(default)]
+    pub exits: Vec<ExitEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scripts: BTreeMap<String, String>,
+}
+
+/// `to` is a room in this file, or the id of a room that already exists in the target world.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExitEntry {
+    pub direction: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub door: Option<DoorEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DoorEntry {
+    pub name: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub pick_difficulty: i64,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// `container` is a room or object in this file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectEntry {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub object_type: String,
+    pub container: String,
+    #[serde(default = "empty_map")]
+    pub properties: Value,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scripts: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrototypeEntry {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub level: i64,
+    pub attributes: BTreeMap<String, i64>,
+    pub max_health: i64,
+    pub physical_defense: i64,
+    pub physical_armor: i64,
+    #[serde(default = "empty_list")]
+    pub loot: Value,
+    #[serde(default = "empty_map")]
+    pub dialogue: Value,
+    #[serde(default = "empty_map")]
+    pub behaviors: Value,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scripts: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetEntry {
+    pub room: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    #[serde(rename = "type")]
+    pub reset_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npc_prototype: Option<i64>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub object_type: String,
+    #[serde(default = "empty_map")]
+    pub properties: Value,
+    pub max_count: i64,
+}
+
+#[derive(Default)]
+pub struct ImportSummary {
+    pub rooms: usize,
+    pub objects: usize,
+    pub prototypes: usize,
+    pub resets: usize,
+}
+
+fn empty_map() -> Value {
+    serde_json::json!({})
+}
+
+fn empty_list() -> Value {
+    serde_json::json!([])
+}
+
+fn parse_json(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| empty_map())
+}
+
+fn is_reset_copy(obj: &GameObject) -> bool {
+    parse_json(&obj.properties).get("reset_id").is_some()
+}
+
+async fn scripts_for(state: &Arc<GameState>, owner_type: &str, owner_id: &str) -> Result<BTreeMap<String, String>, String> {
+    let scripts = script_queries::get_scripts_for_owner(&state.db, owner_type, owner_id).await?;
+    Ok(scripts.into_iter().map(|s| (s.event, s.code)).collect())
+}
+
+/// Builds a world file from one area's rooms. Objects spawned by resets and live NPCs are left
+/// out, since the resets in the file recreate them. Exits to other areas are kept and must
+/// exist wherever the file is imported.
+pub async fn export_world(state: &Arc<GameState>, area: &Area) -> Result<WorldFile, String> {
+    let all_objects = object_queries::get_all_objects(&state.db).await?;
+    let mut file = WorldFile {
+        format_version: FORMAT_VERSION,
+        area: area.name.clone(),
+        area_settings: Some(AreaSettings {
+            min_level: area.min_level,
+            max_level: area.max_level,
+            reset_interval_secs: area.reset_interval_secs,
+            flags: area.flags().iter().map(|f| f.to_string()).collect(),
+            builders: area.builder_list(),
+            vnum_low: area.vnum_low,
+            vnum_high: area.vnum_high,
+        }),
+        rooms: Vec::new(),
+        objects: Vec::new(),
+        npc_prototypes: Vec::new(),
+        resets: Vec::new(),
+    };
+
+    let mut exported: HashSet<String> = HashSet::new();
+    for room in area_queries::get_rooms_in_area(&state.db, area.id).await? {
+        let exits = state.get_exits(&room.id).await?;
+        file.rooms.push(RoomEntry {
+            id: room.id.clone(),
+            name: room.name.clone(),
+            description: room.description.clone(),
+            properties: parse_json(&room.properties),
+            exits: exits.iter().map(exit_entry).collect(),
+            scripts: scripts_for(state, "object", &room.id).await?,
+        });
+        exported.insert(room.id.clone());
+    }
+
+    // only objects that sit (directly or nested) in an exported room, never in a player
+    let mut remaining: Vec<&GameObject> = all_objects
+        .iter()
+        .filter(|o| o.object_type != "room" && !is_reset_copy(o))
+        .collect();
+    loop {
+        let (ready, waiting): (Vec<&GameObject>, Vec<&GameObject>) = remaining
+            .into_iter()
+            .partition(|o| o.container_id.as_ref().is_some_and(|c| exported.contains(c)));
+        if ready.is_empty() {
+            break;
+        }
+        for obj in ready {
+            file.objects.push(ObjectEntry {
+                id: obj.id.clone(),
+                name: obj.name.clone(),
+                description: obj.description.clone(),
+                object_type: obj.object_type.clone(),
+                container: obj.container_id.clone().unwrap_or_default(),
+                properties: parse_json(&obj.properties),
+                scripts: scripts_for(state, "object", &obj.id).await?,
+            });
+            exported.insert(obj.id.clone());
+        }
+        remaining = waiting;
+    }
+
+    for r in reset_queries::get_resets(&state.db, None).await? {
+        // a reset filling something that isn't part of the file can't be carried over
+        if !exported.contains(r.target_id()) {
+            continue;
+        }
+        file.resets.push(ResetEntry {
+            room: r.room_id.clone(),
+            container: r.container_id.clone(),
+            reset_type: r.reset_type.clone(),
+            npc_prototype: r.npc_prototype_id,
+            name: r.name.clone(),
+            description: r.description.clone(),
+            object_type: r.object_type.clone(),
+            properties: parse_json(&r.properties),
+            max_count: r.max_count,
+        });
+    }
+
+    // prototypes in the area's id range, plus any its resets spawn
+    let used: HashSet<i64> = file.resets.iter().filter_map(|r| r.npc_prototype).collect();
+    for p in npc_queries::list_prototypes(&state.db).await? {
+        if !area.owns_vnum(p.id) && !used.contains(&p.id) {
+            continue;
+        }
+        let attributes = BTreeMap::from([
+            ("dexterity".to_string(), p.dexterity),
+            ("strength".to_string(), p.strength),
+            ("vitality".to_string(), p.vitality),
+            ("perception".to_string(), p.perception),
+            ("willpower".to_string(), p.willpower),
+            ("charisma".to_string(), p.charisma),
+        ]);
+        file.npc_prototypes.push(PrototypeEntry {
+            id: p.id,
+            name: p.name.clone(),
+            description: p.description.clone(),
+            level: p.level,
+            attributes,
+            max_health: p.max_health,
+            physical_defense: p.physical_defense,
+            physical_armor: p.physical_armor,
+            loot: serde_json::from_str(&p.loot_table).unwrap_or_else(|_| empty_list()),
+            dialogue: parse_json(&p.dialogue),
+            behaviors: parse_json(&p.behaviors),
+            scripts: scripts_for(state, "npc", &p.id.to_string()).await?,
+        });
+    }
+
+    Ok(file)
+}
+
+fn exit_entry(exit: &RoomExit) -> ExitEntry {
+    ExitEntry {
+        direction: exit.direction.clone(),
+        to: exit.destination_id.clone(),
+        door: exit.door_name.as_ref().map(|name| DoorEntry {
+            name: name.clone(),
+            closed: exit.is_closed == 1,
+            locked: exit.is_locked == 1,
+            key: exit.key_id.clone(),
+            pick_difficulty: exit.pick_difficulty,
+            hidden: exit.is_hidden == 1,
+        }),
+    }
+}
+
+/// Checks every cross-reference in a file before anything is written.
+pub async fn validate(state: &Arc<GameState>, file: &WorldFile) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if file.format_version == 0 || file.format_version > FORMAT_VERSION {
+        errors.push(format!(
+            "Unsupported format_version {} (this server reads up to {}).",
+            file.format_version, FORMAT_VERSION
+        ));
+        return Err(errors);
+    }
+
+    let mut rooms = HashSet::new();
+    let mut keys = HashSet::new();
+    for room in &file.rooms {
+        if !keys.insert(room.id.as_str()) {
+            errors.push(format!("Duplicate id '{}'.", room.id));
+        }
+        rooms.insert(room.id.as_str());
+    }
+    for obj in &file.objects {
+        if !keys.insert(obj.id.as_str()) {
+            errors.push(format!("Duplicate id '{}'.", obj.id));
+        }
+        if obj.object_type == "room" {
+            errors.push(format!("Object '{}' is a room; rooms belong in the rooms list.", obj.id));
+        }
+    }
+    for obj in &file.objects {
+        if !keys.contains(obj.container.as_str()) {
+            errors.push(format!("Object '{}' is inside unknown container '{}'.", obj.id, obj.container));
+        }
+    }
+    // following containers outward has to reach a room; objects inside each other never would
+    let containers: HashMap<&str, &str> = file.objects.iter().map(|o| (o.id.as_str(), o.container.as_str())).collect();
+    for obj in &file.objects {
+        let mut current = obj.container.as_str();
+        let mut steps = 0;
+        while let Some(&next) = containers.get(current) {
+            if current == obj.id || steps > containers.len() {
+                errors.push(format!("Object '{}' is inside itself, through its containers.", obj.id));
+                break;
+            }
+            current = next;
+            steps += 1;
+        }
+    }
+
+    for room in &file.rooms {
+        for exit in &room.exits {
+            if !rooms.contains(exit.to.as_str()) && state.get_room(&exit.to).await.is_err() {
+                errors.push(format!("Exit '{}' in room '{}' leads to unknown room '{}'.", exit.direction, room.id, exit.to));
+            }
+        }
+    }
+
+    let prototypes: HashSet<i64> = file.npc_prototypes.iter().map(|p| p.id).collect();
+    if prototypes.len() != file.npc_prototypes.len() {
+        errors.push("Duplicate NPC prototype ids.".to_string());
+    }
+    for p in &file.npc_prototypes {
+        if serde_json::from_value::<Vec<crate::models::LootEntry>>(p.loot.clone()).is_err() {
+            errors.push(format!("NPC prototype {} has an invalid loot list.", p.id));
+        }
+        if !p.dialogue.is_object() || !p.behaviors.is_object() {
+            errors.push(format!("NPC prototype {} needs dialogue and behaviors to be maps.", p.id));
+        }
+    }
+
+    for (i, r) in file.resets.iter().enumerate() {
+        if !rooms.contains(r.room.as_str()) {
+            errors.push(format!("Reset {} is in unknown room '{}'.", i + 1, r.room));
+        }
+        if let Some(container) = &r.container {
+            if !keys.contains(container.as_str()) {
+                errors.push(format!("Reset {} fills unknown container '{}'.", i + 1, container));
+            }
+        }
+        match r.reset_type.as_str() {
+            "object" => {}
+            "npc" => match r.npc_prototype {
+                Some(id) if prototypes.contains(&id) => {}
+                _ => errors.push(format!("Reset {} names an NPC prototype that isn't in the file.", i + 1)),
+            },
+            other => errors.push(format!("Reset {} has unknown type '{}'.", i + 1, other)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates and loads a world file, giving every room, object and prototype a new id.
+pub async fn import_world(state: &Arc<GameState>, file: &WorldFile) -> Result<ImportSummary, String> {
+    if let Err(errors) = validate(state, file).await {
+        return Err(errors.join("\n"));
+    }
+
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    for key in file.rooms.iter().map(|r| &r.id).chain(file.objects.iter().map(|o| &o.id)) {
+        ids.insert(key.as_str(), Uuid::new_v4().to_string());
+    }
+    // references to rooms outside the file are kept as they are
+    let remap = |key: &str| ids.get(key).cloned().unwrap_or_else(|| key.to_string());
+
+    // all or nothing: an error anywhere rolls the whole import back when `tx` is dropped
+    let mut tx = state.db.begin().await.map_err(|e| format!("Failed to start import: {}", e))?;
+    let area = match area_queries::get_area_by_name(&mut tx, &file.area).await? {
+        Some(area) => area,
+        None => create_area(&mut tx, file).await?,
+    };
+
+    let mut summary = ImportSummary::default();
+
+    for room in &file.rooms {
+        let mut obj = GameObject::new(remap(&room.id), room.name.clone(), room.description.clone(), "room".to_string(), None);
+        obj.properties = room.properties.to_string();
+        obj.area_id = Some(area.id);
+        object_queries::create_object(&mut tx, &obj).await?;
+        for (event, code) in &room.scripts {
+            script_queries::set_script(&mut tx, "object", &obj.id, event, code).await?;
+        }
+        summary.rooms += 1;
+    }
+
+    for entry in &file.objects {
+        let mut obj = GameObject::new(
+            remap(&entry.id),
+            entry.name.clone(),
+            entry.description.clone(),
+            entry.object_type.clone(),
+            Some(remap(&entry.container)),
+        );
+        obj.properties = entry.properties.to_string();
+        object_queries::create_object(&mut tx, &obj).await?;
+        for (event, code) in &entry.scripts {
+            script_queries::set_script(&mut tx, "object", &obj.id, event, code).await?;
+        }
+        summary.objects += 1;
+    }
+
+    for room in &file.rooms {
+        let room_id = remap(&room.id);
+        for exit in &room.exits {
+            exit_queries::create_exit(&mut tx, &room_id, &exit.direction, &remap(&exit.to)).await?;
+            if let Some(door) = &exit.door {
+                let row = RoomExit {
+                    room_id: room_id.clone(),
+                    direction: exit.direction.to_lowercase(),
+                    destination_id: remap(&exit.to),
+                    door_name: Some(door.name.clone()),
+                    is_closed: door.closed as i64,
+                    is_locked: door.locked as i64,
+                    key_id: door.key.as_deref().map(remap),
+                    pick_difficulty: door.pick_difficulty,
+                    is_hidden: door.hidden as i64,
+                };
+                // written directly: update_door would also rewrite the other side
+                exit_queries::update_door(&mut tx, &row).await?;
+            }
+        }
+    }
+
+    let mut prototype_ids: HashMap<i64, i64> = HashMap::new();
+    for entry in &file.npc_prototypes {
+        let attr = |name: &str| entry.attributes.get(name).copied().unwrap_or(100);
+        let prototype = NpcPrototype {
+            id: 0,
+            name: entry.name.clone(),
+            description: entry.description.clone(),
+            level: entry.level,
+            dexterity: attr("dexterity"),
+            strength: attr("strength"),
+            vitality: attr("vitality"),
+            perception: attr("perception"),
+            willpower: attr("willpower"),
+            charisma: attr("charisma"),
+            max_health: entry.max_health,
+            physical_defense: entry.physical_defense,
+            physical_armor: entry.physical_armor,
+            loot_table: entry.loot.to_string(),
+            dialogue: entry.dialogue.to_string(),
+            behaviors: entry.behaviors.to_string(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        let new_id = npc_queries::insert_prototype(&mut tx, &prototype).await?;
+        for (event, code) in &entry.scripts {
+            script_queries::set_script(&mut tx, "npc", &new_id.to_string(), event, code).await?;
+        }
+        prototype_ids.insert(entry.id, new_id);
+        summary.prototypes += 1;
+    }
+
+    for r in &file.resets {
+        let container = r.container.as_deref().map(remap);
+        reset_queries::create_reset(
+            &mut tx,
+            &area.name,
+            &remap(&r.room),
+            container.as_deref(),
+            &r.reset_type,
+            r.npc_prototype.and_then(|id| prototype_ids.get(&id).copied()),
+            &r.name,
+            &r.description,
+            &r.object_type,
+            &r.properties.to_string(),
+            r.max_count,
+        )
+        .await?;
+        summary.resets += 1;
+    }
+
+    tx.commit().await.map_err(|e| format!("Failed to finish import: {}", e))?;
+    Ok(summary)
+}
+
+/// Creates the area a file is imported into, with the file's settings.
+async fn create_area(tx: &mut Transaction<'_, Sqlite>, file: &WorldFile) -> Result<Area, String> {
+    let id = area_queries::create_area(&mut *tx, &file.area).await?;
+    if let Some(settings) = &file.area_settings {
+        let has = |flag: &str| settings.flags.iter().any(|f| f == flag) as i64;
+        area_queries::update_area_field(&mut *tx, id, "min_level", settings.min_level).await?;
+        area_queries::update_area_field(&mut *tx, id, "max_level", settings.max_level).await?;
+        area_queries::update_area_field(&mut *tx, id, "reset_interval_secs", settings.reset_interval_secs).await?;
+        area_queries::update_area_field(&mut *tx, id, "is_safe", has("safe")).await?;
+        area_queries::update_area_field(&mut *tx, id, "no_recall", has("no-recall")).await?;
+        area_queries::update_area_field(&mut *tx, id, "pk_allowed", has("pk")).await?;
+        area_queries::update_area_field(&mut *tx, id, "vnum_low", settings.vnum_low).await?;
+        area_queries::update_area_field(&mut *tx, id, "vnum_high", settings.vnum_high).await?;
+        let builders = serde_json::to_string(&settings.builders).unwrap_or_else(|_| "[]".to_string());
+        area_queries::update_area_field(&mut *tx, id, "builders", builders).await?;
+    }
+    area_queries::get_area(&mut *tx, id).await
+}
+
+pub async fn export_to_path(state: &Arc<GameState>, area: &Area, path: &Path) -> Result<WorldFile, String> {
+    let file = export_world(state, area).await?;
+    let text = serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize world: {}", e))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, text + "\n").map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(file)
+}
+
+pub async fn import_from_path(state: &Arc<GameState>, path: &Path) -> Result<ImportSummary, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file: WorldFile = serde_json::from_str(&text).map_err(|e| format!("{} is not a valid world file: {}", path.display(), e))?;
+    import_world(state, &file).await
+}
+
+/// In-game names map to `<world_dir>/<name>.json`; anything but plain names is refused.
+fn world_path(state: &Arc<GameState>, name: &str) -> Option<PathBuf> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| Path::new(&state.config.world_dir).join(format!("{}.json", name)))
+}
+
+fn describe_export(file: &WorldFile, path: &Path) -> String {
+    format!(
+        "Exported {} rooms, {} objects, {} NPC prototypes and {} resets to {}.\n",
+        file.rooms.len(),
+        file.objects.len(),
+        file.npc_prototypes.len(),
+        file.resets.len(),
+        path.display()
+    )
+}
+
+fn describe_import(summary: &ImportSummary) -> String {
+    format!(
+        "Imported {} rooms, {} objects, {} NPC prototypes and {} resets.\n",
+        summary.rooms, summary.objects, summary.prototypes, summary.resets
+    )
+}
+
+// admin commands
+pub async fn handle_admin_export(state: Arc<GameState>, player_id: &str, parts: &[&str]) -> String {
+    let player = player_queries::get_player_by_id(&state.db, player_id)
+        .await
+        .unwrap();
+
+    let area = match parts.get(1) {
+        Some(name) => match area_queries::get_area_by_name(&state.db, name).await.unwrap() {
+            Some(area) => area,
+            None => return "There is no area by that name.\n".to_string(),
+        },
+        None => state.get_room_area(&player.current_location).await.unwrap(),
+    };
+    let path = match world_path(&state, &area.name) {
+        Some(path) => path,
+        None => return "That area's name can't be used as a file name (letters, digits, - and _ only).\n".to_string(),
+    };
+
+    match export_to_path(&state, &area, &path).await {
+        Ok(file) => describe_export(&file, &path),
+        Err(e) => format!("Error: {}\n", e),
+    }
+}
+
+pub async fn handle_admin_import(state: Arc<GameState>, parts: &[&str]) -> String {
+    let path = match parts.get(1).and_then(|name| world_path(&state, name)) {
+        Some(path) => path,
+        None => return "Usage: @import <file_name>\n".to_string(),
+    };
+
+    match import_from_path(&state, &path).await {
+        Ok(summary) => describe_import(&summary),
+        Err(e) => format!("Import failed:\n{}\n", e),
+    }
+}
+
+/// `mud_server export <file> [area]` / `mud_server import <file>`, run before the server starts.
+/// Export defaults to the area named like the file.
+pub async fn run_cli(state: &Arc<GameState>, command: &str, path: &str, area: Option<&str>) -> Result<String, String> {
+    let path = Path::new(path);
+    match command {
+        "export" => {
+            let name = area
+                .or_else(|| path.file_stem().and_then(|s| s.to_str()))
+                .unwrap_or("default");
+            let area = area_queries::get_area_by_name(&state.db, name)
+                .await?
+                .ok_or_else(|| format!("There is no area called '{}'.", name))?;
+            let file = export_to_path(state, &area, path).await?;
+            Ok(describe_export(&file, path))
+        }
+        "import" => import_from_path(state, path).await.map(|s| describe_import(&s)),
+        _ => Err(format!("Unknown command '{}'.", command)),
+    }
+}
@@ -8,6 +8,7 @@ use config::ServerConfig;
 use game::state::GameState;
 use network::telnet::handle_telnet_client;
 use network::websocket::ws_handler;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use axum::Router;
@@ -17,6 +18,19 @@ use axum::routing::get;
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = ServerConfig::default();
     let state = Arc::new(GameState::new(config.clone()).await?);
+
+    // `mud_server export <file> [area]` and `mud_server import <file>` work on the database and exit
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, command, path, rest @ ..] = args.as_slice() {
+        match game::world_file::run_cli(&state, command, path, rest.first().map(String::as_str)).await {
+            Ok(message) => print!("{}", message),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
     let app = Router::new().route("/ws", get(ws_handler)).with_state(state.clone());
 
 
@@ -29,17 +43,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     tokio::spawn(async move {
         let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
-        axum::serve(listener, app).await.unwrap();
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
     });
     println!("Starting Websocket handler...");    
 
+    tokio::spawn(game::resets::reset_scheduler(state.clone()));
+    tokio::spawn(game::tick::run_game_loop(state.clone()));
+
     loop {
         let (stream, addr) = listener.accept().await?;
+        if let Some(ban) = game::moderation::site_ban(&state, &addr.ip()).await {
+            println!("Refused connection from {} (banned as {})", addr, ban.target);
+            let _ = stream.try_write(b"Connections from your address are banned.\n");
+            continue;
+        }
         println!("New connection from: {}", addr);
         
         let state_clone = state.clone();
         tokio::spawn(async move {
-            if let Err(e) = handle_telnet_client(stream, state_clone).await {
+            if let Err(e) = handle_telnet_client(stream, addr, state_clone).await {
                 eprintln!("Error handling client: {}", e);
             }
         });
@@ -0,0 +1,53 @@
+// Area (zone) struct
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A named group of rooms sharing resets, flags and builders.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Area {
+    pub id: i64,
+    pub name: String,
+    pub builders: String, // JSON list of usernames allowed to edit the area
+    pub min_level: i64,
+    pub max_level: i64,
+    pub reset_interval_secs: i64, // 0 uses the server default
+
+    // flags, use 0 or 1
+    pub is_safe: i64, // no fighting anywhere in the area
+    pub no_recall: i64, // recall doesn't work from here
+    pub pk_allowed: i64, // players may attack each other
+
+    // NPC prototype ids reserved for this area
+    pub vnum_low: i64,
+    pub vnum_high: i64,
+    pub created_at: i64,
+}
+
+impl Area {
+    pub fn builder_list(&self) -> Vec<String> {
+        serde_json::from_str(&self.builders).unwrap_or_default()
+    }
+
+    pub fn is_builder(&self, username: &str) -> bool {
+        self.builder_list().iter().any(|b| b.eq_ignore_ascii_case(username))
+    }
+
+    pub fn flags(&self) -> Vec<&'static str> {
+        let mut flags = Vec::new();
+        if self.is_safe == 1 {
+            flags.push("safe");
+        }
+        if self.no_recall == 1 {
+            flags.push("no-recall");
+        }
+        if self.pk_allowed == 1 {
+            flags.push("pk");
+        }
+        flags
+    }
+
+    pub fn owns_vnum(&self, vnum: i64) -> bool {
+        self.vnum_high > 0 && (self.vnum_low..=self.vnum_high).contains(&vnum)
+    }
+}
@@ -0,0 +1,15 @@
+// Audit log entries
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A privileged command someone ran.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub player_id: String,
+    pub username: String,
+    pub room_id: String, // where they were standing
+    pub command: String, // the full line they typed
+    pub created_at: i64,
+}
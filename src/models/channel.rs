@@ -0,0 +1,58 @@
+// Chat channels
+
+use crate::models::Role;
+
+/// A server-wide chat channel.
+pub struct Channel {
+    pub name: &'static str,
+    pub title: &'static str, // shown in front of messages
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    pub min_role: Role, // lowest role that can hear, speak or join
+    pub default_on: bool, // players are on it until they leave
+}
+
+pub const CHANNELS: &[Channel] = &[
+    Channel {
+        name: "gossip",
+        title: "Gossip",
+        aliases: &["gos"],
+        description: "General chatter in character",
+        min_role: Role::Player,
+        default_on: true,
+    },
+    Channel {
+        name: "ooc",
+        title: "OOC",
+        aliases: &[],
+        description: "Out of character talk",
+        min_role: Role::Player,
+        default_on: true,
+    },
+    Channel {
+        name: "newbie",
+        title: "Newbie",
+        aliases: &["nb"],
+        description: "Questions and help for new players",
+        min_role: Role::Player,
+        default_on: true,
+    },
+    Channel {
+        name: "immortal",
+        title: "Immortal",
+        aliases: &["imm"],
+        description: "Staff only",
+        min_role: Role::Helper,
+        default_on: true,
+    },
+];
+
+/// Number of recent messages each channel keeps for replay.
+pub const CHANNEL_HISTORY: usize = 20;
+
+impl Channel {
+    pub fn find(name: &str) -> Option<&'static Channel> {
+        let name = name.to_lowercase();
+        CHANNELS.iter().find(|c| c.name == name || c.aliases.contains(&name.as_str()))
+    }
+}
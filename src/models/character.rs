@@ -0,0 +1,135 @@
+// Character creation: attribute rolls, point-buy and pronouns
+
+use crate::models::dice_rolls;
+
+pub const ATTRIBUTE_NAMES: [&str; 6] = [
+    "dexterity",
+    "strength",
+    "vitality",
+    "perception",
+    "willpower",
+    "charisma",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Attributes {
+    pub dexterity: i64,
+    pub strength: i64,
+    pub vitality: i64,
+    pub perception: i64,
+    pub willpower: i64,
+    pub charisma: i64,
+}
+
+impl Attributes {
+    /// Rolls all six attributes from N(100, 10), the same way registration always has.
+    pub async fn roll() -> Self {
+        Self {
+            dexterity: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+            strength: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+            vitality: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+            perception: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+            willpower: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+            charisma: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+        }
+    }
+
+    /// Builds attributes from point-buy values given in `ATTRIBUTE_NAMES` order.
+    /// Every point of the pool must be spent and each value must be within min..=max.
+    pub fn from_point_buy(values: [i64; 6], pool: i64, min: i64, max: i64) -> Result<Self, String> {
+        for (name, value) in ATTRIBUTE_NAMES.iter().zip(values.iter()) {
+            if *value < min || *value > max {
+                return Err(format!("{} must be between {} and {}.", name, min, max));
+            }
+        }
+
+        let spent: i64 = values.iter().sum();
+        if spent != pool {
+            return Err(format!("You spent {} points, but you must spend exactly {}.", spent, pool));
+        }
+
+        Ok(Self {
+            dexterity: values[0],
+            strength: values[1],
+            vitality: values[2],
+            perception: values[3],
+            willpower: values[4],
+            charisma: values[5],
+        })
+    }
+
+    pub fn values(&self) -> [i64; 6] {
+        [
+            self.dexterity,
+            self.strength,
+            self.vitality,
+            self.perception,
+            self.willpower,
+            self.charisma,
+        ]
+    }
+
+    pub fn total(&self) -> i64 {
+        self.values().iter().sum()
+    }
+
+    pub fn display(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in ATTRIBUTE_NAMES.iter().zip(self.values().iter()) {
+            out.push_str(&format!("  {:<11} {}\n", name, value));
+        }
+        out.push_str(&format!("  {:<11} {}\n", "total", self.total()));
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Male,
+    Female,
+    Neutral,
+    Object,
+}
+
+pub struct Pronouns {
+    pub subject: &'static str, // he, she, they, it
+    pub object: &'static str, // him, her, them, it
+    pub possessive: &'static str, // his, her, their, its
+}
+
+impl Gender {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "m" | "male" | "he" => Some(Gender::Male),
+            "f" | "female" | "she" => Some(Gender::Female),
+            "n" | "neutral" | "they" => Some(Gender::Neutral),
+            "o" | "object" | "it" => Some(Gender::Object),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Gender::Male => "male",
+            Gender::Female => "female",
+            Gender::Neutral => "neutral",
+            Gender::Object => "object",
+        }
+    }
+
+    pub fn pronouns(&self) -> Pronouns {
+        match self {
+            Gender::Male => Pronouns { subject: "he", object: "him", possessive: "his" },
+            Gender::Female => Pronouns { subject: "she", object: "her", possessive: "her" },
+            Gender::Neutral => Pronouns { subject: "they", object: "them", possessive: "their" },
+            Gender::Object => Pronouns { subject: "it", object: "it", possessive: "its" },
+        }
+    }
+}
+
+/// Everything gathered during character creation; nothing is saved until the draft is committed.
+pub struct CharacterDraft {
+    pub attributes: Attributes,
+    pub description: String,
+    pub gender: Gender,
+}
@@ -0,0 +1,15 @@
+// Connection history
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One login, from connecting to disconnecting.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Connection {
+    pub id: i64,
+    pub player_id: String,
+    pub username: String,
+    pub address: String,
+    pub connected_at: i64,
+    pub disconnected_at: Option<i64>, // None while still connected, or if the server stopped
+}
@@ -0,0 +1,104 @@
+// Line editor buffer for multi-line text
+
+/// Width that `.f` reflows text to.
+pub const REFLOW_WIDTH: usize = 78;
+/// Longest text the editor will hold.
+pub const MAX_LINES: usize = 100;
+
+/// What a finished buffer is saved into.
+#[derive(Debug, Clone)]
+pub enum EditTarget {
+    Description { object_id: String },
+    BoardPost { board_id: String, subject: String },
+    Mail { recipient_id: String, subject: String },
+    Plan { player_id: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct LineEditor {
+    pub target: EditTarget,
+    pub lines: Vec<String>,
+}
+
+impl LineEditor {
+    /// Starts an editor, pre-filled with the text being edited.
+    pub fn new(target: EditTarget, initial: &str) -> Self {
+        Self {
+            target,
+            lines: initial.lines().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    pub fn append(&mut self, line: &str) -> Result<(), String> {
+        if self.lines.len() >= MAX_LINES {
+            return Err(format!("The buffer is full ({} lines).", MAX_LINES));
+        }
+        self.lines.push(line.to_string());
+        Ok(())
+    }
+
+    /// Inserts a line before line `n` (1-based). `n` one past the end appends.
+    pub fn insert(&mut self, n: usize, line: &str) -> Result<(), String> {
+        if n == 0 || n > self.lines.len() + 1 {
+            return Err(format!("There is no line {}.", n));
+        }
+        if self.lines.len() >= MAX_LINES {
+            return Err(format!("The buffer is full ({} lines).", MAX_LINES));
+        }
+        self.lines.insert(n - 1, line.to_string());
+        Ok(())
+    }
+
+    /// Deletes line `n` (1-based).
+    pub fn delete(&mut self, n: usize) -> Result<String, String> {
+        if n == 0 || n > self.lines.len() {
+            return Err(format!("There is no line {}.", n));
+        }
+        Ok(self.lines.remove(n - 1))
+    }
+
+    /// Re-wraps each paragraph to `REFLOW_WIDTH`, keeping blank lines between paragraphs.
+    pub fn reflow(&mut self) {
+        let mut reflowed = Vec::new();
+        let mut current = String::new();
+
+        for line in &self.lines {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    reflowed.push(std::mem::take(&mut current));
+                }
+                reflowed.push(String::new());
+                continue;
+            }
+            for word in line.split_whitespace() {
+                if !current.is_empty() && current.len() + 1 + word.len() > REFLOW_WIDTH {
+                    reflowed.push(std::mem::take(&mut current));
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(word);
+            }
+        }
+        if !current.is_empty() {
+            reflowed.push(current);
+        }
+
+        self.lines = reflowed;
+    }
+
+    pub fn listing(&self) -> String {
+        if self.lines.is_empty() {
+            return "(empty)\n".to_string();
+        }
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, l)| format!("{:>3}: {}\n", i + 1, l))
+            .collect()
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
@@ -1,5 +1,6 @@
 // Game Object struct and methods
 
+use crate::models::RoomFlags;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -12,6 +13,7 @@ pub struct GameObject {
     pub container_id: Option<String>,
     pub properties: String,
     pub created_at: i64,
+    pub area_id: Option<i64>, // rooms only
 }
 
 impl GameObject {
@@ -30,6 +32,19 @@ impl GameObject {
             container_id,
             properties: "{}".to_string(),
             created_at: chrono::Utc::now().timestamp(),
+            area_id: None,
         }
     }
+
+    /// Flags and sector of a room.
+    pub fn room_flags(&self) -> RoomFlags {
+        RoomFlags::from_properties(&self.properties)
+    }
+
+    /// True if this object's properties set `key` to true, e.g. "light" for a torch.
+    pub fn has_property(&self, key: &str) -> bool {
+        serde_json::from_str::<serde_json::Value>(&self.properties)
+            .map(|v| v[key].as_bool() == Some(true))
+            .unwrap_or(false)
+    }
 }
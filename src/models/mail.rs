@@ -0,0 +1,29 @@
+// Board posts and player mail
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A message pinned to a board object.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BoardPost {
+    pub id: i64,
+    pub board_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub subject: String,
+    pub body: String,
+    pub posted_at: i64,
+}
+
+/// A letter in a player's mailbox.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Mail {
+    pub id: i64,
+    pub recipient_id: String,
+    pub sender_id: String,
+    pub sender_name: String,
+    pub subject: String,
+    pub body: String,
+    pub sent_at: i64,
+    pub is_read: i64, // use 0 or 1
+}
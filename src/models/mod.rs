@@ -4,8 +4,37 @@ pub mod player;
 pub mod game_object;
 pub mod session;
 pub mod dice_rolls;
+pub mod character;
+pub mod npc;
+pub mod reset;
+pub mod script;
+pub mod room_exit;
+pub mod editor;
+pub mod area;
+pub mod room_flags;
+pub mod channel;
+pub mod social;
+pub mod mail;
+pub mod connection;
+pub mod role;
+pub mod audit;
+pub mod sanction;
 
 pub use player::Player;
 pub use game_object::GameObject;
 pub use session::Session;
-pub use dice_rolls::random_distribution_roll_result;
+pub use character::{Attributes, CharacterDraft, Gender};
+pub use npc::{LootEntry, Npc, NpcPrototype, ShopItem};
+pub use reset::Reset;
+pub use script::Script;
+pub use room_exit::RoomExit;
+pub use editor::{EditTarget, LineEditor};
+pub use area::Area;
+pub use mail::{BoardPost, Mail};
+pub use connection::Connection;
+pub use role::{Role, AREA_COMMANDS, COMMAND_ROLES};
+pub use audit::AuditEntry;
+pub use sanction::{Sanction, SanctionKind};
+pub use social::{Social, DEFAULT_SOCIALS, SOCIAL_FIELDS};
+pub use channel::{Channel, CHANNELS, CHANNEL_HISTORY};
+pub use room_flags::{RoomFlags, Sector, PRIVATE_ROOM_LIMIT, ROOM_FLAGS};
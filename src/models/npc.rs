@@ -0,0 +1,133 @@
+// NPC prototype and instance structs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// Template an NPC is spawned from. Builders edit prototypes, never instances.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NpcPrototype {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub level: i64,
+
+    pub dexterity: i64,
+    pub strength: i64,
+    pub vitality: i64,
+    pub perception: i64,
+    pub willpower: i64,
+    pub charisma: i64,
+
+    pub max_health: i64,
+    pub physical_defense: i64,
+    pub physical_armor: i64,
+
+    pub loot_table: String, // JSON list of LootEntry
+    pub dialogue: String, // JSON map of topic -> response
+    pub behaviors: String, // JSON map of behavior name -> parameters
+    pub created_at: i64,
+}
+
+/// A live NPC in the world, joined with the prototype it was spawned from.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Npc {
+    pub id: String,
+    pub prototype_id: i64,
+    pub room_id: String,
+    pub current_health: i64,
+    pub home_room_id: Option<String>, // where it was spawned; wanderers stay near it
+    pub target_id: Option<String>, // player it is fighting or following
+
+    // copied from the prototype by the query join
+    pub name: String,
+    pub description: String,
+    pub level: i64,
+    pub dexterity: i64,
+    pub strength: i64,
+    pub max_health: i64,
+    pub physical_defense: i64,
+    pub physical_armor: i64,
+    pub loot_table: String,
+    pub dialogue: String,
+    pub behaviors: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LootEntry {
+    pub name: String,
+    pub description: String,
+    pub chance: u32, // percent, 1-100
+}
+
+/// Something a shopkeeper sells, listed in its "shopkeeper" behavior's `stock`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopItem {
+    pub name: String,
+    pub description: String,
+    pub price: i64,
+}
+
+impl NpcPrototype {
+    pub fn loot(&self) -> Vec<LootEntry> {
+        serde_json::from_str(&self.loot_table).unwrap_or_default()
+    }
+
+    pub fn dialogue_topics(&self) -> HashMap<String, String> {
+        serde_json::from_str(&self.dialogue).unwrap_or_default()
+    }
+
+    pub fn behavior_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        serde_json::from_str(&self.behaviors).unwrap_or_default()
+    }
+}
+
+impl Npc {
+    pub fn behavior_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        serde_json::from_str(&self.behaviors).unwrap_or_default()
+    }
+
+    pub fn has_behavior(&self, name: &str) -> bool {
+        self.behavior_map().contains_key(name)
+    }
+
+    pub fn shop_stock(&self) -> Option<Vec<ShopItem>> {
+        let behaviors = self.behavior_map();
+        let shop = behaviors.get("shopkeeper")?;
+        Some(
+            shop.get("stock")
+                .and_then(|s| serde_json::from_value(s.clone()).ok())
+                .unwrap_or_default(),
+        )
+    }
+
+    pub fn loot(&self) -> Vec<LootEntry> {
+        serde_json::from_str(&self.loot_table).unwrap_or_default()
+    }
+
+    pub fn dialogue_topics(&self) -> HashMap<String, String> {
+        serde_json::from_str(&self.dialogue).unwrap_or_default()
+    }
+
+    /// Matches a typed target against any word of the NPC's name, ignoring case.
+    pub fn matches(&self, target: &str) -> bool {
+        let target = target.to_lowercase();
+        self.name
+            .to_lowercase()
+            .split_whitespace()
+            .any(|word| word.starts_with(&target))
+            || self.name.to_lowercase() == target
+    }
+
+    /// A rough description of how hurt the NPC is.
+    pub fn condition(&self) -> &'static str {
+        let percent = self.current_health * 100 / self.max_health.max(1);
+        match percent {
+            100.. => "is in perfect health",
+            75..=99 => "has a few scratches",
+            50..=74 => "is wounded",
+            25..=49 => "is badly wounded",
+            _ => "is nearly dead",
+        }
+    }
+}
@@ -2,7 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use crate::models::dice_rolls;
+use crate::models::character::{CharacterDraft, Gender, Pronouns};
+use crate::models::Role;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Player {
@@ -10,7 +11,8 @@ pub struct Player {
     pub username: String,
     pub password_hash: String,
     pub current_location: String,
-    pub is_admin: i64, // use 0 or 1, SQLite doesn't have bools
+    pub role: String, // player, helper, builder, admin or owner; see `role()`
+    pub must_change_password: i64, // use 0 or 1; asked for a new password at next login
     pub created_at: i64,
 
     // Player attributes
@@ -33,25 +35,38 @@ pub struct Player {
     pub physical_armor: i64, // physical damage mitigation
     pub mystical_defense: i64, // how hard a player is to hit with mental/spell attacks
     pub mystical_armor: i64, // damage mitigation for non-physical attacks
+
+    // chosen during character creation
+    pub description: String,
+    pub gender: String, // male, female, neutral or object
+
+    pub level: i64,
+    pub gold: i64,
+
+    // shown by who and finger
+    pub title: String,
+    pub plan: String,
+    pub last_login: i64, // 0 until the first login
 }
 
 impl Player {
-    pub async fn new(id: String, username: String, password_hash: String, is_admin: i64) -> Self {
+    pub fn new(id: String, username: String, password_hash: String, role: Role, draft: &CharacterDraft) -> Self {
         Self {
             id,
             username,
             password_hash,
             current_location: "room_start".to_string(),
-            is_admin,
+            role: role.as_str().to_string(),
+            must_change_password: 0,
             created_at: chrono::Utc::now().timestamp(),
 
-            // rolled player attributes
-            dexterity: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
-            strength: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
-            vitality: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
-            perception: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
-            willpower: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
-            charisma: dice_rolls::random_distribution_roll_result(100.0, 10.0).await,
+            // attributes rolled or bought during character creation
+            dexterity: draft.attributes.dexterity,
+            strength: draft.attributes.strength,
+            vitality: draft.attributes.vitality,
+            perception: draft.attributes.perception,
+            willpower: draft.attributes.willpower,
+            charisma: draft.attributes.charisma,
 
             // derived player stats, setting defaults to start
             current_health: 500,
@@ -65,6 +80,28 @@ impl Player {
             mystical_defense: 100,
             mystical_armor: 0,
 
+            description: draft.description.clone(),
+            gender: draft.gender.as_str().to_string(),
+
+            level: 1,
+            gold: 100,
+
+            title: String::new(),
+            plan: String::new(),
+            last_login: 0,
         }
     }
+
+    pub fn pronouns(&self) -> Pronouns {
+        Gender::parse(&self.gender).unwrap_or(Gender::Neutral).pronouns()
+    }
+
+    pub fn role(&self) -> Role {
+        Role::parse(&self.role).unwrap_or(Role::Player)
+    }
+
+    /// Helpers and up: staff who see hidden room details and aren't hurt by the world.
+    pub fn is_staff(&self) -> bool {
+        self.role().is_staff()
+    }
 }
@@ -0,0 +1,31 @@
+// Area reset definitions
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One line of an area's reset list: keep up to `max_count` copies of an object or NPC in a place.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Reset {
+    pub id: i64,
+    pub area: String,
+    pub room_id: String,
+    pub container_id: Option<String>, // object inside the room to fill instead of the room itself
+    pub reset_type: String, // "object" or "npc"
+    pub npc_prototype_id: Option<i64>,
+
+    // snapshot of the object to recreate, unused for NPC resets
+    pub name: String,
+    pub description: String,
+    pub object_type: String,
+    pub properties: String,
+
+    pub max_count: i64,
+    pub created_at: i64,
+}
+
+impl Reset {
+    /// Where spawned objects go: the container if one is set, otherwise the room.
+    pub fn target_id(&self) -> &str {
+        self.container_id.as_deref().unwrap_or(&self.room_id)
+    }
+}
@@ -0,0 +1,125 @@
+// Staff roles and the commands each one can use
+
+/// Roles in rank order; each can do everything the ones below it can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Player,
+    Helper, // answers questions, keeps the boards and channels tidy
+    Builder, // builds in the areas they are listed on
+    Admin,
+    Owner, // runs the server; the only role that can make other owners
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [Role::Player, Role::Helper, Role::Builder, Role::Admin, Role::Owner];
+
+    pub fn parse(name: &str) -> Option<Role> {
+        Role::ALL.into_iter().find(|r| r.as_str().eq_ignore_ascii_case(name))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Player => "player",
+            Role::Helper => "helper",
+            Role::Builder => "builder",
+            Role::Admin => "admin",
+            Role::Owner => "owner",
+        }
+    }
+
+    /// Shown beside the name in who and finger.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Role::Player => "",
+            Role::Helper => "[Helper]",
+            Role::Builder => "[Builder]",
+            Role::Admin => "[Admin]",
+            Role::Owner => "[Owner]",
+        }
+    }
+
+    pub fn is_staff(&self) -> bool {
+        *self > Role::Player
+    }
+
+    pub fn next(&self) -> Option<Role> {
+        Role::ALL.into_iter().find(|r| r > self)
+    }
+
+    pub fn previous(&self) -> Option<Role> {
+        Role::ALL.into_iter().rev().find(|r| r < self)
+    }
+}
+
+/// The lowest role that can use each privileged command, unless changed with @perms.
+/// Anything not listed here is open to every player.
+pub const COMMAND_ROLES: &[(&str, Role)] = &[
+    // looking around without changing anything
+    ("@examine", Role::Helper),
+    ("@find", Role::Helper),
+    ("@path", Role::Helper),
+    ("@mlist", Role::Helper),
+    ("@alist", Role::Helper),
+    ("@astat", Role::Helper),
+    // keeping the peace
+    ("@bans", Role::Helper),
+    ("@boot", Role::Helper),
+    ("@silence", Role::Helper),
+    ("@freeze", Role::Helper),
+    // building, limited to areas the builder is listed on
+    ("@dig", Role::Builder),
+    ("@create", Role::Builder),
+    ("@desc", Role::Builder),
+    ("@name", Role::Builder),
+    ("@link", Role::Builder),
+    ("@unlink", Role::Builder),
+    ("@destroy", Role::Builder),
+    ("@oset", Role::Builder),
+    ("@clone", Role::Builder),
+    ("@mload", Role::Builder),
+    ("@reset", Role::Builder),
+    ("@door", Role::Builder),
+    ("@script", Role::Builder),
+    ("@rset", Role::Builder),
+    ("@landmark", Role::Builder),
+    ("@generate", Role::Builder),
+    ("@board", Role::Builder),
+    ("@aroom", Role::Builder),
+    ("@teleport", Role::Builder),
+    // running the game
+    ("@mcreate", Role::Admin), // NPC prototypes are shared by every area
+    ("@mset", Role::Admin),
+    ("@acreate", Role::Admin),
+    ("@aset", Role::Admin),
+    ("@social", Role::Admin),
+    ("@export", Role::Admin),
+    ("@import", Role::Admin),
+    ("last", Role::Admin),
+    ("@promote", Role::Admin),
+    ("@demote", Role::Admin),
+    ("@perms", Role::Admin),
+    ("@audit", Role::Admin),
+    ("@lockouts", Role::Admin),
+    ("@ban", Role::Admin),
+    ("@banplayer", Role::Admin),
+];
+
+/// Building commands that change the room the builder is standing in, so builders
+/// below admin can only use them inside their own areas.
+pub const AREA_COMMANDS: &[&str] = &[
+    "@dig", "@create", "@desc", "@name", "@link", "@unlink", "@destroy", "@oset", "@clone",
+    "@mload", "@reset", "@door", "@script", "@rset", "@landmark", "@generate", "@board", "@aroom",
+];
+
+/// Maps command aliases to the name permissions are kept under.
+pub fn canonical_command(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "@tel" => "@teleport".to_string(),
+        "@ex" => "@examine".to_string(),
+        other => other.to_string(),
+    }
+}
+
+pub fn default_command_role(command: &str) -> Option<Role> {
+    COMMAND_ROLES.iter().find(|(c, _)| *c == command).map(|(_, r)| *r)
+}
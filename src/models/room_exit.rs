@@ -0,0 +1,41 @@
+// Room exit struct and door state
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RoomExit {
+    pub room_id: String,
+    pub direction: String,
+    pub destination_id: String,
+
+    // door state, kept in sync with the exit leading back
+    pub door_name: Option<String>, // None means an open passage with no door
+    pub is_closed: i64, // use 0 or 1, SQLite doesn't have bools
+    pub is_locked: i64,
+    pub key_id: Option<String>, // object that locks and unlocks the door
+    pub pick_difficulty: i64, // 0 means the lock can't be picked
+    pub is_hidden: i64, // left out of the exit list, still usable
+}
+
+impl RoomExit {
+    pub fn has_door(&self) -> bool {
+        self.door_name.is_some()
+    }
+
+    /// True if something can walk through right now.
+    pub fn is_passable(&self) -> bool {
+        !self.has_door() || self.is_closed == 0
+    }
+
+    pub fn door(&self) -> &str {
+        self.door_name.as_deref().unwrap_or("door")
+    }
+
+    /// Matches a direction (abbreviations allowed) or the door's name, e.g. "open ne" or "open gate".
+    pub fn matches(&self, target: &str) -> bool {
+        let target = target.to_lowercase();
+        self.direction == crate::game::directions::normalize(&target)
+            || self.door_name.as_deref().map(|d| d.to_lowercase()) == Some(target)
+    }
+}
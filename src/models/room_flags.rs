@@ -0,0 +1,124 @@
+// Room flags and sector types, kept in a room's JSON properties
+
+use serde_json::Value;
+
+/// Flags a builder can set on a room.
+pub const ROOM_FLAGS: [&str; 6] = [
+    "dark",       // can't see without a light
+    "indoors",    // under a roof
+    "no-combat",  // no fighting
+    "no-magic",   // no recall or other magic
+    "private",    // at most PRIVATE_ROOM_LIMIT players
+    "death-trap", // entering kills
+];
+
+pub const PRIVATE_ROOM_LIMIT: usize = 2;
+
+/// The terrain of a room, which sets how much stamina it takes to walk into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sector {
+    Inside,
+    City,
+    Field,
+    Forest,
+    Hills,
+    Mountain,
+    Desert,
+    Water,      // needs a boat
+    Underwater, // needs something to breathe with
+}
+
+impl Sector {
+    pub const ALL: [Sector; 9] = [
+        Sector::Inside,
+        Sector::City,
+        Sector::Field,
+        Sector::Forest,
+        Sector::Hills,
+        Sector::Mountain,
+        Sector::Desert,
+        Sector::Water,
+        Sector::Underwater,
+    ];
+
+    pub fn parse(input: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str().eq_ignore_ascii_case(input))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sector::Inside => "inside",
+            Sector::City => "city",
+            Sector::Field => "field",
+            Sector::Forest => "forest",
+            Sector::Hills => "hills",
+            Sector::Mountain => "mountain",
+            Sector::Desert => "desert",
+            Sector::Water => "water",
+            Sector::Underwater => "underwater",
+        }
+    }
+
+    /// Stamina spent walking into a room of this sector.
+    pub fn move_cost(&self) -> i64 {
+        match self {
+            Sector::Inside | Sector::City => 1,
+            Sector::Field => 2,
+            Sector::Forest => 3,
+            Sector::Hills => 4,
+            Sector::Water => 4,
+            Sector::Desert => 5,
+            Sector::Mountain => 6,
+            Sector::Underwater => 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoomFlags {
+    pub flags: Vec<String>,
+    pub sector: Sector,
+}
+
+impl RoomFlags {
+    /// Reads flags and sector from a room's properties; rooms without them are plain city rooms.
+    pub fn from_properties(properties: &str) -> Self {
+        let value: Value = serde_json::from_str(properties).unwrap_or_default();
+        let flags = value["flags"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|f| f.as_str()).map(str::to_string).collect())
+            .unwrap_or_default();
+        let sector = value["sector"].as_str().and_then(Sector::parse).unwrap_or(Sector::City);
+        Self { flags, sector }
+    }
+
+    /// Writes flags and sector back into a room's properties, keeping everything else.
+    pub fn apply_to(&self, properties: &str) -> String {
+        let mut value: Value = serde_json::from_str(properties).unwrap_or_else(|_| serde_json::json!({}));
+        if !value.is_object() {
+            value = serde_json::json!({});
+        }
+        value["flags"] = serde_json::json!(self.flags);
+        value["sector"] = Value::String(self.sector.as_str().to_string());
+        value.to_string()
+    }
+
+    pub fn has(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn set(&mut self, flag: &str, on: bool) {
+        self.flags.retain(|f| f != flag);
+        if on {
+            self.flags.push(flag.to_string());
+        }
+    }
+
+    pub fn is_dark(&self) -> bool {
+        self.has("dark")
+    }
+
+    pub fn is_indoors(&self) -> bool {
+        self.has("indoors") || self.sector == Sector::Inside
+    }
+}
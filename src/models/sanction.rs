@@ -0,0 +1,138 @@
+// Site bans, player bans, freezes and silences
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanctionKind {
+    Site,    // no connections from an address or network
+    Player,  // the account can't log in
+    Freeze,  // can log in and look around, but not act
+    Silence, // can't talk on channels
+}
+
+impl SanctionKind {
+    pub const ALL: [SanctionKind; 4] = [SanctionKind::Site, SanctionKind::Player, SanctionKind::Freeze, SanctionKind::Silence];
+
+    pub fn parse(name: &str) -> Option<SanctionKind> {
+        SanctionKind::ALL.into_iter().find(|k| k.as_str().eq_ignore_ascii_case(name))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SanctionKind::Site => "site",
+            SanctionKind::Player => "player",
+            SanctionKind::Freeze => "freeze",
+            SanctionKind::Silence => "silence",
+        }
+    }
+
+    /// Shown in @bans.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SanctionKind::Site => "site ban",
+            SanctionKind::Player => "banned",
+            SanctionKind::Freeze => "frozen",
+            SanctionKind::Silence => "silenced",
+        }
+    }
+}
+
+/// A ban, freeze or silence, permanent or until `expires_at`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Sanction {
+    pub id: i64,
+    pub kind: String,
+    pub target: String, // an address or network for site bans, otherwise a player id
+    pub target_name: String, // the player's name, or the address again
+    pub reason: String,
+    pub issued_by: String,
+    pub created_at: i64,
+    pub expires_at: Option<i64>, // None for permanent
+}
+
+impl Sanction {
+    pub fn kind(&self) -> SanctionKind {
+        SanctionKind::parse(&self.kind).unwrap_or(SanctionKind::Player)
+    }
+}
+
+/// Reads "1.2.3.4", "1.2.3.0/24" or an IPv6 equivalent, returning the network address
+/// with the host bits cleared, and the prefix length.
+pub fn parse_network(pattern: &str) -> Option<(IpAddr, u8)> {
+    let (address, bits) = match pattern.split_once('/') {
+        Some((address, bits)) => (address, Some(bits.parse::<u8>().ok()?)),
+        None => (pattern, None),
+    };
+    match address.parse::<IpAddr>().ok()?.to_canonical() {
+        IpAddr::V4(v4) => {
+            let bits = bits.unwrap_or(32);
+            if bits > 32 {
+                return None;
+            }
+            let mask = u32::MAX.checked_shl(32 - bits as u32).unwrap_or(0);
+            Some((IpAddr::V4((u32::from(v4) & mask).into()), bits))
+        }
+        IpAddr::V6(v6) => {
+            let bits = bits.unwrap_or(128);
+            if bits > 128 {
+                return None;
+            }
+            let mask = u128::MAX.checked_shl(128 - bits as u32).unwrap_or(0);
+            Some((IpAddr::V6((u128::from(v6) & mask).into()), bits))
+        }
+    }
+}
+
+/// How a network is stored: a bare address for a single host, else "network/bits".
+pub fn format_network((address, bits): (IpAddr, u8)) -> String {
+    match (address, bits) {
+        (IpAddr::V4(_), 32) | (IpAddr::V6(_), 128) => address.to_string(),
+        _ => format!("{}/{}", address, bits),
+    }
+}
+
+/// True if the address falls inside a stored site ban pattern.
+pub fn network_contains(pattern: &str, address: &IpAddr) -> bool {
+    match parse_network(pattern) {
+        // masking the address to the pattern's length gives the pattern's network if it's inside
+        Some((network, bits)) => parse_network(&format!("{}/{}", address.to_canonical(), bits)) == Some((network, bits)),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(text: &str) -> IpAddr {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn networks_are_normalized() {
+        assert_eq!(parse_network("10.1.2.3/8").map(format_network), Some("10.0.0.0/8".to_string()));
+        assert_eq!(parse_network("10.1.2.3").map(format_network), Some("10.1.2.3".to_string()));
+        assert_eq!(parse_network("10.1.2.3/32").map(format_network), Some("10.1.2.3".to_string()));
+        assert_eq!(parse_network("2001:db8::1/32").map(format_network), Some("2001:db8::/32".to_string()));
+        assert_eq!(parse_network("0.0.0.0/0").map(format_network), Some("0.0.0.0/0".to_string()));
+        assert_eq!(parse_network("10.0.0.0/33"), None);
+        assert_eq!(parse_network("10.0.0/8"), None);
+        assert_eq!(parse_network("bob"), None);
+    }
+
+    #[test]
+    fn addresses_match_networks() {
+        assert!(network_contains("192.168.1.0/24", &ip("192.168.1.77")));
+        assert!(!network_contains("192.168.1.0/24", &ip("192.168.2.1")));
+        assert!(network_contains("127.0.0.2", &ip("127.0.0.2")));
+        assert!(!network_contains("127.0.0.2", &ip("127.0.0.1")));
+        assert!(network_contains("0.0.0.0/0", &ip("8.8.8.8")));
+        assert!(network_contains("2001:db8::/32", &ip("2001:db8:ffff::1")));
+        // IPv4 clients seen through an IPv6 socket still match IPv4 bans
+        assert!(network_contains("10.0.0.0/8", &ip("::ffff:10.9.8.7")));
+        assert!(!network_contains("10.0.0.0/8", &ip("2001:db8::1")));
+        assert!(!network_contains("not a network", &ip("10.0.0.1")));
+    }
+}
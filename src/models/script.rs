@@ -0,0 +1,16 @@
+// Event scripts attached to objects and NPC prototypes
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+pub const SCRIPT_EVENTS: [&str; 6] = ["on_enter", "on_look", "on_get", "on_say", "on_tick", "on_death"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Script {
+    pub id: i64,
+    pub owner_type: String, // "object" (rooms included) or "npc" (prototype id)
+    pub owner_id: String,
+    pub event: String,
+    pub code: String,
+    pub created_at: i64,
+}
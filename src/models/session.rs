@@ -1,19 +1,49 @@
 // Session struct
 
-use tokio::sync::mpsc;
+use crate::models::LineEditor;
+use std::collections::{HashSet, VecDeque};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, Notify};
 
 pub struct Session {
     pub player_id: String,
     pub username: String,
+    pub address: IpAddr,
     pub tx: mpsc::UnboundedSender<String>,
+    /// Notified to disconnect the player, after anything already sent to `tx`.
+    pub boot: Arc<Notify>,
+    /// Set while the player is composing text; their input goes to the editor instead of commands.
+    pub editor: Option<LineEditor>,
+    pub minimap: bool, // show a small map beside room descriptions
+    /// Steps still to walk from `walkto` or `run`, taken one per travel pulse.
+    pub travel: VecDeque<String>,
+    pub muted_channels: HashSet<String>, // silenced until logout, unlike leaving a channel
+    pub reply_to: Option<String>, // player id of whoever last sent a tell
+    pub afk: Option<String>, // away message, sent back to anyone who tells
+    pub pose: Option<String>, // shown after the player's name when others look
+    pub connected_at: Instant,
+    pub last_input: Instant, // for idle times in who
 }
 
 impl Session {
-    pub fn new(player_id: String, username: String, tx: mpsc::UnboundedSender<String>) -> Self {
+    pub fn new(player_id: String, username: String, address: IpAddr, tx: mpsc::UnboundedSender<String>) -> Self {
         Self {
             player_id,
             username,
+            address,
             tx,
+            boot: Arc::new(Notify::new()),
+            editor: None,
+            minimap: false,
+            travel: VecDeque::new(),
+            muted_channels: HashSet::new(),
+            reply_to: None,
+            afk: None,
+            pose: None,
+            connected_at: Instant::now(),
+            last_input: Instant::now(),
         }
     }
 }
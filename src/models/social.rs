@@ -0,0 +1,40 @@
+// Socials: canned actions like smile and bow, edited by builders
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Messages for one social. Empty messages are simply not shown. Messages can use
+/// `$n`/`$N` for the actor's and target's names and `$e`/`$m`/`$s` (`$E`/`$M`/`$S`) for the
+/// actor's (target's) subject, object and possessive pronouns.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Social {
+    pub name: String,
+    // used without a target
+    pub no_target_actor: String,
+    pub no_target_room: String,
+    // used with a target
+    pub actor: String,
+    pub target: String,
+    pub room: String,
+    pub created_at: i64,
+}
+
+/// Message fields a builder can set with @social.
+pub const SOCIAL_FIELDS: [&str; 5] = ["noarg_actor", "noarg_room", "actor", "target", "room"];
+
+/// Socials every new world starts with: name, no-target actor and room, then targeted
+/// actor, target and room messages.
+pub const DEFAULT_SOCIALS: &[[&str; 6]] = &[
+    ["smile", "You smile happily.", "$n smiles happily.", "You smile at $N.", "$n smiles at you.", "$n smiles at $N."],
+    ["grin", "You grin evilly.", "$n grins evilly.", "You grin at $N.", "$n grins at you.", "$n grins at $N."],
+    ["bow", "You bow deeply.", "$n bows deeply.", "You bow before $N.", "$n bows before you.", "$n bows before $N."],
+    ["wave", "You wave.", "$n waves.", "You wave at $N.", "$n waves at you.", "$n waves at $N."],
+    ["nod", "You nod.", "$n nods.", "You nod to $N.", "$n nods to you.", "$n nods to $N."],
+    ["shrug", "You shrug.", "$n shrugs $s shoulders.", "You shrug at $N.", "$n shrugs at you.", "$n shrugs at $N."],
+    ["sigh", "You sigh.", "$n sighs loudly.", "You sigh at $N.", "$n sighs at you.", "$n sighs at $N."],
+    ["laugh", "You fall down laughing.", "$n falls down laughing.", "You laugh at $N.", "$n laughs at you.", "$n laughs at $N."],
+    ["hug", "You hug yourself.", "$n hugs $mself.", "You hug $N.", "$n hugs you.", "$n hugs $N."],
+    ["comfort", "Comfort whom?", "", "You comfort $N.", "$n comforts you.", "$n comforts $N, patting $S back."],
+    ["poke", "Poke whom?", "", "You poke $N in the ribs.", "$n pokes you in the ribs.", "$n pokes $N in the ribs."],
+    ["thank", "Thank whom?", "", "You thank $N.", "$n thanks you.", "$n thanks $N."],
+];
@@ -1,14 +1,21 @@
 // Telnet server handling
 
-use crate::game::{GameState, process_command};
-use crate::models::Session;
+use crate::config::ServerConfig;
+use crate::database::{connection_queries, player_queries};
+use crate::game::{accounts, editor, mail, tells, validation, GameState, process_command};
+use crate::models::character::ATTRIBUTE_NAMES;
+use crate::models::{Attributes, CharacterDraft, Gender, Player, Role, Session};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub async fn handle_telnet_client(
     stream: TcpStream,
+    addr: SocketAddr,
     state: Arc<GameState>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let (reader, mut writer) = stream.into_split();
@@ -27,89 +34,70 @@ pub async fn handle_telnet_client(
         return Err(e.into());
     }
     
-    if let Err(e) = writer.write_all(b"Login (L) or Register (R)? ").await {
-        eprintln!("Failed to send prompt: {}", e);
-        return Err(e.into());
-    }
-    
-    if let Err(e) = writer.flush().await {
-        eprintln!("Failed to flush: {}", e);
-        return Err(e.into());
-    }
+    let player = loop {
+        let choice = prompt(&mut reader, &mut writer, "Login (L) or Register (R)? ").await?;
+        println!("Client chose: {}", choice);
 
-    let mut choice = String::new();
-    if let Err(e) = reader.read_line(&mut choice).await {
-        eprintln!("Failed to read choice: {}", e);
-        return Err(e.into());
-    }
-    
-    println!("Client chose: {}", choice.trim());
-
-    let player = match choice.trim().to_uppercase().as_str() {
-        "L" => {
-            writer.write_all(b"Username: ").await?;
-            writer.flush().await?;
-            let mut username = String::new();
-            reader.read_line(&mut username).await?;
-
-            writer.write_all(b"Password: ").await?;
-            writer.flush().await?;
-            let mut password = String::new();
-            reader.read_line(&mut password).await?;
-
-            match state.authenticate(username.trim(), password.trim()).await {
-                Ok(p) => p,
-                Err(e) => {
-                    writer.write_all(format!("Error: {}\n", e).as_bytes()).await?;
-                    return Ok(());
-                }
-            }
-        }
-        "R" => {
-            writer.write_all(b"Choose username: ").await?;
-            writer.flush().await?;
-            let mut username = String::new();
-            reader.read_line(&mut username).await?;
-
-            writer.write_all(b"Choose password: ").await?;
-            writer.flush().await?;
-            let mut password = String::new();
-            reader.read_line(&mut password).await?;
-
-            let is_admin = 0;
-
-            match state.register_player(username.trim(), password.trim(), is_admin).await {
-                Ok(p) => {
-                    writer.write_all(b"Registration successful!\n").await?;
-                    p
-                }
-                Err(e) => {
-                    writer.write_all(format!("Error: {}\n", e).as_bytes()).await?;
-                    return Ok(());
-                }
-            }
-        }
-        _ => {
-            writer.write_all(b"Invalid choice.\n").await?;
-            return Ok(());
+        match choice.to_uppercase().as_str() {
+            "L" => break login(&mut reader, &mut writer, &state, &addr).await?,
+            "R" => break register(&mut reader, &mut writer, &state).await?,
+            _ => writer.write_all(b"Invalid choice.\n").await?,
         }
     };
+    let player = match player {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+
+    if player.must_change_password != 0 {
+        writer.write_all(b"You must choose a new password before playing.\n").await?;
+        let password = loop {
+            let password = prompt(&mut reader, &mut writer, "New password: ").await?;
+            if let Err(e) = validation::check_password(&state.config, &player.username, &password) {
+                writer.write_all(format!("{}\n", e).as_bytes()).await?;
+                continue;
+            }
+            if accounts::verify_password(&player.password_hash, &password) {
+                writer.write_all(b"Choose a different password from the one you have now.\n").await?;
+                continue;
+            }
+            if prompt(&mut reader, &mut writer, "Repeat new password: ").await? != password {
+                writer.write_all(b"The passwords don't match.\n").await?;
+                continue;
+            }
+            break password;
+        };
+        accounts::set_password(&state, &player.id, &password).await?;
+        writer.write_all(b"Password changed.\n").await?;
+    }
 
     writer.write_all(format!("Welcome, {}!\n", player.username).as_bytes()).await?;
 
     let (tx, mut rx) = mpsc::unbounded_channel();
     let session_id = player.id.clone();
+    let session = Session::new(player.id.clone(), player.username.clone(), addr.ip(), tx);
+    let boot = session.boot.clone();
     
     {
         let mut sessions = state.sessions.write().await;
-        sessions.insert(
-            session_id.clone(),
-            Session::new(player.id.clone(), player.username.clone(), tx),
-        );
+        sessions.insert(session_id.clone(), session);
+    }
+    let connection_id = connection_queries::log_connection(&state.db, &player.id, &player.username, &addr.ip().to_string()).await;
+    if let Err(e) = player_queries::update_last_login(&state.db, &player.id, chrono::Utc::now().timestamp()).await {
+        eprintln!("Failed to record login for {}: {}", player.username, e);
     }
 
     let look_result = process_command(state.clone(), &player.id, "look").await;
     writer.write_all(look_result.as_bytes()).await?;
+    let tells = tells::deliver_offline_tells(&state, &player.id).await;
+    match tells {
+        Ok(text) => writer.write_all(text.as_bytes()).await?,
+        Err(e) => eprintln!("Failed to deliver tells to {}: {}", player.username, e),
+    }
+    match mail::login_notice(&state, &player.id).await {
+        Ok(text) => writer.write_all(text.as_bytes()).await?,
+        Err(e) => eprintln!("Failed to check mail for {}: {}", player.username, e),
+    }
     writer.flush().await?;
 
     let mut line = String::new();
@@ -119,7 +107,10 @@ pub async fn handle_telnet_client(
                 match result {
                     Ok(0) | Err(_) => break,
                     Ok(_) => {
-                        if line.trim() == "quit" {
+                        if let Some(session) = state.sessions.write().await.get_mut(&player.id) {
+                            session.last_input = Instant::now();
+                        }
+                        if line.trim() == "quit" && !editor::is_editing(&state, &player.id).await {
                             break;
                         }
                         
@@ -134,6 +125,14 @@ pub async fn handle_telnet_client(
                 writer.write_all(msg.as_bytes()).await?;
                 writer.flush().await?;
             }
+            _ = boot.notified() => {
+                // deliver whatever was queued first, including the reason
+                while let Ok(msg) = rx.try_recv() {
+                    writer.write_all(msg.as_bytes()).await?;
+                }
+                writer.flush().await?;
+                break;
+            }
         }
     }
 
@@ -141,6 +140,206 @@ pub async fn handle_telnet_client(
         let mut sessions = state.sessions.write().await;
         sessions.remove(&session_id);
     }
+    match connection_id {
+        Ok(id) => connection_queries::log_disconnection(&state.db, id).await?,
+        Err(e) => eprintln!("Failed to log connection for {}: {}", player.username, e),
+    }
 
     Ok(())
 }
+
+async fn prompt(
+    reader: &mut BufReader<OwnedReadHalf>,
+    writer: &mut OwnedWriteHalf,
+    text: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    writer.write_all(text.as_bytes()).await?;
+    writer.flush().await?;
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Err("Connection closed during prompt".into());
+    }
+    Ok(line.trim().to_string())
+}
+
+/// Asks for a username and password until they match, or the connection has used up its
+/// attempts. Returns None if it has.
+async fn login(
+    reader: &mut BufReader<OwnedReadHalf>,
+    writer: &mut OwnedWriteHalf,
+    state: &Arc<GameState>,
+    addr: &SocketAddr,
+) -> Result<Option<Player>, Box<dyn std::error::Error>> {
+    let mut attempts = 0;
+    loop {
+        let username = prompt(reader, writer, "Username: ").await?;
+        let password = prompt(reader, writer, "Password: ").await?;
+
+        match accounts::login(state, &addr.ip(), &username, &password).await {
+            Ok(p) => return Ok(Some(p)),
+            Err(e) => {
+                writer.write_all(format!("Error: {}\n", e).as_bytes()).await?;
+                attempts += 1;
+                if attempts >= state.config.max_login_attempts {
+                    writer.write_all(b"Too many failed attempts. Goodbye.\n").await?;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// Asks for a name and password until both are acceptable, then creates the character.
+/// Returns None if character creation is cancelled.
+async fn register(
+    reader: &mut BufReader<OwnedReadHalf>,
+    writer: &mut OwnedWriteHalf,
+    state: &Arc<GameState>,
+) -> Result<Option<Player>, Box<dyn std::error::Error>> {
+    let username = loop {
+        let username = prompt(reader, writer, "Choose username: ").await?;
+        match validation::check_username(state, &username).await {
+            Ok(()) => break username,
+            Err(e) => writer.write_all(format!("{}\n", e).as_bytes()).await?,
+        }
+    };
+
+    let password = loop {
+        let password = prompt(reader, writer, "Choose password: ").await?;
+        if let Err(e) = validation::check_password(&state.config, &username, &password) {
+            writer.write_all(format!("{}\n", e).as_bytes()).await?;
+            continue;
+        }
+        if prompt(reader, writer, "Repeat password: ").await? != password {
+            writer.write_all(b"The passwords don't match.\n").await?;
+            continue;
+        }
+        break password;
+    };
+
+    let draft = create_character(reader, writer, &state.config).await?;
+    let draft = match draft {
+        Some(draft) => draft,
+        None => {
+            writer.write_all(b"Character creation cancelled.\n").await?;
+            return Ok(None);
+        }
+    };
+
+    // checked again here in case someone took the name meanwhile
+    match state.register_player(&username, &password, Role::Player, &draft).await {
+        Ok(p) => {
+            writer.write_all(b"Registration successful!\n").await?;
+            Ok(Some(p))
+        }
+        Err(e) => {
+            writer.write_all(format!("Error: {}\n", e).as_bytes()).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Walks a new player through attributes, description and gender.
+/// Returns None if they decline the final confirmation; nothing is saved here.
+async fn create_character(
+    reader: &mut BufReader<OwnedReadHalf>,
+    writer: &mut OwnedWriteHalf,
+    config: &ServerConfig,
+) -> Result<Option<CharacterDraft>, Box<dyn std::error::Error>> {
+    let mut attributes = Attributes::roll().await;
+    let mut rerolls_left = config.max_attribute_rerolls;
+
+    loop {
+        writer.write_all(format!("\nYour attributes:\n{}", attributes.display()).as_bytes()).await?;
+        let choice = prompt(
+            reader,
+            writer,
+            &format!("(A)ccept, (R)eroll [{} left] or (P)oint-buy? ", rerolls_left),
+        ).await?;
+
+        match choice.to_uppercase().as_str() {
+            "A" => break,
+            "R" => {
+                if rerolls_left == 0 {
+                    writer.write_all(b"You have no rerolls left.\n").await?;
+                } else {
+                    rerolls_left -= 1;
+                    attributes = Attributes::roll().await;
+                }
+            }
+            "P" => {
+                writer.write_all(format!(
+                    "Spend exactly {} points, between {} and {} per attribute.\n",
+                    config.point_buy_pool, config.point_buy_min, config.point_buy_max
+                ).as_bytes()).await?;
+
+                let mut values = [0i64; 6];
+                let mut spent = 0;
+                for (i, name) in ATTRIBUTE_NAMES.iter().enumerate() {
+                    let input = prompt(
+                        reader,
+                        writer,
+                        &format!("{} ({} points left): ", name, config.point_buy_pool - spent),
+                    ).await?;
+                    values[i] = input.parse().unwrap_or(0);
+                    spent += values[i];
+                }
+
+                match Attributes::from_point_buy(
+                    values,
+                    config.point_buy_pool,
+                    config.point_buy_min,
+                    config.point_buy_max,
+                ) {
+                    Ok(bought) => attributes = bought,
+                    Err(e) => writer.write_all(format!("{}\n", e).as_bytes()).await?,
+                }
+            }
+            _ => writer.write_all(b"Invalid choice.\n").await?,
+        }
+    }
+
+    let description = loop {
+        let input = prompt(reader, writer, "Describe your character in a sentence or two: ").await?;
+        if input.is_empty() {
+            writer.write_all(b"Please enter a description.\n").await?;
+        } else if input.chars().count() > 240 {
+            writer.write_all(b"Please keep it under 240 characters.\n").await?;
+        } else {
+            break input;
+        }
+    };
+
+    let gender = loop {
+        let input = prompt(
+            reader,
+            writer,
+            "Gender: (M)ale [he/him], (F)emale [she/her], (N)eutral [they/them] or (O)bject [it/its]? ",
+        ).await?;
+        match Gender::parse(&input) {
+            Some(g) => break g,
+            None => writer.write_all(b"Invalid choice.\n").await?,
+        }
+    };
+
+    let pronouns = gender.pronouns();
+    writer.write_all(format!(
+        "\nAttributes:\n{}Description: {}\nPronouns: {}/{}/{}\n",
+        attributes.display(),
+        description,
+        pronouns.subject,
+        pronouns.object,
+        pronouns.possessive
+    ).as_bytes()).await?;
+
+    let confirm = prompt(reader, writer, "Create this character? (Y/N) ").await?;
+    if !confirm.eq_ignore_ascii_case("y") {
+        return Ok(None);
+    }
+
+    Ok(Some(CharacterDraft {
+        attributes,
+        description,
+        gender,
+    }))
+}
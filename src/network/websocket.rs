@@ -1,18 +1,26 @@
 // Websocket handler
 use crate::Arc;
 use crate::GameState;
+use crate::game::moderation;
 use axum::{
-    extract::{ws::WebSocket, WebSocketUpgrade, State},
-    response::IntoResponse,
+    extract::{ws::WebSocket, ConnectInfo, WebSocketUpgrade, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::get,
     Router,
 };
+use std::net::SocketAddr;
 
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     State(state): State<Arc<GameState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+) -> Response {
+    if let Some(ban) = moderation::site_ban(&state, &addr.ip()).await {
+        println!("Refused websocket from {} (banned as {})", addr, ban.target);
+        return (StatusCode::FORBIDDEN, "Connections from your address are banned.\n").into_response();
+    }
+    ws.on_upgrade(|socket| handle_websocket(socket, state)).into_response()
 }
 
 pub async fn handle_websocket(socket: WebSocket, state: Arc<GameState>) {